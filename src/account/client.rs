@@ -0,0 +1,545 @@
+use async_trait::async_trait;
+use reqwest::header::{COOKIE, SET_COOKIE, USER_AGENT};
+use reqwest::redirect::Policy;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use scraper::{ElementRef, Html, Selector};
+
+use crate::account::session::Session;
+use crate::api::AccountGateway;
+use crate::types::HnResult;
+use crate::utils::constants::HTTP_USER_AGENT;
+
+/// Name of the cookie Hacker News keeps the login session in
+const SESSION_COOKIE_NAME: &str = "user";
+/// Most characters of an error page quoted in error messages
+const MAX_ERROR_PAGE_CHARS: usize = 100;
+
+/// Actions taken by following a link on an item's page
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ItemLink {
+    Upvote,
+    Favorite,
+    Hide,
+    Flag,
+}
+
+impl ItemLink {
+    /// Path the action's link points to
+    fn path(self) -> &'static str {
+        return match self {
+            ItemLink::Upvote => "vote",
+            ItemLink::Favorite => "fave",
+            ItemLink::Hide => "hide",
+            ItemLink::Flag => "flag",
+        };
+    }
+
+    fn name(self) -> &'static str {
+        return match self {
+            ItemLink::Upvote => "upvote",
+            ItemLink::Favorite => "favorite",
+            ItemLink::Hide => "hide",
+            ItemLink::Flag => "flag",
+        };
+    }
+}
+
+/// Client for the logged-in actions of a Hacker News account
+///
+/// Every action first loads the item's page with the session cookie, then follows
+/// the action's link or submits the comment form. The links and the form carry
+/// per-session tokens, so they cannot be built without the page.
+pub struct AccountClient {
+    client: Client,
+    base_url: String,
+    session: Session,
+}
+
+#[async_trait]
+impl AccountGateway for AccountClient {
+    async fn upvote(&self, id: u64) -> HnResult<()> {
+        return self.follow_item_link(id, ItemLink::Upvote).await;
+    }
+
+    async fn favorite(&self, id: u64) -> HnResult<()> {
+        return self.follow_item_link(id, ItemLink::Favorite).await;
+    }
+
+    async fn hide(&self, id: u64) -> HnResult<()> {
+        return self.follow_item_link(id, ItemLink::Hide).await;
+    }
+
+    async fn flag(&self, id: u64) -> HnResult<()> {
+        return self.follow_item_link(id, ItemLink::Flag).await;
+    }
+
+    async fn reply(&self, parent: u64, text: &str) -> HnResult<()> {
+        let page: String = self.item_page(parent).await?;
+        let mut fields: Vec<(String, String)> = comment_form(&page, parent)?;
+        fields.push(("text".to_string(), text.to_string()));
+
+        let response: Response = self
+            .request(self.client.post(format!("{}/comment", self.base_url)))
+            .form(&fields)
+            .send()
+            .await?;
+
+        return accepted(response, "reply").await;
+    }
+}
+
+impl AccountClient {
+    /// Create a client for a logged-in session on a Hacker News site
+    pub fn new(base_url: &str, session: Session) -> HnResult<Self> {
+        return Ok(Self {
+            client: site_client()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+        });
+    }
+
+    /// Log in to a Hacker News site, returning the new session
+    pub async fn login(base_url: &str, username: &str, password: &str) -> HnResult<Session> {
+        let client: Client = site_client()?;
+        let url: String = format!("{}/login", base_url.trim_end_matches('/'));
+
+        // Submit the hidden fields of the login form along with the credentials, as
+        // the site may require its tokens
+        let page: String = client
+            .get(&url)
+            .header(USER_AGENT, HTTP_USER_AGENT)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let mut fields: Vec<(String, String)> =
+            hidden_form_fields(&Html::parse_document(&page), "login")
+                .ok_or("Login form not found")?;
+        fields.push(("acct".to_string(), username.to_string()));
+        fields.push(("pw".to_string(), password.to_string()));
+
+        let response: Response = client
+            .post(&url)
+            .header(USER_AGENT, HTTP_USER_AGENT)
+            .form(&fields)
+            .send()
+            .await?;
+
+        let cookie: String =
+            session_cookie(&response).ok_or("Login failed, check the username and password")?;
+
+        return Ok(Session {
+            username: username.to_string(),
+            cookie,
+        });
+    }
+
+    /// Add the user agent and session cookie to a request
+    fn request(&self, builder: RequestBuilder) -> RequestBuilder {
+        return builder
+            .header(USER_AGENT, HTTP_USER_AGENT)
+            .header(COOKIE, &self.session.cookie);
+    }
+
+    /// Fetch an item's page as the logged-in user
+    async fn item_page(&self, id: u64) -> HnResult<String> {
+        let page: String = self
+            .request(self.client.get(format!("{}/item?id={id}", self.base_url)))
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        return Ok(page);
+    }
+
+    /// Follow an action's link from an item's page
+    async fn follow_item_link(&self, id: u64, link: ItemLink) -> HnResult<()> {
+        let page: String = self.item_page(id).await?;
+        let href: String = item_link(&page, id, link)?;
+
+        let response: Response = self
+            .request(self.client.get(format!("{}/{href}", self.base_url)))
+            .send()
+            .await?;
+
+        return accepted(response, link.name()).await;
+    }
+}
+
+/// HTTP client for the Hacker News site
+///
+/// Redirects are not followed, as the site answers accepted forms with a redirect
+/// and sets the session cookie on it.
+fn site_client() -> HnResult<Client> {
+    return Ok(Client::builder().redirect(Policy::none()).build()?);
+}
+
+/// Get the session cookie (e.g. `user=alice&token`) set by a response
+fn session_cookie(response: &Response) -> Option<String> {
+    return response
+        .headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| value.split(';').next())
+        .find(|cookie| {
+            cookie
+                .split_once('=')
+                .is_some_and(|(name, value)| name == SESSION_COOKIE_NAME && !value.is_empty())
+        })
+        .map(str::to_string);
+}
+
+/// Check that the site accepted an action
+///
+/// Accepted actions redirect back to a page, while rejected ones (e.g. expired tokens
+/// or posting too fast) render a short error page, which is quoted in the error.
+async fn accepted(response: Response, action: &str) -> HnResult<()> {
+    if response.status().is_redirection() {
+        return Ok(());
+    }
+
+    let status: StatusCode = response.status();
+    let page: String = response.text().await?;
+    let text: String = Html::parse_document(&page)
+        .root_element()
+        .text()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    let message: String = if text.is_empty() {
+        status.to_string()
+    } else {
+        text.chars().take(MAX_ERROR_PAGE_CHARS).collect()
+    };
+
+    return Err(format!("Hacker News did not accept the {action}: {message}").into());
+}
+
+/// Fail unless a page was rendered for a logged-in user
+fn require_login(document: &Html) -> HnResult<()> {
+    let logout: Selector = Selector::parse("a#logout").unwrap();
+    if document.select(&logout).next().is_none() {
+        return Err(
+            "Not logged in, the session may have expired. Log in again with `hn-stories login`"
+                .into(),
+        );
+    }
+
+    return Ok(());
+}
+
+/// Find the link of an action on an item's page, relative to the site root
+fn item_link(page: &str, id: u64, link: ItemLink) -> HnResult<String> {
+    let document: Html = Html::parse_document(page);
+    require_login(&document)?;
+
+    if link == ItemLink::Upvote {
+        // The arrow stays on the page after voting, but is hidden
+        let arrow: Selector = Selector::parse(&format!("a#up_{id}")).unwrap();
+        return match document.select(&arrow).next() {
+            Some(element) if element.value().classes().any(|class| class == "nosee") => {
+                Err(format!("Item {id} is already upvoted").into())
+            }
+            Some(element) => element
+                .value()
+                .attr("href")
+                .map(str::to_string)
+                .ok_or_else(|| format!("Unable to upvote item {id}").into()),
+            None => Err(format!("Item {id} can not be upvoted").into()),
+        };
+    }
+
+    // Links that undo an action (`un=t`) mean it was already taken
+    let prefix: String = format!("{}?id={id}&", link.path());
+    let anchors: Selector = Selector::parse("a[href]").unwrap();
+    let href: Option<&str> = document
+        .select(&anchors)
+        .filter_map(|element: ElementRef| element.value().attr("href"))
+        .find(|href| href.starts_with(&prefix) && !href.contains("un=t"));
+
+    return href.map(str::to_string).ok_or_else(|| {
+        format!(
+            "Unable to {} item {id}, it may already be done",
+            link.name()
+        )
+        .into()
+    });
+}
+
+/// Get the hidden fields of the reply form on an item's page
+fn comment_form(page: &str, id: u64) -> HnResult<Vec<(String, String)>> {
+    let document: Html = Html::parse_document(page);
+    require_login(&document)?;
+
+    return hidden_form_fields(&document, "comment")
+        .ok_or_else(|| format!("Item {id} can not be replied to").into());
+}
+
+/// Get the names and values of the hidden inputs of the form posting to `action`
+fn hidden_form_fields(document: &Html, action: &str) -> Option<Vec<(String, String)>> {
+    let form_selector: Selector = Selector::parse(&format!(r#"form[action="{action}"]"#)).unwrap();
+    let hidden: Selector = Selector::parse(r#"input[type="hidden"][name]"#).unwrap();
+
+    let form: ElementRef = document.select(&form_selector).next()?;
+    let fields: Vec<(String, String)> = form
+        .select(&hidden)
+        .filter_map(|input| {
+            let name: &str = input.value().attr("name")?;
+            let value: &str = input.value().attr("value").unwrap_or_default();
+            return Some((name.to_string(), value.to_string()));
+        })
+        .collect();
+
+    return Some(fields);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use http_body_util::Full;
+    use hyper::body::Bytes;
+    use hyper::{Method, Request, Response as SiteResponse, StatusCode};
+    use reqwest::Url;
+
+    use crate::utils::test_server;
+
+    /// Token the stand-in site puts in its login form
+    const LOGIN_TOKEN: &str = "login-token";
+    /// Session cookie the stand-in site issues to alice
+    const ALICE_COOKIE: &str = "user=alice&s3cret";
+    /// Token the stand-in site puts in its action links
+    const AUTH_TOKEN: &str = "auth-token";
+    /// Token the stand-in site puts in its reply forms
+    const HMAC_TOKEN: &str = "hmac-token";
+
+    /// Page of an item as the stand-in site renders it
+    fn item_page(id: &str, logged_in: bool) -> String {
+        if !logged_in {
+            return format!(
+                r#"<html><body><a href="login?goto=item%3Fid%3D{id}">login</a>
+                <a id="up_{id}" href="vote?id={id}&amp;how=up&amp;goto=item%3Fid%3D{id}">up</a>
+                </body></html>"#
+            );
+        }
+
+        return format!(
+            r#"<html><body>
+            <a id="me" href="user?id=alice">alice</a> | <a id="logout" href="logout?auth=x&amp;goto=news">logout</a>
+            <a id="up_{id}" class="clicky" href="vote?id={id}&amp;how=up&amp;auth={AUTH_TOKEN}&amp;goto=item%3Fid%3D{id}">up</a>
+            <a href="flag?id={id}&amp;auth={AUTH_TOKEN}&amp;goto=item%3Fid%3D{id}">flag</a> |
+            <a href="hide?id={id}&amp;goto=item%3Fid%3D{id}&amp;auth={AUTH_TOKEN}">hide</a> |
+            <a href="fave?id={id}&amp;auth={AUTH_TOKEN}">favorite</a>
+            <form action="comment" method="post">
+              <input type="hidden" name="parent" value="{id}">
+              <input type="hidden" name="goto" value="item?id={id}">
+              <input type="hidden" name="hmac" value="{HMAC_TOKEN}">
+              <textarea name="text"></textarea><input type="submit" value="add comment">
+            </form></body></html>"#
+        );
+    }
+
+    fn html(status: StatusCode, body: String) -> SiteResponse<Full<Bytes>> {
+        let mut response: SiteResponse<Full<Bytes>> =
+            SiteResponse::new(Full::new(Bytes::from(body)));
+        *response.status_mut() = status;
+        return response;
+    }
+
+    fn redirect(location: &str) -> SiteResponse<Full<Bytes>> {
+        return SiteResponse::builder()
+            .status(StatusCode::FOUND)
+            .header("location", location)
+            .body(Full::new(Bytes::new()))
+            .unwrap();
+    }
+
+    /// Answer a request like the Hacker News site, recording accepted actions
+    fn handle(request: Request<Bytes>, actions: &Mutex<Vec<String>>) -> SiteResponse<Full<Bytes>> {
+        let method: Method = request.method().clone();
+        let path: String = request.uri().path().to_string();
+        let query: String = request.uri().query().unwrap_or_default().to_string();
+        let logged_in: bool = request
+            .headers()
+            .get("cookie")
+            .is_some_and(|cookie| cookie == ALICE_COOKIE);
+
+        // Query and form parameters together
+        let url: Url = Url::parse(&format!(
+            "http://site/?{query}&{}",
+            String::from_utf8_lossy(request.body())
+        ))
+        .unwrap();
+        let parameters: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        let parameter = |name: &str| parameters.get(name).cloned().unwrap_or_default();
+        let expired: SiteResponse<Full<Bytes>> =
+            html(StatusCode::OK, "Unknown or expired link.".to_string());
+
+        return match (method, path.as_str()) {
+            (Method::GET, "/login") => html(
+                StatusCode::OK,
+                format!(
+                    r#"<form action="login" method="post">
+                    <input type="hidden" name="goto" value="news">
+                    <input type="hidden" name="fnid" value="{LOGIN_TOKEN}">
+                    <input type="text" name="acct"><input type="password" name="pw">
+                    </form>"#
+                ),
+            ),
+            (Method::POST, "/login") => {
+                if parameter("fnid") == LOGIN_TOKEN
+                    && parameter("acct") == "alice"
+                    && parameter("pw") == "hunter2"
+                {
+                    let mut response: SiteResponse<Full<Bytes>> = redirect(&parameter("goto"));
+                    let headers = response.headers_mut();
+                    headers.append("set-cookie", "theme=dark; Path=/".parse().unwrap());
+                    headers.append(
+                        "set-cookie",
+                        format!("{ALICE_COOKIE}; Path=/; HttpOnly").parse().unwrap(),
+                    );
+                    response
+                } else {
+                    html(StatusCode::OK, "Bad login.".to_string())
+                }
+            }
+            (Method::GET, "/item") => html(StatusCode::OK, item_page(&parameter("id"), logged_in)),
+            (Method::GET, "/vote" | "/fave" | "/hide" | "/flag") => {
+                if logged_in && parameter("auth") == AUTH_TOKEN {
+                    actions
+                        .lock()
+                        .unwrap()
+                        .push(format!("{} {}", &path[1..], parameter("id")));
+                    redirect(&parameter("goto"))
+                } else {
+                    expired
+                }
+            }
+            (Method::POST, "/comment") if parameter("text").is_empty() => html(
+                StatusCode::OK,
+                "<p>Please don't submit blank comments.</p>".to_string(),
+            ),
+            (Method::POST, "/comment") => {
+                if logged_in && parameter("hmac") == HMAC_TOKEN {
+                    actions.lock().unwrap().push(format!(
+                        "comment {} {}",
+                        parameter("parent"),
+                        parameter("text")
+                    ));
+                    redirect(&parameter("goto"))
+                } else {
+                    expired
+                }
+            }
+            _ => html(StatusCode::NOT_FOUND, "Unknown.".to_string()),
+        };
+    }
+
+    /// Start a stand-in for the Hacker News site, returning its base URL
+    async fn spawn_site(actions: Arc<Mutex<Vec<String>>>) -> String {
+        return test_server::spawn(move |request| handle(request, &actions)).await;
+    }
+
+    #[tokio::test]
+    async fn test_login() {
+        let base_url: String = spawn_site(Arc::default()).await;
+
+        let session: Session = AccountClient::login(&base_url, "alice", "hunter2")
+            .await
+            .unwrap();
+        assert_eq!(
+            session,
+            Session {
+                username: "alice".to_string(),
+                cookie: ALICE_COOKIE.to_string()
+            }
+        );
+
+        let error: String = AccountClient::login(&base_url, "alice", "wrong")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Login failed"));
+    }
+
+    #[tokio::test]
+    async fn test_item_actions() {
+        let actions: Arc<Mutex<Vec<String>>> = Arc::default();
+        let base_url: String = spawn_site(Arc::clone(&actions)).await;
+        let session: Session = AccountClient::login(&base_url, "alice", "hunter2")
+            .await
+            .unwrap();
+        let client: AccountClient = AccountClient::new(&base_url, session).unwrap();
+
+        client.upvote(42).await.unwrap();
+        client.favorite(42).await.unwrap();
+        client.hide(42).await.unwrap();
+        client.flag(7).await.unwrap();
+        client.reply(42, "Nice work!").await.unwrap();
+
+        // Rejected forms quote the site's error page
+        let error: String = client.reply(42, "").await.unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "Hacker News did not accept the reply: Please don't submit blank comments."
+        );
+
+        assert_eq!(
+            *actions.lock().unwrap(),
+            vec![
+                "vote 42",
+                "fave 42",
+                "hide 42",
+                "flag 7",
+                "comment 42 Nice work!"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_item_actions_with_expired_session() {
+        let actions: Arc<Mutex<Vec<String>>> = Arc::default();
+        let base_url: String = spawn_site(Arc::clone(&actions)).await;
+        let session: Session = Session {
+            username: "alice".to_string(),
+            cookie: "user=alice&expired".to_string(),
+        };
+        let client: AccountClient = AccountClient::new(&base_url, session).unwrap();
+
+        let error: String = client.upvote(42).await.unwrap_err().to_string();
+        assert!(error.contains("Log in again"), "{error}");
+
+        let error: String = client.reply(42, "Hello").await.unwrap_err().to_string();
+        assert!(error.contains("Log in again"), "{error}");
+
+        assert!(actions.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_item_link_already_taken() {
+        let page: &str = r#"<a id="logout" href="logout">logout</a>
+            <a id="up_1" class="clicky nosee" href="vote?id=1&amp;how=up&amp;auth=x">up</a>
+            <a href="fave?id=1&amp;un=t&amp;auth=x">un-favorite</a>
+            <a href="hide?id=1&amp;auth=x">hide</a>"#;
+
+        assert!(
+            item_link(page, 1, ItemLink::Upvote)
+                .unwrap_err()
+                .to_string()
+                .contains("already upvoted")
+        );
+        assert!(item_link(page, 1, ItemLink::Favorite).is_err());
+        assert_eq!(
+            item_link(page, 1, ItemLink::Hide).unwrap(),
+            "hide?id=1&auth=x"
+        );
+    }
+}
@@ -0,0 +1,130 @@
+pub mod client;
+pub mod session;
+
+use std::process::{Output, Stdio};
+
+use serde::Deserialize;
+use tokio::process::Command;
+
+use crate::account::client::AccountClient;
+use crate::account::session::{Session, SessionStore};
+use crate::types::HnResult;
+use crate::utils::constants::Y_COMBINATOR_BASE_URL;
+
+/// Account settings from the `[account]` section of the config file
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AccountConfig {
+    pub username: Option<String>,
+    /// Plain text password, prefer `password_command` to keep it out of the file
+    pub password: Option<String>,
+    /// Command printing the password, e.g. `pass show news.ycombinator.com`
+    pub password_command: Option<String>,
+}
+
+impl AccountConfig {
+    /// Get the configured password, running `password_command` when it is set
+    ///
+    /// Returns `None` when no password is configured.
+    pub async fn password(&self) -> HnResult<Option<String>> {
+        let Some(command) = &self.password_command else {
+            return Ok(self.password.clone());
+        };
+
+        let arguments: Vec<String> = shlex::split(command)
+            .filter(|arguments| !arguments.is_empty())
+            .ok_or_else(|| format!("Invalid password command: {command}"))?;
+        let output: Output = Command::new(&arguments[0])
+            .args(&arguments[1..])
+            .stderr(Stdio::inherit())
+            .output()
+            .await
+            .map_err(|error| format!("Failed to run '{}': {error}", arguments[0]))?;
+
+        if !output.status.success() {
+            return Err(format!("'{}' exited with {}", arguments[0], output.status).into());
+        }
+
+        // Password managers print the secret on the first line
+        let stdout: String = String::from_utf8(output.stdout)?;
+        return Ok(Some(stdout.lines().next().unwrap_or_default().to_string()));
+    }
+
+    /// Get the configured password when logging in as the configured username, as it
+    /// belongs to that account only
+    pub async fn password_for(&self, username: &str) -> HnResult<Option<String>> {
+        if self.username.as_deref() != Some(username) {
+            return Ok(None);
+        }
+
+        return self.password().await;
+    }
+}
+
+/// Get a client for the logged-in account, or `None` when logged out
+pub fn logged_in_client() -> HnResult<Option<AccountClient>> {
+    let session: Option<Session> = SessionStore::open_default()?.load()?;
+
+    return session
+        .map(|session| AccountClient::new(Y_COMBINATOR_BASE_URL, session))
+        .transpose();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_password_from_config() {
+        let config: AccountConfig = AccountConfig {
+            password: Some("hunter2".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(config.password().await.unwrap().as_deref(), Some("hunter2"));
+        assert_eq!(AccountConfig::default().password().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_password_for_configured_username_only() {
+        let config: AccountConfig = AccountConfig {
+            username: Some("alice".to_string()),
+            password: Some("hunter2".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.password_for("alice").await.unwrap().as_deref(),
+            Some("hunter2")
+        );
+        assert_eq!(config.password_for("bob").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_password_from_command() {
+        // The command wins over a plain password
+        let config: AccountConfig = AccountConfig {
+            password: Some("stale".to_string()),
+            password_command: Some("printf 'from command\\nmetadata'".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.password().await.unwrap().as_deref(),
+            Some("from command")
+        );
+
+        let failing: AccountConfig = AccountConfig {
+            password_command: Some("false".to_string()),
+            ..Default::default()
+        };
+        assert!(
+            failing
+                .password()
+                .await
+                .unwrap_err()
+                .to_string()
+                .contains("exited")
+        );
+    }
+}
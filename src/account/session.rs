@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::HnResult;
+use crate::utils::json_store::JsonFile;
+use crate::utils::paths::data_dir;
+
+/// File name of the login session within the data directory
+const SESSION_FILE_NAME: &str = "session.json";
+
+/// A logged-in Hacker News account
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Session {
+    pub username: String,
+    /// `user` cookie issued at login (e.g. `user=alice&token`), sent with every request
+    pub cookie: String,
+}
+
+/// The login session, in a `session.json` only the current user can read
+pub struct SessionStore {
+    file: JsonFile<Session>,
+}
+
+impl SessionStore {
+    pub fn new(path: PathBuf) -> Self {
+        return Self {
+            file: JsonFile::new(path, "session").private(),
+        };
+    }
+
+    /// Open the session in the data directory
+    pub fn open_default() -> HnResult<Self> {
+        return Ok(Self::new(data_dir()?.join(SESSION_FILE_NAME)));
+    }
+
+    /// Load the session, or `None` when logged out
+    pub fn load(&self) -> HnResult<Option<Session>> {
+        return self.file.load();
+    }
+
+    /// Save the session, readable only by the current user
+    pub fn save(&self, session: &Session) -> HnResult<()> {
+        return self.file.save(session);
+    }
+
+    /// Remove the session
+    ///
+    /// Returns whether there was a session to remove.
+    pub fn clear(&self) -> HnResult<bool> {
+        return self.file.remove();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_session_store_round_trip() {
+        let directory = tempfile::tempdir().unwrap();
+        let store: SessionStore = SessionStore::new(directory.path().join("session.json"));
+        let session: Session = Session {
+            username: "alice".to_string(),
+            cookie: "user=alice&token".to_string(),
+        };
+
+        assert_eq!(store.load().unwrap(), None);
+
+        store.save(&session).unwrap();
+        assert_eq!(store.load().unwrap(), Some(session));
+
+        assert!(store.clear().unwrap());
+        assert!(!store.clear().unwrap());
+        assert_eq!(store.load().unwrap(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_session_store_saves_private_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let directory = tempfile::tempdir().unwrap();
+        let path: PathBuf = directory.path().join("session.json");
+        let store: SessionStore = SessionStore::new(path.clone());
+
+        store
+            .save(&Session {
+                username: "alice".to_string(),
+                cookie: "user=alice&token".to_string(),
+            })
+            .unwrap();
+
+        let mode: u32 = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
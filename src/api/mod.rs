@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 
 use crate::models::story::{HackerNewsStoryType, StoryItem};
+use crate::models::user::HackerNewsUser;
 use crate::types::HnResult;
 
 /// A gateway to the Hacker News API
@@ -19,4 +20,59 @@ pub trait HackerNewsGateway: Send + Sync {
         story_type: &HackerNewsStoryType,
         limit: u16,
     ) -> HnResult<Vec<StoryItem>>;
+
+    /// Fetch a merged, de-duplicated feed of several story types, with each story
+    /// tagged with its rank in every list it appears in
+    async fn fetch_feed(
+        &self,
+        story_types: &[HackerNewsStoryType],
+        limit: u16,
+    ) -> HnResult<Vec<StoryItem>>;
+
+    /// Fetch a single item by ID, or `None` if it does not exist
+    async fn fetch_item(&self, id: u64) -> HnResult<Option<StoryItem>>;
+
+    /// Fetch the ID of the newest item
+    async fn fetch_max_item_id(&self) -> HnResult<u64>;
+
+    /// Fetch items by ID with at most `concurrency` requests at once, in the order of
+    /// `ids` and skipping IDs without an item, failing if any request fails
+    async fn fetch_items(&self, ids: &[u64], concurrency: usize) -> HnResult<Vec<StoryItem>>;
+
+    /// Fetch a user profile by username, or `None` if it does not exist
+    async fn fetch_user(&self, id: &str) -> HnResult<Option<HackerNewsUser>>;
+
+    /// Search for the most recent stories from a site (e.g. `example.com`,
+    /// `github.com/rust-lang`), newest first
+    async fn fetch_domain_stories(&self, site: &str, limit: u16) -> HnResult<Vec<StoryItem>>;
+
+    /// Search for previous submissions of a link, given in the form returned by
+    /// [`normalize_url`](crate::models::domain::normalize_url), newest first
+    async fn fetch_url_submissions(&self, url: &str) -> HnResult<Vec<StoryItem>>;
+
+    /// Fetch the raw HTML of a story's linked web page
+    async fn fetch_article(&self, url: &str) -> HnResult<String>;
+}
+
+/// A gateway to the logged-in actions of a Hacker News account
+///
+/// The Firebase API is read-only, so implementations drive the forms on the Hacker
+/// News site with a session cookie.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AccountGateway: Send + Sync {
+    /// Upvote a story or comment
+    async fn upvote(&self, id: u64) -> HnResult<()>;
+
+    /// Add a story or comment to the account's favorites
+    async fn favorite(&self, id: u64) -> HnResult<()>;
+
+    /// Hide a story from the account's story lists
+    async fn hide(&self, id: u64) -> HnResult<()>;
+
+    /// Flag a story or comment
+    async fn flag(&self, id: u64) -> HnResult<()>;
+
+    /// Post a comment in reply to a story or comment
+    async fn reply(&self, parent: u64, text: &str) -> HnResult<()>;
 }
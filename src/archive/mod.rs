@@ -0,0 +1,367 @@
+pub mod query;
+pub mod recorder;
+pub mod store;
+
+use clap::ValueEnum;
+
+use crate::api::HackerNewsGateway;
+use crate::archive::store::{ArchiveRange, ArchiveStore};
+use crate::models::story::StoryItem;
+use crate::types::HnResult;
+
+/// Order in which item IDs are crawled
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum CrawlDirection {
+    /// From the newest item down to the first one
+    Backward,
+    /// From the last archived item up to the newest one
+    Forward,
+}
+
+/// Settings of a crawl
+#[derive(Debug, Clone, Copy)]
+pub struct CrawlOptions {
+    pub direction: CrawlDirection,
+    /// Most item IDs to walk in this run, or `None` to walk until the end
+    pub limit: Option<u64>,
+    /// Most requests in flight at once, which is also the size of each saved batch
+    pub concurrency: usize,
+}
+
+/// Progress of a crawl
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CrawlSummary {
+    /// Item IDs walked in this run
+    pub walked: u64,
+    /// Items stored in this run (IDs without an item are walked but not stored)
+    pub archived: u64,
+    /// ID of the newest item when the crawl started
+    pub max_item_id: u64,
+    /// Range of item IDs crawled so far, across runs
+    pub range: Option<ArchiveRange>,
+}
+
+/// Walk item IDs from the last checkpoint and store every item in the archive
+///
+/// Items are fetched and saved in batches, each with its checkpoint, so a crawl that
+/// fails or is interrupted resumes after the last saved batch. `on_batch` is called
+/// after every batch.
+pub async fn crawl<G, F>(
+    gateway: &G,
+    store: &mut ArchiveStore,
+    options: CrawlOptions,
+    mut on_batch: F,
+) -> HnResult<CrawlSummary>
+where
+    G: HackerNewsGateway,
+    F: FnMut(&CrawlSummary),
+{
+    let mut summary: CrawlSummary = CrawlSummary {
+        max_item_id: gateway.fetch_max_item_id().await?,
+        range: store.range()?,
+        ..Default::default()
+    };
+
+    loop {
+        let remaining: u64 = options
+            .limit
+            .map_or(u64::MAX, |limit| limit.saturating_sub(summary.walked));
+        let size: usize = usize::try_from(remaining).map_or(options.concurrency, |remaining| {
+            remaining.min(options.concurrency)
+        });
+        let ids: Vec<u64> = next_batch(summary.range, summary.max_item_id, options.direction, size);
+        if ids.is_empty() {
+            break;
+        }
+
+        let items: Vec<StoryItem> = gateway.fetch_items(&ids, options.concurrency).await?;
+        summary.range = ArchiveRange::extend(summary.range, &ids);
+        if let Some(range) = summary.range {
+            store.save_batch(&items, range)?;
+        }
+
+        summary.walked += ids.len() as u64;
+        summary.archived += items.len() as u64;
+        on_batch(&summary);
+    }
+
+    return Ok(summary);
+}
+
+/// IDs of the next batch to crawl, bordering the range crawled so far
+///
+/// Backward crawls continue below the range (starting from the newest item), and
+/// forward crawls above it (starting from the first item).
+fn next_batch(
+    range: Option<ArchiveRange>,
+    max_item_id: u64,
+    direction: CrawlDirection,
+    size: usize,
+) -> Vec<u64> {
+    return match direction {
+        CrawlDirection::Backward => {
+            let start: u64 = range.map_or(max_item_id, |range| range.low.saturating_sub(1));
+            (1..=start).rev().take(size).collect()
+        }
+        CrawlDirection::Forward => {
+            let start: u64 = range.map_or(1, |range| range.high + 1);
+            (start..=max_item_id).take(size).collect()
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::store::tests::archived_item;
+    use crate::client::HackerNewsClient;
+    use crate::utils::rate_limit::{RateLimitConfig, RateLimiter, ThrottleStats};
+    use crate::utils::test_server;
+
+    use std::collections::HashSet;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use http_body_util::Full;
+    use hyper::body::Bytes;
+    use hyper::{Request, Response, StatusCode};
+
+    /// Items of the stand-in Firebase API
+    #[derive(Default)]
+    struct MockFirebase {
+        max_item_id: u64,
+        /// IDs whose next request fails with a server error
+        failing: HashSet<u64>,
+        /// IDs of every item requested, in order
+        requested: Vec<u64>,
+    }
+
+    fn json(status: StatusCode, body: String) -> Response<Full<Bytes>> {
+        let mut response: Response<Full<Bytes>> = Response::new(Full::new(Bytes::from(body)));
+        *response.status_mut() = status;
+        return response;
+    }
+
+    /// Answer a request like the Firebase API, where every fifth ID has no item
+    fn handle(request: Request<Bytes>, state: &Mutex<MockFirebase>) -> Response<Full<Bytes>> {
+        let mut state = state.lock().unwrap();
+        let path: &str = request.uri().path();
+        if path == "/v0/maxitem.json" {
+            return json(StatusCode::OK, state.max_item_id.to_string());
+        }
+
+        let Some(id) = path
+            .strip_prefix("/v0/item/")
+            .and_then(|rest| rest.strip_suffix(".json"))
+            .and_then(|id| id.parse::<u64>().ok())
+        else {
+            return json(StatusCode::NOT_FOUND, "null".to_string());
+        };
+        state.requested.push(id);
+
+        if state.failing.remove(&id) {
+            return json(StatusCode::INTERNAL_SERVER_ERROR, "{}".to_string());
+        }
+        if id % 5 == 0 || id > state.max_item_id {
+            return json(StatusCode::OK, "null".to_string());
+        }
+
+        let item: String = if id == 1 {
+            format!(
+                r#"{{"id":{id},"type":"story","by":"pg","title":"Y Combinator","time":1160418111}}"#
+            )
+        } else {
+            format!(
+                r#"{{"id":{id},"type":"comment","by":"alice","parent":{},"text":"Reply {id}"}}"#,
+                id - 1
+            )
+        };
+
+        return json(StatusCode::OK, item);
+    }
+
+    /// Start a stand-in for the Firebase API, returning its `v0` base URL
+    async fn spawn_firebase(state: Arc<Mutex<MockFirebase>>) -> String {
+        let base_url: String = test_server::spawn(move |request| handle(request, &state)).await;
+        return format!("{base_url}/v0");
+    }
+
+    fn options(direction: CrawlDirection, limit: Option<u64>) -> CrawlOptions {
+        return CrawlOptions {
+            direction,
+            limit,
+            concurrency: 4,
+        };
+    }
+
+    #[tokio::test]
+    async fn test_crawl_resumes_from_checkpoint() {
+        let state: Arc<Mutex<MockFirebase>> = Arc::new(Mutex::new(MockFirebase {
+            max_item_id: 25,
+            failing: HashSet::from([13]),
+            ..Default::default()
+        }));
+        let client: HackerNewsClient =
+            HackerNewsClient::with_base_url(&spawn_firebase(Arc::clone(&state)).await);
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("archive.db");
+        let mut store: ArchiveStore = ArchiveStore::open(&path).unwrap();
+
+        // The batch holding item 13 fails, after 25..=14 were saved in three batches
+        let mut batches: usize = 0;
+        let result: HnResult<CrawlSummary> = crawl(
+            &client,
+            &mut store,
+            options(CrawlDirection::Backward, None),
+            |_| batches += 1,
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(batches, 3);
+        assert_eq!(
+            store.range().unwrap(),
+            Some(ArchiveRange { low: 14, high: 25 })
+        );
+
+        // A new run (with a new connection) picks up at item 13
+        let mut store: ArchiveStore = ArchiveStore::open(&path).unwrap();
+        let summary: CrawlSummary = crawl(
+            &client,
+            &mut store,
+            options(CrawlDirection::Backward, None),
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary.walked, 13);
+        assert_eq!(summary.archived, 11);
+        assert_eq!(summary.range, Some(ArchiveRange { low: 1, high: 25 }));
+        assert_eq!(store.count().unwrap(), 20);
+        assert_eq!(
+            archived_item(&store, 1).unwrap().title.as_deref(),
+            Some("Y Combinator")
+        );
+        assert!(archived_item(&store, 10).is_none());
+
+        // Only the failed batch was requested again
+        let mut requested: Vec<u64> = state.lock().unwrap().requested.clone();
+        requested.sort_unstable();
+        let mut expected: Vec<u64> = (1..=25).chain(10..=13).collect();
+        expected.sort_unstable();
+        assert_eq!(requested, expected);
+
+        // Nothing is left below item 1
+        let summary: CrawlSummary = crawl(
+            &client,
+            &mut store,
+            options(CrawlDirection::Backward, None),
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary.walked, 0);
+    }
+
+    #[tokio::test]
+    async fn test_crawl_forward_catches_up_with_new_items() {
+        let state: Arc<Mutex<MockFirebase>> = Arc::new(Mutex::new(MockFirebase {
+            max_item_id: 8,
+            ..Default::default()
+        }));
+        let client: HackerNewsClient =
+            HackerNewsClient::with_base_url(&spawn_firebase(Arc::clone(&state)).await);
+        let directory = tempfile::tempdir().unwrap();
+        let mut store: ArchiveStore =
+            ArchiveStore::open(&directory.path().join("archive.db")).unwrap();
+
+        let summary: CrawlSummary = crawl(
+            &client,
+            &mut store,
+            options(CrawlDirection::Backward, Some(3)),
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary.walked, 3);
+        assert_eq!(summary.range, Some(ArchiveRange { low: 6, high: 8 }));
+
+        state.lock().unwrap().max_item_id = 12;
+        let summary: CrawlSummary = crawl(
+            &client,
+            &mut store,
+            options(CrawlDirection::Forward, None),
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary.max_item_id, 12);
+        assert_eq!(summary.walked, 4);
+        assert_eq!(summary.archived, 3);
+        assert_eq!(summary.range, Some(ArchiveRange { low: 6, high: 12 }));
+        assert_eq!(archived_item(&store, 12).unwrap().parent, Some(11));
+    }
+
+    #[tokio::test]
+    async fn test_crawl_is_paced_by_the_rate_limiter() {
+        let state: Arc<Mutex<MockFirebase>> = Arc::new(Mutex::new(MockFirebase {
+            max_item_id: 12,
+            ..Default::default()
+        }));
+        let limiter: Arc<RateLimiter> = Arc::new(RateLimiter::new(RateLimitConfig {
+            requests_per_second: 40.0,
+            burst: 4,
+            max_retries: 0,
+        }));
+        let client: HackerNewsClient =
+            HackerNewsClient::with_base_url(&spawn_firebase(state).await)
+                .with_rate_limiter(Arc::clone(&limiter));
+        let directory = tempfile::tempdir().unwrap();
+        let mut store: ArchiveStore =
+            ArchiveStore::open(&directory.path().join("archive.db")).unwrap();
+
+        // After a burst of four, the other nine requests wait 25ms each for a token
+        let started: Instant = Instant::now();
+        crawl(
+            &client,
+            &mut store,
+            options(CrawlDirection::Backward, None),
+            |_| {},
+        )
+        .await
+        .unwrap();
+
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        let stats: ThrottleStats = limiter.stats();
+        assert_eq!(stats.requests, 13);
+        assert_eq!(stats.delayed, 9);
+    }
+
+    #[test]
+    fn test_next_batch() {
+        let range: Option<ArchiveRange> = Some(ArchiveRange { low: 5, high: 10 });
+
+        assert_eq!(
+            next_batch(None, 10, CrawlDirection::Backward, 3),
+            vec![10, 9, 8]
+        );
+        assert_eq!(
+            next_batch(range, 20, CrawlDirection::Backward, 8),
+            vec![4, 3, 2, 1]
+        );
+        assert_eq!(next_batch(None, 20, CrawlDirection::Forward, 2), vec![1, 2]);
+        assert_eq!(
+            next_batch(range, 12, CrawlDirection::Forward, 5),
+            vec![11, 12]
+        );
+        assert!(next_batch(range, 10, CrawlDirection::Forward, 5).is_empty());
+        assert!(
+            next_batch(
+                Some(ArchiveRange { low: 1, high: 10 }),
+                10,
+                CrawlDirection::Backward,
+                5
+            )
+            .is_empty()
+        );
+    }
+}
@@ -0,0 +1,360 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{NaiveDate, NaiveTime};
+use rusqlite::types::Value;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::models::domain::normalize_site;
+use crate::types::HnResult;
+
+/// Columns selected by filter queries
+const FILTER_COLUMNS: &str = "id, type, by, time, title, url, domain, score, descendants, parent";
+
+/// Item types that can be filtered on
+const ITEM_TYPES: [&str; 5] = ["story", "comment", "job", "poll", "pollopt"];
+
+/// Rows returned by a query
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryRows {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl QueryRows {
+    /// Values of the `id` column, or `None` if the query has none
+    pub fn ids(&self) -> Option<Vec<u64>> {
+        let index: usize = self.columns.iter().position(|column| column == "id")?;
+
+        return Some(
+            self.rows
+                .iter()
+                .filter_map(|row| match row[index] {
+                    Value::Integer(id) => u64::try_from(id).ok(),
+                    _ => None,
+                })
+                .collect(),
+        );
+    }
+
+    /// Render the rows as a JSON array of objects keyed by column
+    pub fn to_json(&self) -> HnResult<String> {
+        let objects: Vec<Map<String, JsonValue>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                return self
+                    .columns
+                    .iter()
+                    .cloned()
+                    .zip(row.iter().map(json_value))
+                    .collect();
+            })
+            .collect();
+
+        return Ok(serde_json::to_string_pretty(&objects)?);
+    }
+
+    /// Render the rows as CSV with a header line
+    pub fn to_csv(&self) -> String {
+        let mut lines: Vec<String> = vec![csv_line(self.columns.iter().map(String::as_str))];
+        for row in &self.rows {
+            let fields: Vec<String> = row.iter().map(text_value).collect();
+            lines.push(csv_line(fields.iter().map(String::as_str)));
+        }
+
+        return lines.join("\n");
+    }
+}
+
+/// Whether a query is SQL rather than filter terms
+pub fn is_sql(query: &str) -> bool {
+    let keyword: String = query.split_whitespace().next().unwrap_or("").to_uppercase();
+
+    return keyword == "SELECT" || keyword == "WITH";
+}
+
+/// Filter over archived items, built from terms such as `by:pg`, `domain:github.com`,
+/// `type:story`, `score>100`, `comments>=10`, `after:2024-01-01`, `before:2025-01-01`,
+/// `sort:score` and plain words searched for in titles and text
+#[derive(Debug, Default, PartialEq)]
+pub struct ItemFilter {
+    conditions: Vec<String>,
+    parameters: Vec<Value>,
+    order: &'static str,
+}
+
+impl ItemFilter {
+    pub fn parse(terms: &[String]) -> HnResult<Self> {
+        let mut filter: ItemFilter = ItemFilter {
+            order: "time DESC",
+            ..Default::default()
+        };
+
+        for term in terms.iter().flat_map(|term| term.split_whitespace()) {
+            if let Some((column, operator, value)) = comparison(term) {
+                let value: i64 = value
+                    .parse()
+                    .map_err(|_| format!("Invalid number in filter: {term}"))?;
+                filter.add(
+                    format!("{column} {operator} ?"),
+                    vec![Value::Integer(value)],
+                );
+                continue;
+            }
+
+            let Some((key, value)) = term.split_once(':') else {
+                let pattern: Value = Value::Text(format!("%{}%", escape_like(term)));
+                filter.add(
+                    "(title LIKE ? ESCAPE '\\' OR text LIKE ? ESCAPE '\\')".to_string(),
+                    vec![pattern.clone(), pattern],
+                );
+                continue;
+            };
+
+            match key.to_lowercase().as_str() {
+                "by" | "author" => {
+                    filter.add("by = ? COLLATE NOCASE".to_string(), vec![text(value)]);
+                }
+                "type" => {
+                    let item_type: String = value.to_lowercase();
+                    if !ITEM_TYPES.contains(&item_type.as_str()) {
+                        return Err(format!(
+                            "Invalid item type: {value} (use {})",
+                            ITEM_TYPES.join(", ")
+                        )
+                        .into());
+                    }
+                    filter.add("type = ?".to_string(), vec![Value::Text(item_type)]);
+                }
+                "domain" | "site" => {
+                    let site: String =
+                        normalize_site(value).ok_or_else(|| format!("Invalid site: {value}"))?;
+                    let subsites: Value = Value::Text(format!("{}/%", escape_like(&site)));
+                    filter.add(
+                        "(domain = ? OR domain LIKE ? ESCAPE '\\')".to_string(),
+                        vec![Value::Text(site), subsites],
+                    );
+                }
+                "after" => filter.add("time >= ?".to_string(), vec![date(value)?]),
+                "before" => filter.add("time < ?".to_string(), vec![date(value)?]),
+                "sort" => {
+                    filter.order = match value.to_lowercase().as_str() {
+                        "time" | "new" => "time DESC",
+                        "old" => "time ASC",
+                        "score" => "score DESC",
+                        "comments" => "descendants DESC",
+                        _ => {
+                            return Err(format!(
+                                "Invalid sort: {value} (use time, old, score or comments)"
+                            )
+                            .into());
+                        }
+                    };
+                }
+                _ => {
+                    return Err(format!(
+                        "Unknown filter: {term} (use by:, type:, domain:, after:, before:, sort:, score or comments comparisons, or words)"
+                    )
+                    .into());
+                }
+            }
+        }
+
+        return Ok(filter);
+    }
+
+    /// Build the SQL statement and its parameters
+    pub fn to_sql(&self, limit: u16) -> (String, Vec<Value>) {
+        let mut sql: String = format!("SELECT {FILTER_COLUMNS} FROM items");
+        if !self.conditions.is_empty() {
+            sql.push_str(&format!(" WHERE {}", self.conditions.join(" AND ")));
+        }
+        sql.push_str(&format!(" ORDER BY {} LIMIT {limit}", self.order));
+
+        return (sql, self.parameters.clone());
+    }
+
+    fn add(&mut self, condition: String, parameters: Vec<Value>) {
+        self.conditions.push(condition);
+        self.parameters.extend(parameters);
+    }
+}
+
+/// Split a numeric comparison such as `score>=100` into column, operator and value
+fn comparison(term: &str) -> Option<(&'static str, &'static str, &str)> {
+    let split: usize = term.find(['<', '>', '='])?;
+    let column: &str = match term[..split].to_lowercase().as_str() {
+        "score" | "points" => "score",
+        "comments" | "descendants" => "descendants",
+        _ => return None,
+    };
+
+    let rest: &str = &term[split..];
+    for operator in [">=", "<=", ">", "<", "="] {
+        if let Some(value) = rest.strip_prefix(operator) {
+            return Some((column, operator, value));
+        }
+    }
+
+    return None;
+}
+
+/// Unix seconds at the start of a `YYYY-MM-DD` date (UTC)
+fn date(value: &str) -> HnResult<Value> {
+    let date: NaiveDate = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date: {value} (use YYYY-MM-DD)"))?;
+
+    return Ok(Value::Integer(
+        date.and_time(NaiveTime::MIN).and_utc().timestamp(),
+    ));
+}
+
+fn text(value: &str) -> Value {
+    return Value::Text(value.to_string());
+}
+
+/// Escape `LIKE` wildcards so they match literally
+fn escape_like(value: &str) -> String {
+    return value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+}
+
+fn json_value(value: &Value) -> JsonValue {
+    return match value {
+        Value::Null => JsonValue::Null,
+        Value::Integer(number) => JsonValue::from(*number),
+        Value::Real(number) => JsonValue::from(*number),
+        Value::Text(text) => JsonValue::from(text.as_str()),
+        Value::Blob(bytes) => JsonValue::from(BASE64.encode(bytes)),
+    };
+}
+
+fn text_value(value: &Value) -> String {
+    return match value {
+        Value::Null => String::new(),
+        Value::Integer(number) => number.to_string(),
+        Value::Real(number) => number.to_string(),
+        Value::Text(text) => text.clone(),
+        Value::Blob(bytes) => BASE64.encode(bytes),
+    };
+}
+
+/// Join fields into a CSV line, quoting those with commas, quotes or line breaks
+fn csv_line<'a>(fields: impl Iterator<Item = &'a str>) -> String {
+    return fields
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                return format!("\"{}\"", field.replace('"', "\"\""));
+            }
+            return field.to_string();
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(query: &str) -> Vec<String> {
+        return vec![query.to_string()];
+    }
+
+    #[test]
+    fn test_is_sql() {
+        assert!(is_sql("select id from items"));
+        assert!(is_sql("  WITH recent AS (SELECT 1) SELECT * FROM recent"));
+        assert!(!is_sql("by:pg selection"));
+    }
+
+    #[test]
+    fn test_item_filter_to_sql() {
+        let filter: ItemFilter = ItemFilter::parse(&terms(
+            "by:PG domain:www.github.com score>=100 rust_lang sort:score",
+        ))
+        .unwrap();
+
+        let (sql, parameters) = filter.to_sql(30);
+
+        assert_eq!(
+            sql,
+            format!(
+                "SELECT {FILTER_COLUMNS} FROM items WHERE by = ? COLLATE NOCASE \
+                AND (domain = ? OR domain LIKE ? ESCAPE '\\') AND score >= ? \
+                AND (title LIKE ? ESCAPE '\\' OR text LIKE ? ESCAPE '\\') \
+                ORDER BY score DESC LIMIT 30"
+            )
+        );
+        assert_eq!(
+            parameters,
+            vec![
+                text("PG"),
+                text("github.com"),
+                text("github.com/%"),
+                Value::Integer(100),
+                text("%rust\\_lang%"),
+                text("%rust\\_lang%"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_item_filter_dates_and_errors() {
+        let filter: ItemFilter =
+            ItemFilter::parse(&terms("type:Story after:2024-01-01 before:2024-01-02")).unwrap();
+        let (sql, parameters) = filter.to_sql(5);
+        assert!(
+            sql.ends_with("WHERE type = ? AND time >= ? AND time < ? ORDER BY time DESC LIMIT 5")
+        );
+        assert_eq!(
+            parameters,
+            vec![
+                text("story"),
+                Value::Integer(1704067200),
+                Value::Integer(1704153600)
+            ]
+        );
+
+        for (query, error) in [
+            ("type:post", "Invalid item type"),
+            ("after:yesterday", "Invalid date"),
+            ("score>many", "Invalid number"),
+            ("sort:random", "Invalid sort"),
+            ("karma:5", "Unknown filter"),
+        ] {
+            let message: String = ItemFilter::parse(&terms(query)).unwrap_err().to_string();
+            assert!(message.contains(error), "{query}: {message}");
+        }
+    }
+
+    #[test]
+    fn test_query_rows_output() {
+        let rows: QueryRows = QueryRows {
+            columns: vec!["id".to_string(), "title".to_string()],
+            rows: vec![
+                vec![Value::Integer(1), text("Hello, \"world\"")],
+                vec![Value::Integer(2), Value::Null],
+            ],
+        };
+
+        assert_eq!(rows.ids(), Some(vec![1, 2]));
+        assert_eq!(rows.to_csv(), "id,title\n1,\"Hello, \"\"world\"\"\"\n2,");
+
+        let json: JsonValue = serde_json::from_str(&rows.to_json().unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"id": 1, "title": "Hello, \"world\""},
+                {"id": 2, "title": null}
+            ])
+        );
+
+        let counts: QueryRows = QueryRows {
+            columns: vec!["count".to_string()],
+            rows: vec![vec![Value::Integer(3)]],
+        };
+        assert_eq!(counts.ids(), None);
+    }
+}
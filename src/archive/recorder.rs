@@ -0,0 +1,245 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use async_trait::async_trait;
+
+use crate::api::HackerNewsGateway;
+use crate::archive::store::ArchiveStore;
+use crate::models::story::{HackerNewsStoryType, StoryItem};
+use crate::models::user::HackerNewsUser;
+use crate::types::HnResult;
+
+/// Gateway that keeps every item and user fetched through another gateway in the archive
+///
+/// Search results are not kept, as they only hold part of each story. The crawler stores
+/// its items with its checkpoints, so it runs without this gateway. Archive failures
+/// are logged as warnings, so fetches still succeed.
+#[derive(Clone)]
+pub struct RecordingGateway<G: HackerNewsGateway> {
+    gateway: G,
+    store: Arc<Mutex<ArchiveStore>>,
+}
+
+impl<G: HackerNewsGateway> RecordingGateway<G> {
+    pub fn new(gateway: G, store: ArchiveStore) -> Self {
+        return Self {
+            gateway,
+            store: Arc::new(Mutex::new(store)),
+        };
+    }
+
+    fn store(&self) -> HnResult<MutexGuard<'_, ArchiveStore>> {
+        return Ok(self
+            .store
+            .lock()
+            .map_err(|_| "Archive is unavailable after an earlier failure")?);
+    }
+
+    /// Keep items in the archive, warning rather than failing the fetch that returned them
+    fn record_items(&self, items: &[StoryItem]) {
+        if items.is_empty() {
+            return;
+        }
+
+        if let Err(error) = self.store().and_then(|mut store| store.save_items(items)) {
+            log::warn!("Unable to archive {} items: {error}", items.len());
+        }
+    }
+
+    /// Keep a user in the archive, warning rather than failing the fetch that returned it
+    fn record_user(&self, user: &HackerNewsUser) {
+        if let Err(error) = self.store().and_then(|store| store.save_user(user)) {
+            log::warn!("Unable to archive user {}: {error}", user.id);
+        }
+    }
+}
+
+#[async_trait]
+impl<G: HackerNewsGateway> HackerNewsGateway for RecordingGateway<G> {
+    async fn fetch_stories(
+        &self,
+        story_type: &HackerNewsStoryType,
+        limit: u16,
+    ) -> HnResult<Vec<StoryItem>> {
+        let stories: Vec<StoryItem> = self.gateway.fetch_stories(story_type, limit).await?;
+        self.record_items(&stories);
+
+        return Ok(stories);
+    }
+
+    async fn fetch_feed(
+        &self,
+        story_types: &[HackerNewsStoryType],
+        limit: u16,
+    ) -> HnResult<Vec<StoryItem>> {
+        let stories: Vec<StoryItem> = self.gateway.fetch_feed(story_types, limit).await?;
+        self.record_items(&stories);
+
+        return Ok(stories);
+    }
+
+    async fn fetch_item(&self, id: u64) -> HnResult<Option<StoryItem>> {
+        let item: Option<StoryItem> = self.gateway.fetch_item(id).await?;
+        if let Some(item) = &item {
+            self.record_items(std::slice::from_ref(item));
+        }
+
+        return Ok(item);
+    }
+
+    async fn fetch_max_item_id(&self) -> HnResult<u64> {
+        return self.gateway.fetch_max_item_id().await;
+    }
+
+    async fn fetch_items(&self, ids: &[u64], concurrency: usize) -> HnResult<Vec<StoryItem>> {
+        let items: Vec<StoryItem> = self.gateway.fetch_items(ids, concurrency).await?;
+        self.record_items(&items);
+
+        return Ok(items);
+    }
+
+    async fn fetch_user(&self, id: &str) -> HnResult<Option<HackerNewsUser>> {
+        let user: Option<HackerNewsUser> = self.gateway.fetch_user(id).await?;
+        if let Some(user) = &user {
+            self.record_user(user);
+        }
+
+        return Ok(user);
+    }
+
+    async fn fetch_domain_stories(&self, site: &str, limit: u16) -> HnResult<Vec<StoryItem>> {
+        return self.gateway.fetch_domain_stories(site, limit).await;
+    }
+
+    async fn fetch_url_submissions(&self, url: &str) -> HnResult<Vec<StoryItem>> {
+        return self.gateway.fetch_url_submissions(url).await;
+    }
+
+    async fn fetch_article(&self, url: &str) -> HnResult<String> {
+        return self.gateway.fetch_article(url).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::MockHackerNewsGateway;
+    use crate::archive::query::QueryRows;
+    use crate::archive::store::tests::archived_item;
+
+    #[tokio::test]
+    async fn test_recording_gateway_keeps_items_and_users() {
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway.expect_fetch_stories().returning(|_, _| {
+            Ok(vec![StoryItem {
+                id: 1,
+                title: Some("Story".to_string()),
+                ..Default::default()
+            }])
+        });
+        mock_gateway.expect_fetch_item().returning(|id| {
+            Ok(Some(StoryItem {
+                id,
+                parent: Some(1),
+                ..Default::default()
+            }))
+        });
+        mock_gateway.expect_fetch_user().returning(|id| {
+            Ok(Some(HackerNewsUser {
+                id: id.to_string(),
+                karma: 42,
+                ..Default::default()
+            }))
+        });
+        mock_gateway
+            .expect_fetch_domain_stories()
+            .returning(|_, _| Ok(vec![StoryItem::default()]));
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("archive.db");
+        let gateway: RecordingGateway<MockHackerNewsGateway> =
+            RecordingGateway::new(mock_gateway, ArchiveStore::open(&path).unwrap());
+
+        gateway
+            .fetch_stories(&HackerNewsStoryType::T, 1)
+            .await
+            .unwrap();
+        gateway.fetch_item(2).await.unwrap();
+        gateway.fetch_user("alice").await.unwrap();
+        gateway
+            .fetch_domain_stories("example.com", 1)
+            .await
+            .unwrap();
+
+        let store: ArchiveStore = ArchiveStore::open(&path).unwrap();
+        assert_eq!(store.count().unwrap(), 2);
+        assert_eq!(
+            archived_item(&store, 1).unwrap().title.as_deref(),
+            Some("Story")
+        );
+        assert_eq!(archived_item(&store, 2).unwrap().parent, Some(1));
+
+        let users: QueryRows = store.query("SELECT id, karma FROM users", &[]).unwrap();
+        assert_eq!(users.rows.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_recording_gateway_keeps_fetched_comments() {
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway.expect_fetch_items().returning(|ids, _| {
+            Ok(ids
+                .iter()
+                .map(|id| StoryItem {
+                    id: *id,
+                    parent: Some(1),
+                    text: Some(format!("Comment {id}")),
+                    ..Default::default()
+                })
+                .collect())
+        });
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("archive.db");
+        let gateway: RecordingGateway<MockHackerNewsGateway> =
+            RecordingGateway::new(mock_gateway, ArchiveStore::open(&path).unwrap());
+
+        let comments: Vec<StoryItem> = gateway.fetch_items(&[2, 3], 2).await.unwrap();
+        assert_eq!(comments.len(), 2);
+
+        let store: ArchiveStore = ArchiveStore::open(&path).unwrap();
+        assert_eq!(store.count().unwrap(), 2);
+        assert_eq!(
+            archived_item(&store, 3).unwrap().text.as_deref(),
+            Some("Comment 3")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_recording_gateway_fetches_when_archive_fails() {
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_stories()
+            .returning(|_, _| Ok(vec![StoryItem::default()]));
+        mock_gateway.expect_fetch_user().returning(|id| {
+            Ok(Some(HackerNewsUser {
+                id: id.to_string(),
+                ..Default::default()
+            }))
+        });
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("archive.db");
+        let gateway: RecordingGateway<MockHackerNewsGateway> =
+            RecordingGateway::new(mock_gateway, ArchiveStore::open(&path).unwrap());
+        rusqlite::Connection::open(&path)
+            .unwrap()
+            .execute_batch("DROP TABLE items; DROP TABLE users;")
+            .unwrap();
+
+        let stories: Vec<StoryItem> = gateway
+            .fetch_stories(&HackerNewsStoryType::T, 1)
+            .await
+            .unwrap();
+        assert_eq!(stories.len(), 1);
+        assert!(gateway.fetch_user("alice").await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,427 @@
+use std::path::Path;
+use std::time::Duration;
+
+use rusqlite::functions::FunctionFlags;
+use rusqlite::types::Value;
+use rusqlite::{
+    CachedStatement, Connection, OptionalExtension, Statement, Transaction, params,
+    params_from_iter,
+};
+
+use crate::archive::query::QueryRows;
+use crate::models::domain::site_domain;
+use crate::models::story::StoryItem;
+use crate::models::user::HackerNewsUser;
+use crate::types::HnResult;
+use crate::utils::paths::data_dir;
+
+/// File name of the archive database within the data directory
+const ARCHIVE_FILE_NAME: &str = "archive.db";
+
+/// Name of the checkpoint holding the range of item IDs crawled so far
+const CRAWL_CHECKPOINT: &str = "crawl";
+
+/// How long to wait for another process (e.g. a running crawl) to finish writing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Schema changes in the order they are applied, the database's `user_version`
+/// being the number applied so far
+const MIGRATIONS: [&str; 2] = [
+    "CREATE TABLE IF NOT EXISTS items (
+        id INTEGER PRIMARY KEY,
+        type TEXT,
+        by TEXT,
+        time INTEGER,
+        title TEXT,
+        url TEXT,
+        text TEXT,
+        score INTEGER,
+        descendants INTEGER,
+        parent INTEGER,
+        json TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS checkpoints (
+        name TEXT PRIMARY KEY,
+        low INTEGER NOT NULL,
+        high INTEGER NOT NULL
+    );",
+    "ALTER TABLE items ADD COLUMN domain TEXT;
+    UPDATE items SET domain = site_domain(url) WHERE url IS NOT NULL;
+    CREATE INDEX items_time ON items (time);
+    CREATE INDEX items_by ON items (by);
+    CREATE INDEX items_score ON items (score);
+    CREATE INDEX items_domain ON items (domain);
+    CREATE TABLE users (
+        id TEXT PRIMARY KEY,
+        created INTEGER NOT NULL,
+        karma INTEGER NOT NULL,
+        about TEXT,
+        json TEXT NOT NULL
+    );",
+];
+
+/// Contiguous range of item IDs that have all been crawled, inclusive
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArchiveRange {
+    pub low: u64,
+    pub high: u64,
+}
+
+impl ArchiveRange {
+    /// Grow the range to also cover the given IDs, which must border it
+    pub fn extend(range: Option<Self>, ids: &[u64]) -> Option<Self> {
+        let low: u64 = ids.iter().copied().min()?;
+        let high: u64 = ids.iter().copied().max()?;
+
+        return Some(match range {
+            Some(range) => Self {
+                low: range.low.min(low),
+                high: range.high.max(high),
+            },
+            None => Self { low, high },
+        });
+    }
+}
+
+/// Local SQLite database of Hacker News items and users
+///
+/// Queries can use `site_domain(url)`, which gives the site of a URL as the story
+/// list shows it.
+pub struct ArchiveStore {
+    connection: Connection,
+}
+
+impl ArchiveStore {
+    /// Open (and create or upgrade if needed) the database at a path
+    pub fn open(path: &Path) -> HnResult<Self> {
+        let mut connection: Connection = Connection::open(path)
+            .map_err(|error| format!("Unable to open archive {}: {error}", path.display()))?;
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        connection.create_scalar_function(
+            "site_domain",
+            1,
+            FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+            |context| {
+                let url: Option<String> = context.get(0)?;
+                return Ok(url.as_deref().and_then(site_domain));
+            },
+        )?;
+        migrate(&mut connection)?;
+
+        return Ok(Self { connection });
+    }
+
+    /// Open the database in the local data directory
+    pub fn open_default() -> HnResult<Self> {
+        return Self::open(&data_dir()?.join(ARCHIVE_FILE_NAME));
+    }
+
+    /// Range of item IDs crawled so far, or `None` before the first crawl
+    pub fn range(&self) -> HnResult<Option<ArchiveRange>> {
+        let range: Option<ArchiveRange> = self
+            .connection
+            .query_row(
+                "SELECT low, high FROM checkpoints WHERE name = ?1",
+                params![CRAWL_CHECKPOINT],
+                |row| {
+                    return Ok(ArchiveRange {
+                        low: row.get(0)?,
+                        high: row.get(1)?,
+                    });
+                },
+            )
+            .optional()?;
+
+        return Ok(range);
+    }
+
+    /// Store a batch of crawled items together with the new crawl checkpoint
+    ///
+    /// Both are written in one transaction, so an interrupted crawl resumes right
+    /// after the last batch that was saved.
+    pub fn save_batch(&mut self, items: &[StoryItem], range: ArchiveRange) -> HnResult<()> {
+        let transaction: Transaction = self.connection.transaction()?;
+        insert_items(&transaction, items)?;
+        transaction.execute(
+            "INSERT OR REPLACE INTO checkpoints (name, low, high) VALUES (?1, ?2, ?3)",
+            params![CRAWL_CHECKPOINT, range.low, range.high],
+        )?;
+        transaction.commit()?;
+
+        return Ok(());
+    }
+
+    /// Store items fetched outside of a crawl, replacing older copies
+    pub fn save_items(&mut self, items: &[StoryItem]) -> HnResult<()> {
+        let transaction: Transaction = self.connection.transaction()?;
+        insert_items(&transaction, items)?;
+        transaction.commit()?;
+
+        return Ok(());
+    }
+
+    /// Store a user profile, replacing an older copy
+    pub fn save_user(&self, user: &HackerNewsUser) -> HnResult<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO users (id, created, karma, about, json)
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                user.id,
+                user.created,
+                user.karma,
+                user.about,
+                serde_json::to_string(user)?
+            ],
+        )?;
+
+        return Ok(());
+    }
+
+    /// Get the archived items with the given IDs, in the order of `ids`
+    pub fn items(&self, ids: &[u64]) -> HnResult<Vec<StoryItem>> {
+        let mut select: CachedStatement = self
+            .connection
+            .prepare_cached("SELECT json FROM items WHERE id = ?1")?;
+
+        let mut items: Vec<StoryItem> = Vec::new();
+        for id in ids {
+            let json: Option<String> =
+                select.query_row(params![id], |row| row.get(0)).optional()?;
+            if let Some(json) = json {
+                items.push(serde_json::from_str(&json)?);
+            }
+        }
+
+        return Ok(items);
+    }
+
+    /// Run a read-only SQL statement with positional parameters
+    pub fn query(&self, sql: &str, parameters: &[Value]) -> HnResult<QueryRows> {
+        let mut statement: Statement = self
+            .connection
+            .prepare(sql)
+            .map_err(|error| format!("Invalid query: {error}"))?;
+        if !statement.readonly() {
+            return Err("Only read-only queries (e.g. SELECT) are allowed".into());
+        }
+
+        let columns: Vec<String> = statement
+            .column_names()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let mut rows: Vec<Vec<Value>> = Vec::new();
+        let mut results = statement.query(params_from_iter(parameters))?;
+        while let Some(row) = results.next()? {
+            let mut values: Vec<Value> = Vec::new();
+            for index in 0..columns.len() {
+                values.push(row.get(index)?);
+            }
+            rows.push(values);
+        }
+
+        return Ok(QueryRows { columns, rows });
+    }
+
+    /// Number of archived items
+    pub fn count(&self) -> HnResult<u64> {
+        let count: u64 = self
+            .connection
+            .query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))?;
+
+        return Ok(count);
+    }
+}
+
+/// Apply the migrations the database has not had yet
+fn migrate(connection: &mut Connection) -> HnResult<()> {
+    let version: usize = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let transaction: Transaction = connection.transaction()?;
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", index + 1)?;
+        transaction.commit()?;
+    }
+
+    return Ok(());
+}
+
+/// Insert or replace items within a transaction
+fn insert_items(transaction: &Transaction, items: &[StoryItem]) -> HnResult<()> {
+    let mut insert: CachedStatement = transaction.prepare_cached(
+        "INSERT OR REPLACE INTO items
+            (id, type, by, time, title, url, domain, text, score, descendants, parent, json)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+    )?;
+
+    for item in items {
+        insert.execute(params![
+            item.id,
+            item.item_type.map(|item_type| item_type.to_string()),
+            item.by,
+            item.time.map(|time| time.timestamp()),
+            item.title,
+            item.url,
+            item.domain(),
+            item.text,
+            item.score,
+            item.descendants,
+            item.parent,
+            serde_json::to_string(item)?,
+        ])?;
+    }
+
+    return Ok(());
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::models::story::ItemType;
+
+    /// Get an archived item by ID
+    pub fn archived_item(store: &ArchiveStore, id: u64) -> Option<StoryItem> {
+        return store.items(&[id]).unwrap().pop();
+    }
+
+    #[test]
+    fn test_archive_range_extend() {
+        assert_eq!(ArchiveRange::extend(None, &[]), None);
+
+        let range: Option<ArchiveRange> = ArchiveRange::extend(None, &[9, 10, 8]);
+        assert_eq!(range, Some(ArchiveRange { low: 8, high: 10 }));
+
+        assert_eq!(
+            ArchiveRange::extend(range, &[5, 6, 7]),
+            Some(ArchiveRange { low: 5, high: 10 })
+        );
+        assert_eq!(
+            ArchiveRange::extend(range, &[11]),
+            Some(ArchiveRange { low: 8, high: 11 })
+        );
+    }
+
+    #[test]
+    fn test_archive_store_round_trip() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("archive.db");
+        let mut store: ArchiveStore = ArchiveStore::open(&path).unwrap();
+
+        assert_eq!(store.range().unwrap(), None);
+        assert_eq!(store.count().unwrap(), 0);
+
+        let comment: StoryItem = StoryItem {
+            id: 8,
+            by: Some("alice".to_string()),
+            text: Some("Nice".to_string()),
+            parent: Some(7),
+            item_type: Some(ItemType::Comment),
+            ..Default::default()
+        };
+        store
+            .save_batch(&[comment], ArchiveRange { low: 7, high: 8 })
+            .unwrap();
+
+        // Reopening keeps both the items and the checkpoint
+        let store: ArchiveStore = ArchiveStore::open(&path).unwrap();
+        assert_eq!(
+            store.range().unwrap(),
+            Some(ArchiveRange { low: 7, high: 8 })
+        );
+        assert_eq!(store.count().unwrap(), 1);
+
+        let item: StoryItem = archived_item(&store, 8).unwrap();
+        assert_eq!(item.parent, Some(7));
+        assert_eq!(item.item_type, Some(ItemType::Comment));
+        assert!(archived_item(&store, 7).is_none());
+    }
+
+    #[test]
+    fn test_archive_store_items_users_and_queries() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut store: ArchiveStore =
+            ArchiveStore::open(&directory.path().join("archive.db")).unwrap();
+
+        let stories: Vec<StoryItem> = (1..=3)
+            .map(|id| StoryItem {
+                id,
+                score: Some(id as i32 * 10),
+                url: Some(format!("https://blog.example.com/{id}")),
+                ..Default::default()
+            })
+            .collect();
+        store.save_items(&stories).unwrap();
+        store
+            .save_user(&HackerNewsUser {
+                id: "pg".to_string(),
+                created: 1160418092,
+                karma: 157236,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let ids: Vec<u64> = store
+            .items(&[3, 9, 1])
+            .unwrap()
+            .iter()
+            .map(|item| item.id)
+            .collect();
+        assert_eq!(ids, vec![3, 1]);
+
+        let rows: QueryRows = store
+            .query(
+                "SELECT id, domain FROM items WHERE score > ? ORDER BY id",
+                &[Value::Integer(15)],
+            )
+            .unwrap();
+        assert_eq!(rows.columns, vec!["id", "domain"]);
+        assert_eq!(
+            rows.rows,
+            vec![
+                vec![Value::Integer(2), Value::Text("example.com".to_string())],
+                vec![Value::Integer(3), Value::Text("example.com".to_string())],
+            ]
+        );
+
+        let rows: QueryRows = store.query("SELECT karma FROM users", &[]).unwrap();
+        assert_eq!(rows.rows, vec![vec![Value::Integer(157236)]]);
+
+        assert!(
+            store
+                .query("DELETE FROM items", &[])
+                .unwrap_err()
+                .to_string()
+                .contains("read-only")
+        );
+        assert!(store.query("SELECT nope FROM items", &[]).is_err());
+    }
+
+    #[test]
+    fn test_archive_store_upgrades_first_schema() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("archive.db");
+
+        // An archive written before domains and users were stored
+        let connection: Connection = Connection::open(&path).unwrap();
+        connection.execute_batch(MIGRATIONS[0]).unwrap();
+        connection
+            .execute(
+                "INSERT INTO items (id, url, json) VALUES (1, 'https://github.com/rust-lang/rust', '{\"id\":1}')",
+                [],
+            )
+            .unwrap();
+        drop(connection);
+
+        let store: ArchiveStore = ArchiveStore::open(&path).unwrap();
+        let rows: QueryRows = store.query("SELECT domain FROM items", &[]).unwrap();
+        assert_eq!(
+            rows.rows,
+            vec![vec![Value::Text("github.com/rust-lang".to_string())]]
+        );
+
+        // Opening again does not re-run migrations
+        drop(store);
+        assert!(ArchiveStore::open(&path).is_ok());
+    }
+}
@@ -1,60 +1,221 @@
-use chroma_print::print_warn;
-use clap::Parser;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use clap::{ArgAction, Parser};
+use clap_complete::engine::ArgValueCandidates;
 
 use crate::api::HackerNewsGateway;
+use crate::commands::Command;
+use crate::commands::completions::{story_type_candidates, theme_candidates};
+use crate::config::Config;
 use crate::models::story::{HackerNewsStoryType, StoryItem};
+use crate::print_warn;
 use crate::types::HnResult;
 use crate::ui::UserInterface;
+use crate::ui::opener::{LinkOpener, OpenMode};
+use crate::ui::theme::{ColorLevel, DEFAULT_THEME, Theme};
 use crate::utils::helpers::{clear_loading, show_loading};
+use crate::utils::logging::{LogConfig, LogFilter};
+use crate::utils::rate_limit::{MIN_REQUESTS_PER_SECOND, RateLimitConfig};
+use crate::utils::time::{DisplayTimezone, SystemClock, TimeFormatter, TimeMode};
 
 /// Command line arguments for interactively fetching, browsing, and opening Hacker News stories
 #[derive(Parser, Debug)]
 #[command(version)]
 pub struct Args {
-    /// Story type (top, new, best, ask, show, job)
-    #[arg(short = 's', long = "story", ignore_case = true, default_value = "t")]
-    story: HackerNewsStoryType,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Story type (top, new, best, ask, show, job). Separate several types with commas to merge them (e.g. top,show,ask)
+    #[arg(
+        short = 's',
+        long = "story",
+        ignore_case = true,
+        default_value = "t",
+        value_delimiter = ',',
+        add = ArgValueCandidates::new(story_type_candidates)
+    )]
+    story: Vec<HackerNewsStoryType>,
 
     /// The number of stories to fetch and display in the terminal UI
     #[arg(short = 'l', long = "limit", ignore_case = true, default_value = "30")]
     limit: u16,
+
+    /// How links are opened (browser, print, clipboard, hyperlink)
+    #[arg(
+        short = 'o',
+        long = "open",
+        ignore_case = true,
+        default_value = "browser"
+    )]
+    open: OpenMode,
+
+    /// Custom command used to open links in browser mode, e.g. "firefox --new-tab {url}"
+    #[arg(short = 'b', long = "browser")]
+    browser: Option<String>,
+
+    /// How story times are shown (relative, absolute)
+    #[arg(
+        short = 't',
+        long = "time",
+        ignore_case = true,
+        default_value = "relative"
+    )]
+    time: TimeMode,
+
+    /// Timezone for absolute times, e.g. UTC or Europe/Berlin
+    #[arg(short = 'z', long = "timezone", default_value = "local")]
+    timezone: DisplayTimezone,
+
+    /// Color theme (dark, light, high-contrast, monochrome or a theme from the config file) [default: dark]
+    #[arg(long = "theme", add = ArgValueCandidates::new(theme_candidates))]
+    theme: Option<String>,
+
+    /// Most requests per second to Hacker News [default: 20]
+    #[arg(long = "rate-limit", value_name = "RPS", global = true)]
+    rate_limit: Option<f64>,
+
+    /// Requests that may be sent at once before the rate limit applies [default: 40]
+    #[arg(long = "burst", value_name = "N", global = true)]
+    burst: Option<u32>,
+
+    /// Log each request to stderr, with more detail when repeated (-vv). RUST_LOG takes precedence, e.g. RUST_LOG=hn_stories=debug,reqwest=info
+    #[arg(short = 'v', long = "verbose", action = ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// Log debug details of every library, including the HTTP client
+    #[arg(long = "debug", global = true)]
+    debug: bool,
+
+    /// Append log records to a file instead of stderr
+    #[arg(long = "log-file", value_name = "PATH", global = true)]
+    log_file: Option<PathBuf>,
 }
 
 impl Args {
+    /// Get the link opener configured by the command line arguments
+    pub fn link_opener(&self) -> LinkOpener {
+        return LinkOpener::new(self.open, self.browser.clone());
+    }
+
+    /// Get the story time formatter configured by the command line arguments
+    pub fn time_formatter(&self) -> TimeFormatter {
+        return TimeFormatter::new(self.time, self.timezone, Arc::new(SystemClock));
+    }
+
+    /// Get the color theme chosen on the command line or in the config file
+    pub fn theme(&self, config: &Config) -> HnResult<Theme> {
+        let name: &str = self
+            .theme
+            .as_deref()
+            .or(config.theme.as_deref())
+            .unwrap_or(DEFAULT_THEME);
+
+        return Ok(Theme::resolve(name, &config.themes)?.with_color_level(ColorLevel::stdout()));
+    }
+
+    /// Get the rate limit set on the command line or in the config file
+    pub fn rate_limit(&self, config: &Config) -> HnResult<RateLimitConfig> {
+        let rate_limit: RateLimitConfig = RateLimitConfig {
+            requests_per_second: self
+                .rate_limit
+                .unwrap_or(config.rate_limit.requests_per_second),
+            burst: self.burst.unwrap_or(config.rate_limit.burst),
+            ..config.rate_limit
+        };
+
+        if rate_limit.requests_per_second.is_nan()
+            || rate_limit.requests_per_second < MIN_REQUESTS_PER_SECOND
+        {
+            return Err(format!(
+                "Rate limit must be at least {MIN_REQUESTS_PER_SECOND} requests per second"
+            )
+            .into());
+        }
+        if rate_limit.burst == 0 {
+            return Err("Burst must be at least 1 request".into());
+        }
+
+        return Ok(rate_limit);
+    }
+
+    /// Get where and what to log, from a `RUST_LOG`-style filter or the verbosity flags,
+    /// or `None` when logging is off
+    pub fn log_config(&self, env_filter: Option<&str>) -> HnResult<Option<LogConfig>> {
+        let filter: &str = match env_filter.filter(|filter| !filter.trim().is_empty()) {
+            Some(filter) => filter,
+            None if self.debug => "debug",
+            None => match self.verbose {
+                0 if self.log_file.is_some() => "hn_stories=info",
+                0 => return Ok(None),
+                1 => "hn_stories=info",
+                2 => "hn_stories=debug",
+                _ => "hn_stories=trace",
+            },
+        };
+
+        return Ok(Some(LogConfig {
+            filter: LogFilter::parse(filter)?,
+            file: self.log_file.clone(),
+            // Long-running commands log as they go, the rest once the terminal UI is done
+            hold: io::stdout().is_terminal()
+                && !self.command.as_ref().is_some_and(Command::is_long_running),
+        }));
+    }
+
+    /// Whether the items fetched should be kept in the archive as they are fetched
+    pub fn records_items(&self) -> bool {
+        return !self.command.as_ref().is_some_and(Command::is_archive_crawl);
+    }
+
     /// Run/execute command line arguments
     pub async fn run<G, U>(self, gateway: G, ui: U) -> HnResult<()>
     where
-        G: HackerNewsGateway,
+        G: HackerNewsGateway + 'static,
         U: UserInterface,
     {
+        if let Some(command) = self.command {
+            return command.run(gateway, ui).await;
+        }
+
         // Validate
-        let max_limit: u16 = self.story.max_stories_limit();
-        if self.limit > max_limit {
-            return Err(format!(
-                "Max story limit for '{}' stories is {}",
-                self.story.full_name(),
-                max_limit
-            )
-            .into());
+        let mut story_types: Vec<HackerNewsStoryType> = Vec::new();
+        for story_type in self.story {
+            let max_limit: u16 = story_type.max_stories_limit();
+            if self.limit > max_limit {
+                return Err(format!(
+                    "Max story limit for '{}' stories is {}",
+                    story_type.full_name(),
+                    max_limit
+                )
+                .into());
+            }
+
+            if !story_types.contains(&story_type) {
+                story_types.push(story_type);
+            }
         }
 
+        let story_names: String = HackerNewsStoryType::join_full_names(&story_types);
+
         show_loading(Some(&format!(
             "Fetching {} stories...please wait",
-            self.story.full_name()
+            story_names
         )));
 
-        let result: HnResult<Vec<StoryItem>> = gateway.fetch_stories(&self.story, self.limit).await;
+        let result: HnResult<Vec<StoryItem>> = gateway.fetch_feed(&story_types, self.limit).await;
 
         clear_loading();
 
         let stories: Vec<StoryItem> = result?;
 
         if stories.is_empty() {
-            print_warn!("No {} stories found to display", self.story.full_name());
+            print_warn!("No {} stories found to display", story_names);
             return Ok(());
         }
 
-        ui.render_stories_list(stories, &self.story).await?;
+        ui.render_stories_list(stories, &story_names).await?;
 
         return Ok(());
     }
@@ -79,10 +240,11 @@ mod tests {
             url: Some("https://google.com".to_string()),
             score: Some(100),
             title: Some("Test Story".to_string()),
+            ..Default::default()
         }];
 
         mock_gateway
-            .expect_fetch_stories()
+            .expect_fetch_feed()
             .times(1)
             .returning(move |_, _| Ok(fake_stories.clone()));
 
@@ -94,8 +256,19 @@ mod tests {
 
         // Create args
         let args = Args {
-            story: HackerNewsStoryType::T,
+            command: None,
+            story: vec![HackerNewsStoryType::T],
             limit: 1,
+            open: OpenMode::Browser,
+            browser: None,
+            time: TimeMode::Relative,
+            timezone: DisplayTimezone::Local,
+            theme: None,
+            rate_limit: None,
+            burst: None,
+            verbose: 0,
+            debug: false,
+            log_file: None,
         };
 
         let result: HnResult<()> = args.run(mock_gateway, mock_ui).await;
@@ -123,8 +296,19 @@ mod tests {
             let mock_ui = MockUserInterface::new();
 
             let args = Args {
-                story: story_type,
+                command: None,
+                story: vec![story_type],
                 limit: story_type.max_stories_limit() + 1,
+                open: OpenMode::Browser,
+                browser: None,
+                time: TimeMode::Relative,
+                timezone: DisplayTimezone::Local,
+                theme: None,
+                rate_limit: None,
+                burst: None,
+                verbose: 0,
+                debug: false,
+                log_file: None,
             };
 
             let result: HnResult<()> = args.run(mock_gateway, mock_ui).await;
@@ -145,4 +329,143 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_run_multiple_story_types() {
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_feed()
+            .withf(|story_types, limit| {
+                story_types == [HackerNewsStoryType::T, HackerNewsStoryType::S] && *limit == 10
+            })
+            .times(1)
+            .returning(|_, _| {
+                Ok(vec![StoryItem {
+                    id: 1,
+                    ..Default::default()
+                }])
+            });
+
+        let mut mock_ui = MockUserInterface::new();
+        mock_ui
+            .expect_render_stories_list()
+            .withf(|stories, list_name| stories.len() == 1 && list_name == "Top, Show")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        // Duplicate story types are only fetched once
+        let args = Args {
+            command: None,
+            story: vec![
+                HackerNewsStoryType::T,
+                HackerNewsStoryType::S,
+                HackerNewsStoryType::T,
+            ],
+            limit: 10,
+            open: OpenMode::Browser,
+            browser: None,
+            time: TimeMode::Relative,
+            timezone: DisplayTimezone::Local,
+            theme: None,
+            rate_limit: None,
+            burst: None,
+            verbose: 0,
+            debug: false,
+            log_file: None,
+        };
+
+        let result: HnResult<()> = args.run(mock_gateway, mock_ui).await;
+
+        assert!(result.is_ok(), "Error: {:?}", result.err());
+    }
+
+    #[tokio::test]
+    async fn test_run_multiple_story_types_max_limit() {
+        let mock_gateway = MockHackerNewsGateway::new();
+        let mock_ui = MockUserInterface::new();
+
+        // 300 is within the 'top' limit but above the 'ask' limit
+        let args = Args {
+            command: None,
+            story: vec![HackerNewsStoryType::T, HackerNewsStoryType::A],
+            limit: 300,
+            open: OpenMode::Browser,
+            browser: None,
+            time: TimeMode::Relative,
+            timezone: DisplayTimezone::Local,
+            theme: None,
+            rate_limit: None,
+            burst: None,
+            verbose: 0,
+            debug: false,
+            log_file: None,
+        };
+
+        let result: HnResult<()> = args.run(mock_gateway, mock_ui).await;
+
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains(MAX_STORY_LIMIT_ERROR_SUBSTRING)
+        );
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let parse = |args: &[&str]| Args::try_parse_from(args).unwrap();
+        let config: Config = Config::default();
+
+        let rate_limit: RateLimitConfig = parse(&["hn-stories", "--rate-limit", "0.5"])
+            .rate_limit(&config)
+            .unwrap();
+        assert_eq!(rate_limit.requests_per_second, 0.5);
+
+        for rate in ["0", "1e-300", "NaN", "-1"] {
+            assert!(
+                parse(&["hn-stories", &format!("--rate-limit={rate}")])
+                    .rate_limit(&config)
+                    .is_err(),
+                "{rate} was accepted"
+            );
+        }
+        assert!(
+            parse(&["hn-stories", "--burst", "0"])
+                .rate_limit(&config)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_log_config() {
+        let parse = |args: &[&str]| Args::try_parse_from(args).unwrap();
+
+        assert!(parse(&["hn-stories"]).log_config(None).unwrap().is_none());
+
+        let verbose: LogConfig = parse(&["hn-stories", "-vv"])
+            .log_config(None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            verbose.filter,
+            LogFilter::parse("hn_stories=debug").unwrap()
+        );
+        assert_eq!(verbose.file, None);
+
+        let env: LogConfig = parse(&["hn-stories", "-v", "--debug"])
+            .log_config(Some("reqwest=trace"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(env.filter, LogFilter::parse("reqwest=trace").unwrap());
+
+        let file: LogConfig = parse(&["hn-stories", "serve", "--log-file", "hn.log"])
+            .log_config(Some(""))
+            .unwrap()
+            .unwrap();
+        assert_eq!(file.filter, LogFilter::parse("hn_stories=info").unwrap());
+        assert_eq!(file.file, Some(PathBuf::from("hn.log")));
+        assert!(!file.hold);
+
+        assert!(parse(&["hn-stories"]).log_config(Some("=debug")).is_err());
+    }
 }
@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::story::StoryItem;
+use crate::types::HnResult;
+use crate::utils::json_store::JsonFile;
+use crate::utils::paths::data_dir;
+
+/// File name of the bookmarks within the data directory
+const BOOKMARKS_FILE_NAME: &str = "bookmarks.json";
+
+/// A saved story
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Bookmark {
+    pub bookmarked_at: DateTime<Utc>,
+    pub item: StoryItem,
+}
+
+/// Locally saved stories, in `bookmarks.json`
+pub struct BookmarkStore {
+    file: JsonFile<Vec<Bookmark>>,
+}
+
+impl BookmarkStore {
+    pub fn new(path: PathBuf) -> Self {
+        return Self {
+            file: JsonFile::new(path, "bookmarks"),
+        };
+    }
+
+    /// Open the bookmarks in the data directory
+    pub fn open_default() -> HnResult<Self> {
+        return Ok(Self::new(data_dir()?.join(BOOKMARKS_FILE_NAME)));
+    }
+
+    /// Load all bookmarks, newest first
+    pub fn load(&self) -> HnResult<Vec<Bookmark>> {
+        let mut bookmarks: Vec<Bookmark> = self.file.load()?.unwrap_or_default();
+        bookmarks.sort_by_key(|bookmark| std::cmp::Reverse(bookmark.bookmarked_at));
+
+        return Ok(bookmarks);
+    }
+
+    /// Bookmark stories, skipping ones that are already saved
+    ///
+    /// Returns the number of stories that were added.
+    pub fn add(&self, stories: &[StoryItem], bookmarked_at: DateTime<Utc>) -> HnResult<usize> {
+        let mut bookmarks: Vec<Bookmark> = self.load()?;
+        let existing: usize = bookmarks.len();
+
+        for story in stories {
+            if !bookmarks
+                .iter()
+                .any(|bookmark| bookmark.item.id == story.id)
+            {
+                bookmarks.push(Bookmark {
+                    bookmarked_at,
+                    item: story.clone(),
+                });
+            }
+        }
+
+        let added: usize = bookmarks.len() - existing;
+        if added > 0 {
+            self.file.save(&bookmarks)?;
+        }
+
+        return Ok(added);
+    }
+
+    /// Remove bookmarks by story ID
+    ///
+    /// Returns the number of bookmarks that were removed.
+    pub fn remove(&self, ids: &[u64]) -> HnResult<usize> {
+        let mut bookmarks: Vec<Bookmark> = self.load()?;
+        let existing: usize = bookmarks.len();
+
+        bookmarks.retain(|bookmark| !ids.contains(&bookmark.item.id));
+
+        let removed: usize = existing - bookmarks.len();
+        if removed > 0 {
+            self.file.save(&bookmarks)?;
+        }
+
+        return Ok(removed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::fs;
+
+    fn story(id: u64) -> StoryItem {
+        return StoryItem {
+            id,
+            title: Some(format!("Story {id}")),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_bookmark_store_add_and_remove() {
+        let directory = tempfile::tempdir().unwrap();
+        let store: BookmarkStore = BookmarkStore::new(directory.path().join("bookmarks.json"));
+        let earlier: DateTime<Utc> = Utc.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+        let later: DateTime<Utc> = Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap();
+
+        // Missing file means no bookmarks yet
+        assert!(store.load().unwrap().is_empty());
+
+        assert_eq!(store.add(&[story(1), story(2)], earlier).unwrap(), 2);
+        // Already bookmarked stories are skipped
+        assert_eq!(store.add(&[story(2), story(3)], later).unwrap(), 1);
+
+        let bookmarks: Vec<Bookmark> = store.load().unwrap();
+        let ids: Vec<u64> = bookmarks.iter().map(|bookmark| bookmark.item.id).collect();
+        assert_eq!(ids, vec![3, 1, 2]);
+        assert_eq!(bookmarks[0].bookmarked_at, later);
+        assert_eq!(bookmarks[1].item.title.as_deref(), Some("Story 1"));
+
+        assert_eq!(store.remove(&[1, 99]).unwrap(), 1);
+        assert_eq!(store.load().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_bookmark_store_corrupt_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path: PathBuf = directory.path().join("bookmarks.json");
+        fs::write(&path, "not json").unwrap();
+
+        let error: String = BookmarkStore::new(path).load().unwrap_err().to_string();
+
+        assert!(error.contains("Corrupt bookmarks"));
+    }
+}
@@ -1,21 +1,83 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
 use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use encoding_rs::{Encoding, UTF_8};
+use futures::future::try_join_all;
 use futures::stream::{self, StreamExt};
-use reqwest::Client;
+use mime::Mime;
+use reqwest::header::{
+    CONTENT_TYPE, ETAG, HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+    RETRY_AFTER, USER_AGENT,
+};
+use reqwest::{Client, Method, Request, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
 
 use crate::api::HackerNewsGateway;
-use crate::models::story::{HackerNewsStoryType, StoryItem};
-use crate::types::HnResult;
-use crate::utils::constants::HACKER_NEWS_API_BASE_URL;
+use crate::http_cache::{CachedResponse, HttpCache};
+use crate::models::domain::{normalize_url, site_matches};
+use crate::models::feed::merge_story_lists;
+use crate::models::search::{SearchHit, SearchResponse};
+use crate::models::story::{HackerNewsStoryType, StoryItem, StoryRanking};
+use crate::models::user::HackerNewsUser;
+use crate::types::{AsyncSafeError, HnResult};
+use crate::utils::constants::{
+    HACKER_NEWS_API_BASE_URL, HACKER_NEWS_SEARCH_API_BASE_URL, HTTP_USER_AGENT,
+};
+use crate::utils::logging::RequestSpan;
+use crate::utils::rate_limit::{RateLimitConfig, RateLimiter};
+
+/// Decode a text body with the charset of its `Content-Type`, falling back to UTF-8
+fn decode_text(headers: &HeaderMap, body: &[u8]) -> String {
+    let charset: Option<String> = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<Mime>().ok())
+        .and_then(|mime| {
+            mime.get_param(mime::CHARSET)
+                .map(|charset| charset.to_string())
+        });
+    let encoding: &Encoding = charset
+        .and_then(|charset| Encoding::for_label(charset.as_bytes()))
+        .unwrap_or(UTF_8);
+    let (text, _, _) = encoding.decode(body);
+
+    return text.into_owned();
+}
 
 /// Hacker News API versions
 pub enum ApiVersion {
     V0,
 }
 
+/// The most search results requested at once
+const MAX_SEARCH_HITS: u16 = 1000;
+/// Concurrent item requests when fetching a story list
+const STORY_ITEMS_CONCURRENCY: usize = 5;
+/// Search results requested when looking for submissions of a link
+const MAX_URL_SEARCH_HITS: u16 = 100;
+/// Wait before the first retry of a rate-limited request without `Retry-After`,
+/// doubled for every further retry
+const RETRY_BACKOFF: Duration = Duration::from_secs(1);
+/// Longest wait before retrying, beyond which rate-limited requests fail
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+/// Header asking the Firebase API to send an `ETag`, which it leaves out otherwise
+const FIREBASE_ETAG: &str = "x-firebase-etag";
+
 /// Client for interacting with the Hacker News API
+#[derive(Clone)]
 pub struct HackerNewsClient {
     client: Client,
     base_url: String,
+    search_url: String,
+    /// Rate limit shared by every request, including those of clones
+    limiter: Arc<RateLimiter>,
+    /// Stored responses to send conditional requests for
+    cache: Option<Arc<HttpCache>>,
 }
 
 #[async_trait]
@@ -32,6 +94,148 @@ impl HackerNewsGateway for HackerNewsClient {
 
         return Ok(story_items);
     }
+
+    async fn fetch_feed(
+        &self,
+        story_types: &[HackerNewsStoryType],
+        limit: u16,
+    ) -> HnResult<Vec<StoryItem>> {
+        if let [story_type] = story_types {
+            return self.fetch_stories(story_type, limit).await;
+        }
+
+        // Fetch every ID list concurrently
+        let id_lists: Vec<Vec<u64>> =
+            try_join_all(story_types.iter().map(|story_type| {
+                self.get_story_ids(story_type.parameter_name().to_string(), limit)
+            }))
+            .await?;
+
+        let lists: Vec<(HackerNewsStoryType, Vec<u64>)> =
+            story_types.iter().copied().zip(id_lists).collect();
+        let merged: Vec<(u64, Vec<StoryRanking>)> = merge_story_lists(&lists);
+
+        // Stories in several lists are only fetched once
+        let ids: Vec<u64> = merged.iter().map(|(id, _)| *id).collect();
+        let mut items: HashMap<u64, StoryItem> = self
+            .get_story_items(ids)
+            .await?
+            .into_iter()
+            .map(|item| (item.id, item))
+            .collect();
+
+        let story_items: Vec<StoryItem> = merged
+            .into_iter()
+            .filter_map(|(id, rankings)| {
+                let mut item: StoryItem = items.remove(&id)?;
+                item.rankings = rankings;
+                return Some(item);
+            })
+            .collect();
+
+        return Ok(story_items);
+    }
+
+    async fn fetch_item(&self, id: u64) -> HnResult<Option<StoryItem>> {
+        let request: RequestBuilder = self.client.get(format!("{}item/{id}.json", self.base_url));
+        let result: Option<StoryItem> = self.get_json(request).await?;
+
+        return Ok(result);
+    }
+
+    async fn fetch_max_item_id(&self) -> HnResult<u64> {
+        let request: RequestBuilder = self.client.get(format!("{}maxitem.json", self.base_url));
+        let result: u64 = self.get_json(request).await?;
+
+        return Ok(result);
+    }
+
+    async fn fetch_items(&self, ids: &[u64], concurrency: usize) -> HnResult<Vec<StoryItem>> {
+        let mut items: Vec<StoryItem> = Vec::new();
+        for result in self.get_items(ids.to_vec(), concurrency).await {
+            items.extend(result?);
+        }
+
+        return Ok(items);
+    }
+
+    async fn fetch_user(&self, id: &str) -> HnResult<Option<HackerNewsUser>> {
+        let request: RequestBuilder = self.client.get(format!("{}user/{id}.json", self.base_url));
+        let result: Option<HackerNewsUser> = self.get_json(request).await?;
+
+        return Ok(result);
+    }
+
+    async fn fetch_domain_stories(&self, site: &str, limit: u16) -> HnResult<Vec<StoryItem>> {
+        // The search matches URL words, so fetch extra hits and keep exact site matches
+        let hits_per_page: String = limit
+            .saturating_mul(3)
+            .clamp(50, MAX_SEARCH_HITS)
+            .to_string();
+        let request: RequestBuilder = self
+            .client
+            .get(format!("{}/search_by_date", self.search_url))
+            .query(&[
+                ("tags", "story"),
+                ("query", site),
+                ("restrictSearchableAttributes", "url"),
+                ("hitsPerPage", hits_per_page.as_str()),
+            ]);
+        let response: SearchResponse = self.get_json(request).await?;
+
+        let stories: Vec<StoryItem> = response
+            .hits
+            .into_iter()
+            .filter_map(SearchHit::into_story_item)
+            .filter(|story| {
+                story
+                    .domain()
+                    .is_some_and(|domain| site_matches(&domain, site))
+            })
+            .take(usize::from(limit))
+            .collect();
+
+        return Ok(stories);
+    }
+
+    async fn fetch_url_submissions(&self, url: &str) -> HnResult<Vec<StoryItem>> {
+        // The search matches URL words, so keep only the hits for the same page
+        let hits_per_page: String = MAX_URL_SEARCH_HITS.to_string();
+        let request: RequestBuilder = self
+            .client
+            .get(format!("{}/search", self.search_url))
+            .query(&[
+                ("tags", "story"),
+                ("query", url),
+                ("restrictSearchableAttributes", "url"),
+                ("hitsPerPage", hits_per_page.as_str()),
+            ]);
+        let response: SearchResponse = self.get_json(request).await?;
+
+        let mut stories: Vec<StoryItem> = response
+            .hits
+            .into_iter()
+            .filter_map(SearchHit::into_story_item)
+            .filter(|story| {
+                story
+                    .url
+                    .as_deref()
+                    .and_then(normalize_url)
+                    .is_some_and(|submitted| submitted == url)
+            })
+            .collect();
+        stories.sort_by_key(|story| Reverse(story.time));
+
+        return Ok(stories);
+    }
+
+    async fn fetch_article(&self, url: &str) -> HnResult<String> {
+        let request: RequestBuilder = self.client.get(url).header(USER_AGENT, HTTP_USER_AGENT);
+        let (headers, body): (HeaderMap, Bytes) = self.send(request).await?;
+        let result: String = decode_text(&headers, &body);
+
+        return Ok(result);
+    }
 }
 
 impl HackerNewsClient {
@@ -42,12 +246,35 @@ impl HackerNewsClient {
             ApiVersion::V0 => "v0",
         };
 
+        return Self::with_base_url(&format!(
+            "{}/{}",
+            HACKER_NEWS_API_BASE_URL, api_version_as_path
+        ));
+    }
+
+    /// Create a client for an API at a custom base URL (e.g. a mirror or a local mock)
+    pub fn with_base_url(base_url: &str) -> Self {
         return Self {
             client: Client::new(),
-            base_url: format!("{}/{}/", HACKER_NEWS_API_BASE_URL, api_version_as_path),
+            base_url: format!("{}/", base_url.trim_end_matches('/')),
+            search_url: HACKER_NEWS_SEARCH_API_BASE_URL.to_string(),
+            limiter: Arc::new(RateLimiter::new(RateLimitConfig::default())),
+            cache: None,
         };
     }
 
+    /// Share a rate limiter, e.g. to read its stats after the client is done
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.limiter = limiter;
+        return self;
+    }
+
+    /// Revalidate responses stored in a cache instead of always fetching them in full
+    pub fn with_cache(mut self, cache: Arc<HttpCache>) -> Self {
+        self.cache = Some(cache);
+        return self;
+    }
+
     /// Get story IDs for a given story type and limit
     async fn get_story_ids(&self, story_type: String, limit: u16) -> HnResult<Vec<u64>> {
         let url: String = format!(
@@ -55,34 +282,508 @@ impl HackerNewsClient {
             self.base_url, story_type, limit
         );
 
-        let result: Vec<u64> = self
-            .client
-            .get(url)
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?;
+        let request: RequestBuilder = self.client.get(url);
+        let result: Vec<u64> = self.get_json(request).await?;
 
         return Ok(result);
     }
 
     /// Get story items for a list of story IDs
     async fn get_story_items(&self, ids: Vec<u64>) -> HnResult<Vec<StoryItem>> {
-        let client: Client = self.client.clone();
-        let base_url: String = self.base_url.clone();
-
-        let result: Vec<StoryItem> = stream::iter(ids)
-            .map(|id: u64| {
-                let client = client.clone();
-                let url = format!("{base_url}item/{id}.json");
-                async move { client.get(url).send().await?.json::<StoryItem>().await }
+        let result: Vec<StoryItem> = self
+            .get_items(ids, STORY_ITEMS_CONCURRENCY)
+            .await
+            .into_iter()
+            .filter_map(|res| res.ok().flatten()) // Skip failed requests or null items
+            .collect();
+
+        return Ok(result);
+    }
+
+    /// Get items for a list of IDs with at most `concurrency` requests at once, keeping
+    /// the order of `ids`, with `None` for IDs without an item
+    async fn get_items(
+        &self,
+        ids: Vec<u64>,
+        concurrency: usize,
+    ) -> Vec<HnResult<Option<StoryItem>>> {
+        let result: Vec<HnResult<Option<StoryItem>>> = stream::iter(ids)
+            .map(|id: u64| async move {
+                let request: RequestBuilder =
+                    self.client.get(format!("{}item/{id}.json", self.base_url));
+                let item: Option<StoryItem> = self.get_json(request).await?;
+                return Ok(item);
             })
-            .buffer_unordered(5) // Concurrency limit
-            .filter_map(|res| async { res.ok() }) // Skip failed requests or null items
-            .collect::<Vec<_>>()
+            .buffered(concurrency.max(1))
+            .collect()
             .await;
 
+        return result;
+    }
+
+    /// Send a request and parse its JSON body
+    async fn get_json<T: DeserializeOwned>(&self, request: RequestBuilder) -> HnResult<T> {
+        let (_, body): (HeaderMap, Bytes) = self.send(request).await?;
+        let result: T = serde_json::from_slice(&body)?;
+
         return Ok(result);
     }
+
+    /// Send a request once the rate limit allows it and read its headers and body, retrying
+    /// rate-limited (429 or 503) responses after the server's `Retry-After` or an
+    /// exponential backoff
+    ///
+    /// GET requests to the APIs are cached: requests for cached responses are
+    /// conditional, and reuse the cached body when the server answers `304 Not Modified`.
+    async fn send(&self, request: RequestBuilder) -> HnResult<(HeaderMap, Bytes)> {
+        let mut request: Request = request.build()?;
+        let span: RequestSpan =
+            RequestSpan::start(request.method().as_str(), request.url().as_str());
+
+        // Responses are cached under the requested URL, even when they were redirected
+        let url: String = request.url().to_string();
+        let cacheable: bool = request.method() == Method::GET && self.is_api_request(&request);
+        if cacheable && url.starts_with(&self.base_url) {
+            request
+                .headers_mut()
+                .insert(FIREBASE_ETAG, HeaderValue::from_static("true"));
+        }
+        let cached: Option<CachedResponse> =
+            cacheable.then(|| self.cached_response(&url)).flatten();
+        if let Some(cached) = &cached {
+            let headers: &mut HeaderMap = request.headers_mut();
+            let validators = [
+                (IF_NONE_MATCH, &cached.etag),
+                (IF_MODIFIED_SINCE, &cached.last_modified),
+            ];
+            for (name, value) in validators {
+                if let Some(value) = value.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+                    headers.insert(name, value);
+                }
+            }
+        }
+
+        let mut attempt: u32 = 0;
+        loop {
+            let retry: Option<Request> = request.try_clone();
+            self.limiter.acquire().await;
+            let response: Response = self
+                .client
+                .execute(request)
+                .await
+                .inspect_err(|error| span.fail(error))?;
+
+            let status: StatusCode = response.status();
+            if status == StatusCode::NOT_MODIFIED
+                && let Some(cached) = cached
+            {
+                span.revalidated(cached.body.len());
+                self.touch_cached_response(&url);
+                return Ok((response.headers().clone(), cached.body));
+            }
+            if status != StatusCode::TOO_MANY_REQUESTS && status != StatusCode::SERVICE_UNAVAILABLE
+            {
+                let response: Response = response
+                    .error_for_status()
+                    .inspect_err(|error| span.fail(error))?;
+                let headers: HeaderMap = response.headers().clone();
+                let body: Bytes = response
+                    .bytes()
+                    .await
+                    .inspect_err(|error| span.fail(error))?;
+                span.finish(status.as_u16(), body.len());
+                if cacheable {
+                    self.store_response(&url, &headers, &body);
+                }
+
+                return Ok((headers, body));
+            }
+
+            let delay: Duration =
+                retry_after(&response).unwrap_or(RETRY_BACKOFF * 2_u32.pow(attempt));
+            self.limiter.record_rate_limited();
+            let Some(next) =
+                retry.filter(|_| attempt < self.limiter.max_retries() && delay <= MAX_RETRY_DELAY)
+            else {
+                let message: String = format!(
+                    "Rate limited by {} ({status}), try again in {} seconds",
+                    response.url().host_str().unwrap_or("the server"),
+                    delay.as_secs().max(1)
+                );
+                let error: AsyncSafeError = message.into();
+                span.fail(error.as_ref());
+                return Err(error);
+            };
+
+            attempt += 1;
+            span.retry(status.as_u16(), delay, attempt);
+            self.limiter.record_retry();
+            // Only the Hacker News APIs hold back every request, a slow article site
+            // just delays its own retry
+            if self.is_api_request(&next) {
+                self.limiter.pause(delay);
+            } else {
+                tokio::time::sleep(delay).await;
+            }
+            request = next;
+        }
+    }
+
+    /// Whether a request goes to the Hacker News or search API, rather than e.g. an article
+    fn is_api_request(&self, request: &Request) -> bool {
+        let url: &str = request.url().as_str();
+        return url.starts_with(&self.base_url) || url.starts_with(&self.search_url);
+    }
+
+    /// Cached response to revalidate a GET request with
+    fn cached_response(&self, url: &str) -> Option<CachedResponse> {
+        let cache: &HttpCache = self.cache.as_deref()?;
+
+        // A broken cache only costs a full fetch
+        return cache
+            .get(url)
+            .inspect_err(|error| log::warn!("Could not read the HTTP cache: {error}"))
+            .ok()
+            .flatten();
+    }
+
+    /// Cache a response that can be revalidated, i.e. one with an `ETag` or `Last-Modified`
+    fn store_response(&self, url: &str, headers: &HeaderMap, body: &Bytes) {
+        let Some(cache) = self.cache.as_deref() else {
+            return;
+        };
+        let header = |name| -> Option<String> {
+            return headers.get(name)?.to_str().ok().map(str::to_string);
+        };
+        let response: CachedResponse = CachedResponse {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            body: body.clone(),
+        };
+        if response.etag.is_none() && response.last_modified.is_none() {
+            return;
+        }
+
+        if let Err(error) = cache.put(url, &response) {
+            log::warn!("Could not write the HTTP cache: {error}");
+        }
+    }
+
+    /// Keep a cached response the server confirmed is unchanged from being pruned
+    fn touch_cached_response(&self, url: &str) {
+        if let Some(cache) = self.cache.as_deref()
+            && let Err(error) = cache.touch(url)
+        {
+            log::warn!("Could not write the HTTP cache: {error}");
+        }
+    }
+}
+
+/// Wait asked for by a response's `Retry-After` header, in seconds or as an HTTP date
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value: &str = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date: DateTime<Utc> = DateTime::parse_from_rfc2822(value).ok()?.to_utc();
+    return Some((date - Utc::now()).to_std().unwrap_or(Duration::ZERO));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use http_body_util::Full;
+    use hyper::Request;
+
+    use crate::utils::rate_limit::ThrottleStats;
+    use crate::utils::test_server;
+
+    /// Status code and `Retry-After` header of a mock response
+    type MockResponse = (u16, Option<&'static str>);
+
+    /// Version tag of the mock `maxitem.json`
+    const MAX_ITEM_ETAG: &str = "\"v42\"";
+    /// `42` compressed with gzip
+    const GZIPPED_MAX_ITEM: [u8; 22] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0x33, 0x31, 0x02, 0x00, 0x88,
+        0xb0, 0x24, 0x32, 0x02, 0x00, 0x00, 0x00,
+    ];
+
+    /// State of the stand-in API
+    #[derive(Default)]
+    struct MockServer {
+        /// Responses sent before the regular ones
+        queued: Vec<MockResponse>,
+        /// Requests answered with `304 Not Modified`
+        not_modified: usize,
+        /// Responses compressed with gzip
+        gzipped: usize,
+    }
+
+    /// Answer with the next queued response, or with `42` compressed when the client
+    /// accepts gzip and, like Firebase, tagged with an `ETag` only when asked for one.
+    /// `moved.json` redirects to `maxitem.json`, and articles (`post`) are always tagged.
+    fn handle(request: Request<Bytes>, state: &Mutex<MockServer>) -> hyper::Response<Full<Bytes>> {
+        let mut state = state.lock().unwrap();
+        let header = |name: &str| -> &str {
+            return request
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default();
+        };
+
+        let mut response = hyper::Response::new(Full::new(Bytes::new()));
+        if !state.queued.is_empty() {
+            let (status, retry_after) = state.queued.remove(0);
+            *response.status_mut() = hyper::StatusCode::from_u16(status).unwrap();
+            if let Some(retry_after) = retry_after {
+                response
+                    .headers_mut()
+                    .insert("retry-after", retry_after.parse().unwrap());
+            }
+            return response;
+        }
+
+        if request.uri().path().ends_with("/moved.json") {
+            *response.status_mut() = hyper::StatusCode::MOVED_PERMANENTLY;
+            response
+                .headers_mut()
+                .insert("location", "maxitem.json".parse().unwrap());
+            return response;
+        }
+
+        if header("if-none-match") == MAX_ITEM_ETAG {
+            state.not_modified += 1;
+            *response.status_mut() = hyper::StatusCode::NOT_MODIFIED;
+            return response;
+        }
+
+        if header(FIREBASE_ETAG) == "true" || request.uri().path().ends_with("/post") {
+            response
+                .headers_mut()
+                .insert("etag", MAX_ITEM_ETAG.parse().unwrap());
+        }
+        if header("accept-encoding").contains("gzip") {
+            state.gzipped += 1;
+            response
+                .headers_mut()
+                .insert("content-encoding", "gzip".parse().unwrap());
+            *response.body_mut() = Full::new(Bytes::from_static(&GZIPPED_MAX_ITEM));
+        } else {
+            *response.body_mut() = Full::new(Bytes::from_static(b"42"));
+        }
+
+        return response;
+    }
+
+    /// Start a stand-in for the API answering every path like `maxitem.json`,
+    /// returning its base URL
+    async fn spawn_server(state: Arc<Mutex<MockServer>>) -> String {
+        let base_url: String = test_server::spawn(move |request| handle(request, &state)).await;
+        return format!("{base_url}/v0");
+    }
+
+    /// Start a stand-in for the API sending the queued responses first
+    async fn spawn_queued_server(queued: Vec<MockResponse>) -> String {
+        return spawn_server(Arc::new(Mutex::new(MockServer {
+            queued,
+            ..Default::default()
+        })))
+        .await;
+    }
+
+    fn client(base_url: &str) -> (HackerNewsClient, Arc<RateLimiter>) {
+        let limiter: Arc<RateLimiter> = Arc::new(RateLimiter::new(RateLimitConfig::default()));
+        let client: HackerNewsClient =
+            HackerNewsClient::with_base_url(base_url).with_rate_limiter(Arc::clone(&limiter));
+        return (client, limiter);
+    }
+
+    #[tokio::test]
+    async fn test_send_retries_after_rate_limit() {
+        let base_url: String = spawn_queued_server(vec![(429, Some("0")), (503, Some("0"))]).await;
+        let (client, limiter) = client(&base_url);
+
+        let max_item_id: u64 = client.fetch_max_item_id().await.unwrap();
+
+        let stats: ThrottleStats = limiter.stats();
+        assert_eq!(max_item_id, 42);
+        assert_eq!(stats.requests, 3);
+        assert_eq!(stats.rate_limited, 2);
+        assert_eq!(stats.retries, 2);
+    }
+
+    #[tokio::test]
+    async fn test_send_fails_when_retry_is_too_far_away() {
+        let base_url: String = spawn_queued_server(vec![(429, Some("3600"))]).await;
+        let (client, limiter) = client(&base_url);
+
+        let error: String = client.fetch_max_item_id().await.unwrap_err().to_string();
+
+        assert!(error.starts_with("Rate limited by 127.0.0.1 (429 Too Many Requests)"));
+        assert!(error.ends_with("try again in 3600 seconds"));
+        assert_eq!(limiter.stats().retries, 0);
+
+        // Requests after the failure are not held back for the hour
+        let started: std::time::Instant = std::time::Instant::now();
+        assert_eq!(client.fetch_max_item_id().await.unwrap(), 42);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(limiter.stats().delayed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_article_rate_limit_does_not_pause_api_requests() {
+        let article_url: String = spawn_queued_server(vec![(429, Some("1"))]).await;
+        let api_url: String = spawn_queued_server(Vec::new()).await;
+        let (client, limiter) = client(&api_url);
+
+        let article = tokio::spawn({
+            let client: HackerNewsClient = client.clone();
+            async move { client.fetch_article(&format!("{article_url}/post")).await }
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let started: std::time::Instant = std::time::Instant::now();
+        assert_eq!(client.fetch_max_item_id().await.unwrap(), 42);
+        assert!(started.elapsed() < Duration::from_millis(500));
+
+        assert_eq!(article.await.unwrap().unwrap(), "42");
+        assert_eq!(limiter.stats().delayed, 0);
+        assert_eq!(limiter.stats().retries, 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_gives_up_after_max_retries() {
+        let base_url: String = spawn_queued_server(vec![(429, Some("0")); 4]).await;
+        let (client, limiter) = client(&base_url);
+
+        let result: HnResult<u64> = client.fetch_max_item_id().await;
+
+        assert!(result.is_err());
+        assert_eq!(limiter.stats().retries, 3);
+    }
+
+    #[tokio::test]
+    async fn test_send_revalidates_cached_responses() {
+        let state: Arc<Mutex<MockServer>> = Arc::default();
+        let base_url: String = spawn_server(Arc::clone(&state)).await;
+        let directory = tempfile::tempdir().unwrap();
+        let cache: Arc<HttpCache> =
+            Arc::new(HttpCache::open(&directory.path().join("cache.db")).unwrap());
+        let (client, _) = client(&base_url);
+        let client: HackerNewsClient = client.with_cache(Arc::clone(&cache));
+
+        assert_eq!(client.fetch_max_item_id().await.unwrap(), 42);
+        assert_eq!(state.lock().unwrap().not_modified, 0);
+
+        // The second run only gets a 304 and reuses the decompressed cached body
+        assert_eq!(client.fetch_max_item_id().await.unwrap(), 42);
+        let cached: CachedResponse = cache
+            .get(&format!("{base_url}/maxitem.json"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.etag.as_deref(), Some(MAX_ITEM_ETAG));
+        assert_eq!(cached.body, Bytes::from_static(b"42"));
+
+        let state = state.lock().unwrap();
+        assert_eq!(state.not_modified, 1);
+        assert_eq!(state.gzipped, 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_without_cache_fetches_in_full() {
+        let state: Arc<Mutex<MockServer>> = Arc::default();
+        let base_url: String = spawn_server(Arc::clone(&state)).await;
+        let (client, _) = client(&base_url);
+
+        assert_eq!(client.fetch_max_item_id().await.unwrap(), 42);
+        assert_eq!(client.fetch_max_item_id().await.unwrap(), 42);
+
+        let state = state.lock().unwrap();
+        assert_eq!(state.not_modified, 0);
+        assert_eq!(state.gzipped, 2);
+    }
+
+    #[tokio::test]
+    async fn test_send_caches_redirected_responses_under_the_requested_url() {
+        let state: Arc<Mutex<MockServer>> = Arc::default();
+        let base_url: String = spawn_server(Arc::clone(&state)).await;
+        let directory = tempfile::tempdir().unwrap();
+        let cache: Arc<HttpCache> =
+            Arc::new(HttpCache::open(&directory.path().join("cache.db")).unwrap());
+        let (client, _) = client(&base_url);
+        let client: HackerNewsClient = client.with_cache(Arc::clone(&cache));
+        let moved_url: String = format!("{base_url}/moved.json");
+
+        for _ in 0..2 {
+            let max_item_id: u64 = client
+                .get_json(client.client.get(&moved_url))
+                .await
+                .unwrap();
+            assert_eq!(max_item_id, 42);
+        }
+
+        assert!(cache.get(&moved_url).unwrap().is_some());
+        assert_eq!(
+            cache.get(&format!("{base_url}/maxitem.json")).unwrap(),
+            None
+        );
+        assert_eq!(state.lock().unwrap().not_modified, 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_does_not_cache_articles() {
+        let state: Arc<Mutex<MockServer>> = Arc::default();
+        let article_url: String = format!("{}/post", spawn_server(Arc::clone(&state)).await);
+        let directory = tempfile::tempdir().unwrap();
+        let cache: Arc<HttpCache> =
+            Arc::new(HttpCache::open(&directory.path().join("cache.db")).unwrap());
+        let (client, _) = client("http://127.0.0.1:9/v0");
+        let client: HackerNewsClient = client.with_cache(Arc::clone(&cache));
+
+        assert_eq!(client.fetch_article(&article_url).await.unwrap(), "42");
+        assert_eq!(client.fetch_article(&article_url).await.unwrap(), "42");
+
+        assert_eq!(cache.get(&article_url).unwrap(), None);
+        assert_eq!(state.lock().unwrap().not_modified, 0);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_article_decodes_content_type_charset() {
+        let base_url: String = test_server::spawn(|request| {
+            let (content_type, body): (&str, &'static [u8]) = match request.uri().path() {
+                // "Café" in ISO-8859-1 and "日本" in Shift_JIS
+                "/latin1" => ("text/html; charset=ISO-8859-1", b"Caf\xe9"),
+                "/sjis" => ("text/html; charset=\"Shift_JIS\"", b"\x93\xfa\x96\x7b"),
+                _ => ("text/html", "Café".as_bytes()),
+            };
+            let mut response = hyper::Response::new(Full::new(Bytes::from_static(body)));
+            response
+                .headers_mut()
+                .insert("content-type", content_type.parse().unwrap());
+            return response;
+        })
+        .await;
+        let (client, _) = client("http://127.0.0.1:9/v0");
+
+        let latin1: String = client
+            .fetch_article(&format!("{base_url}/latin1"))
+            .await
+            .unwrap();
+        let sjis: String = client
+            .fetch_article(&format!("{base_url}/sjis"))
+            .await
+            .unwrap();
+        let utf8: String = client
+            .fetch_article(&format!("{base_url}/utf8"))
+            .await
+            .unwrap();
+        assert_eq!(latin1, "Café");
+        assert_eq!(sjis, "日本");
+        assert_eq!(utf8, "Café");
+    }
 }
@@ -0,0 +1,93 @@
+use clap::Args as ClapArgs;
+use dialoguer::{Input, Password};
+
+use crate::account::AccountConfig;
+use crate::account::client::AccountClient;
+use crate::account::session::{Session, SessionStore};
+use crate::types::HnResult;
+use crate::ui::theme;
+use crate::utils::helpers::{clear_loading, show_loading};
+use crate::{print_success, print_warn};
+
+/// Arguments for logging in to a Hacker News account
+#[derive(ClapArgs, Debug)]
+pub struct LoginArgs {
+    /// Account to log in to [default: `username` from the config file, or asked for]
+    #[arg(short = 'u', long = "username")]
+    username: Option<String>,
+}
+
+impl LoginArgs {
+    /// Log in and save the session for the story list's account actions
+    ///
+    /// The password comes from the config file's `password_command` or `password` when
+    /// logging in as its `username`, and is asked for otherwise.
+    pub async fn run(
+        self,
+        config: &AccountConfig,
+        store: &SessionStore,
+        base_url: &str,
+    ) -> HnResult<()> {
+        let username: String = match self.username.or_else(|| config.username.clone()) {
+            Some(username) => username,
+            None => Input::with_theme(&theme::current().dialoguer())
+                .with_prompt("Username")
+                .interact_text()?,
+        };
+        let password: String = match config.password_for(&username).await? {
+            Some(password) => password,
+            None => Password::with_theme(&theme::current().dialoguer())
+                .with_prompt("Password")
+                .interact()?,
+        };
+
+        show_loading(Some("Logging in...please wait"));
+        let result: HnResult<Session> = AccountClient::login(base_url, &username, &password).await;
+        clear_loading();
+
+        store.save(&result?)?;
+        print_success!("Logged in as {username}");
+
+        return Ok(());
+    }
+}
+
+/// Arguments for logging out of the Hacker News account
+#[derive(ClapArgs, Debug)]
+pub struct LogoutArgs {}
+
+impl LogoutArgs {
+    /// Forget the saved session
+    pub fn run(self, store: &SessionStore) -> HnResult<()> {
+        if store.clear()? {
+            print_success!("Logged out");
+        } else {
+            print_warn!("Not logged in");
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logout_forgets_session() {
+        let directory = tempfile::tempdir().unwrap();
+        let store: SessionStore = SessionStore::new(directory.path().join("session.json"));
+        store
+            .save(&Session {
+                username: "alice".to_string(),
+                cookie: "user=alice&token".to_string(),
+            })
+            .unwrap();
+
+        LogoutArgs {}.run(&store).unwrap();
+        assert_eq!(store.load().unwrap(), None);
+
+        // Logging out twice is not an error
+        assert!(LogoutArgs {}.run(&store).is_ok());
+    }
+}
@@ -0,0 +1,158 @@
+use clap::Args as ClapArgs;
+
+use crate::api::HackerNewsGateway;
+use crate::archive::store::ArchiveStore;
+use crate::archive::{CrawlDirection, CrawlOptions, CrawlSummary, crawl};
+use crate::types::HnResult;
+use crate::utils::helpers::{clear_loading, show_loading};
+use crate::{print_info, print_success};
+
+/// The most concurrent item requests while crawling
+const MAX_CRAWL_CONCURRENCY: usize = 32;
+
+/// Arguments for mirroring items into the local archive
+#[derive(ClapArgs, Debug)]
+pub struct ArchiveArgs {
+    /// Crawl backward from the oldest archived (or newest) item, or forward from the newest archived item
+    #[arg(
+        short = 'd',
+        long = "direction",
+        value_enum,
+        default_value = "backward"
+    )]
+    direction: CrawlDirection,
+
+    /// The number of item IDs to walk in this run (defaults to all)
+    #[arg(short = 'l', long = "limit")]
+    limit: Option<u64>,
+
+    /// The number of items fetched at once
+    #[arg(short = 'c', long = "concurrency", default_value = "8")]
+    concurrency: usize,
+}
+
+impl ArchiveArgs {
+    /// Crawl items from the last checkpoint into the archive database
+    pub async fn run<G>(self, gateway: G, store: &mut ArchiveStore) -> HnResult<()>
+    where
+        G: HackerNewsGateway,
+    {
+        if self.concurrency == 0 || self.concurrency > MAX_CRAWL_CONCURRENCY {
+            return Err(
+                format!("Concurrency must be between 1 and {MAX_CRAWL_CONCURRENCY}").into(),
+            );
+        }
+
+        let options: CrawlOptions = CrawlOptions {
+            direction: self.direction,
+            limit: self.limit,
+            concurrency: self.concurrency,
+        };
+
+        show_loading(Some("Fetching the newest item ID...please wait"));
+        let result: HnResult<CrawlSummary> = crawl(&gateway, store, options, |summary| {
+            clear_loading();
+            show_loading(Some(&format!(
+                "Archived {} items from {} IDs...press Ctrl-C to stop and resume later",
+                summary.archived, summary.walked
+            )));
+        })
+        .await;
+        clear_loading();
+
+        let summary: CrawlSummary = result?;
+        let Some(range) = summary.range else {
+            print_info!("No items to archive");
+            return Ok(());
+        };
+
+        if summary.walked == 0 {
+            print_info!(
+                "Nothing left to archive, the archive holds {} items from IDs {} to {}",
+                store.count()?,
+                range.low,
+                range.high
+            );
+        } else {
+            print_success!(
+                "Archived {} items from {} IDs, the archive now holds {} items from IDs {} to {} of {}",
+                summary.archived,
+                summary.walked,
+                store.count()?,
+                range.low,
+                range.high,
+                summary.max_item_id
+            );
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::MockHackerNewsGateway;
+    use crate::archive::store::ArchiveRange;
+    use crate::models::story::StoryItem;
+
+    #[tokio::test]
+    async fn test_run_crawls_into_store() {
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_max_item_id()
+            .times(1)
+            .returning(|| Ok(100));
+        mock_gateway
+            .expect_fetch_items()
+            .withf(|ids, concurrency| ids == [100, 99] && *concurrency == 2)
+            .times(1)
+            .returning(|ids, _| {
+                Ok(ids
+                    .iter()
+                    .map(|id| StoryItem {
+                        id: *id,
+                        ..Default::default()
+                    })
+                    .collect())
+            });
+
+        let directory = tempfile::tempdir().unwrap();
+        let mut store: ArchiveStore =
+            ArchiveStore::open(&directory.path().join("archive.db")).unwrap();
+
+        let args: ArchiveArgs = ArchiveArgs {
+            direction: CrawlDirection::Backward,
+            limit: Some(2),
+            concurrency: 2,
+        };
+
+        let result: HnResult<()> = args.run(mock_gateway, &mut store).await;
+
+        assert!(result.is_ok(), "Error: {:?}", result.err());
+        assert_eq!(store.count().unwrap(), 2);
+        assert_eq!(
+            store.range().unwrap(),
+            Some(ArchiveRange { low: 99, high: 100 })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_invalid_options() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut store: ArchiveStore =
+            ArchiveStore::open(&directory.path().join("archive.db")).unwrap();
+
+        let args: ArchiveArgs = ArchiveArgs {
+            direction: CrawlDirection::Forward,
+            limit: None,
+            concurrency: MAX_CRAWL_CONCURRENCY + 1,
+        };
+        let error: String = args
+            .run(MockHackerNewsGateway::new(), &mut store)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Concurrency must be between"));
+    }
+}
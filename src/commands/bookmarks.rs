@@ -0,0 +1,110 @@
+use clap::Args as ClapArgs;
+use clap_complete::engine::ArgValueCandidates;
+
+use crate::bookmarks::BookmarkStore;
+use crate::commands::completions::bookmark_candidates;
+use crate::models::story::StoryItem;
+use crate::types::HnResult;
+use crate::ui::UserInterface;
+use crate::{print_success, print_warn};
+
+/// Arguments for browsing or removing bookmarked stories
+#[derive(ClapArgs, Debug)]
+pub struct BookmarksArgs {
+    /// Remove the bookmark of a story ID instead of listing bookmarks (repeatable)
+    #[arg(
+        short = 'r',
+        long = "remove",
+        value_name = "ID",
+        add = ArgValueCandidates::new(bookmark_candidates)
+    )]
+    remove: Vec<u64>,
+}
+
+impl BookmarksArgs {
+    /// Browse the bookmarked stories in the terminal UI, newest first, or remove bookmarks
+    pub async fn run<U: UserInterface>(self, ui: U, store: &BookmarkStore) -> HnResult<()> {
+        if !self.remove.is_empty() {
+            let removed: usize = store.remove(&self.remove)?;
+            if removed == 0 {
+                print_warn!("No bookmarks found for the given story IDs");
+            } else {
+                print_success!("Removed {removed} bookmark(s)");
+            }
+            return Ok(());
+        }
+
+        let stories: Vec<StoryItem> = store
+            .load()?
+            .into_iter()
+            .map(|bookmark| bookmark.item)
+            .collect();
+
+        if stories.is_empty() {
+            print_warn!("No bookmarks yet, bookmark stories from the story list");
+            return Ok(());
+        }
+
+        ui.render_stories_list(stories, "Bookmarks").await?;
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    use crate::ui::MockUserInterface;
+
+    fn store_with_stories(directory: &tempfile::TempDir) -> BookmarkStore {
+        let store: BookmarkStore = BookmarkStore::new(directory.path().join("bookmarks.json"));
+        let stories: Vec<StoryItem> = (1..=2)
+            .map(|id| StoryItem {
+                id,
+                ..Default::default()
+            })
+            .collect();
+        store.add(&stories, Utc::now()).unwrap();
+
+        return store;
+    }
+
+    #[tokio::test]
+    async fn test_run_lists_bookmarks() {
+        let directory = tempfile::tempdir().unwrap();
+        let store: BookmarkStore = store_with_stories(&directory);
+
+        let mut mock_ui = MockUserInterface::new();
+        mock_ui
+            .expect_render_stories_list()
+            .withf(|stories, list_name| stories.len() == 2 && list_name == "Bookmarks")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let args: BookmarksArgs = BookmarksArgs { remove: Vec::new() };
+
+        let result: HnResult<()> = args.run(mock_ui, &store).await;
+
+        assert!(result.is_ok(), "Error: {:?}", result.err());
+    }
+
+    #[tokio::test]
+    async fn test_run_removes_bookmarks() {
+        let directory = tempfile::tempdir().unwrap();
+        let store: BookmarkStore = store_with_stories(&directory);
+
+        // The list is not shown when removing
+        let args: BookmarksArgs = BookmarksArgs { remove: vec![2] };
+        args.run(MockUserInterface::new(), &store).await.unwrap();
+
+        let ids: Vec<u64> = store
+            .load()
+            .unwrap()
+            .iter()
+            .map(|bookmark| bookmark.item.id)
+            .collect();
+        assert_eq!(ids, vec![1]);
+    }
+}
@@ -0,0 +1,296 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use clap::{Args as ClapArgs, Command as ClapCommand, ValueEnum};
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::{EnvCompleter, Shells};
+use clap_mangen::Man;
+
+use crate::bookmarks::{Bookmark, BookmarkStore};
+use crate::config::Config;
+use crate::history::Snapshot;
+use crate::history::store::SnapshotStore;
+use crate::models::story::HackerNewsStoryType;
+use crate::print_success;
+use crate::types::HnResult;
+use crate::ui::theme::BUILTIN_THEMES;
+
+/// Name of the installed binary
+pub const BIN_NAME: &str = env!("CARGO_PKG_NAME");
+/// Environment variable set by the shell scripts when asking the binary for completions
+pub const COMPLETE_ENV: &str = "COMPLETE";
+
+/// Shells completion scripts can be generated for
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum CompletionShell {
+    Bash,
+    Elvish,
+    Fish,
+    Powershell,
+    Zsh,
+}
+
+/// Arguments for generating shell completions
+#[derive(ClapArgs, Debug)]
+pub struct CompletionsArgs {
+    /// Shell to generate the completion script for
+    #[arg(ignore_case = true)]
+    shell: CompletionShell,
+}
+
+impl CompletionsArgs {
+    /// Print the completion script for the shell
+    ///
+    /// The script calls back into `hn-stories` on every completion, so values such as
+    /// story IDs from snapshots are always current.
+    pub fn run(self) -> HnResult<()> {
+        return write_completion_script(self.shell, &mut io::stdout());
+    }
+}
+
+/// Write the completion script for a shell
+fn write_completion_script(shell: CompletionShell, output: &mut dyn io::Write) -> HnResult<()> {
+    let name: &str = match shell {
+        CompletionShell::Bash => "bash",
+        CompletionShell::Elvish => "elvish",
+        CompletionShell::Fish => "fish",
+        CompletionShell::Powershell => "powershell",
+        CompletionShell::Zsh => "zsh",
+    };
+
+    let shells: Shells = Shells::builtins();
+    let completer: &dyn EnvCompleter = shells
+        .completer(name)
+        .ok_or_else(|| format!("Completions are not supported for {name}"))?;
+    completer.write_registration(COMPLETE_ENV, BIN_NAME, BIN_NAME, BIN_NAME, output)?;
+
+    return Ok(());
+}
+
+/// Arguments for generating man pages
+#[derive(ClapArgs, Debug)]
+pub struct ManArgs {
+    /// Write a page for every command to this directory instead of printing the main page
+    #[arg(short = 'o', long = "output-dir")]
+    output_dir: Option<PathBuf>,
+}
+
+impl ManArgs {
+    /// Print the man page, or write one per command to the output directory
+    pub fn run(self, command: ClapCommand) -> HnResult<()> {
+        match self.output_dir {
+            Some(directory) => {
+                fs::create_dir_all(&directory)?;
+                clap_mangen::generate_to(command, &directory)?;
+                print_success!("Wrote man pages to {}", directory.display());
+            }
+            None => Man::new(command).render(&mut io::stdout())?,
+        }
+
+        return Ok(());
+    }
+}
+
+/// Complete story type names (e.g. `top`) rather than their one-letter values
+pub fn story_type_candidates() -> Vec<CompletionCandidate> {
+    return HackerNewsStoryType::value_variants()
+        .iter()
+        .map(|story_type| {
+            let name: String = story_type.full_name().to_lowercase();
+            return CompletionCandidate::new(name)
+                .help(Some(format!("{} stories", story_type.full_name()).into()));
+        })
+        .collect();
+}
+
+/// Complete built-in theme names and the themes defined in the config file
+pub fn theme_candidates() -> Vec<CompletionCandidate> {
+    let mut names: Vec<String> = BUILTIN_THEMES.iter().map(|name| name.to_string()).collect();
+
+    // A broken config file should not break completion
+    if let Ok(config) = Config::load() {
+        names.extend(config.themes.into_keys());
+    }
+
+    names.sort_unstable();
+    names.dedup();
+
+    return names.into_iter().map(CompletionCandidate::new).collect();
+}
+
+/// Complete story IDs of bookmarked stories, with their titles
+pub fn bookmark_candidates() -> Vec<CompletionCandidate> {
+    // Completion must never fail loudly, so a missing or corrupt store has no candidates
+    let bookmarks: Vec<Bookmark> = BookmarkStore::open_default()
+        .and_then(|store| store.load())
+        .unwrap_or_default();
+
+    return bookmarks
+        .iter()
+        .map(|bookmark| {
+            let title: &str = bookmark.item.title.as_deref().unwrap_or("No Title");
+            return CompletionCandidate::new(bookmark.item.id.to_string())
+                .help(Some(title.to_string().into()));
+        })
+        .collect();
+}
+
+/// Complete story IDs from the latest snapshot of each story type, with their titles
+pub fn snapshot_story_candidates() -> Vec<CompletionCandidate> {
+    let Ok(store) = SnapshotStore::open_default() else {
+        return Vec::new();
+    };
+
+    return latest_snapshot_candidates(&store);
+}
+
+/// Complete story IDs from the latest snapshot of each story type in a store
+fn latest_snapshot_candidates(store: &SnapshotStore) -> Vec<CompletionCandidate> {
+    // Completion must never fail loudly, so a missing or corrupt store has no candidates
+    let snapshots: Vec<Snapshot> = store.load_all().unwrap_or_default();
+
+    let mut seen: HashSet<u64> = HashSet::new();
+    let mut candidates: Vec<CompletionCandidate> = Vec::new();
+    for story_type in HackerNewsStoryType::value_variants() {
+        let Some(latest) = snapshots
+            .iter()
+            .rev()
+            .find(|snapshot| snapshot.story_type == *story_type)
+        else {
+            continue;
+        };
+
+        for entry in &latest.entries {
+            if seen.insert(entry.item.id) {
+                let title: &str = entry.item.title.as_deref().unwrap_or("No Title");
+                candidates.push(
+                    CompletionCandidate::new(entry.item.id.to_string())
+                        .help(Some(title.to_string().into())),
+                );
+            }
+        }
+    }
+
+    return candidates;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+
+    use chrono::{TimeZone, Utc};
+    use clap::CommandFactory;
+
+    use crate::args::Args;
+    use crate::models::story::StoryItem;
+
+    /// Complete the last word of a command line
+    fn complete(line: &[&str]) -> Vec<String> {
+        let args: Vec<OsString> = line.iter().map(OsString::from).collect();
+        let candidates: Vec<CompletionCandidate> =
+            clap_complete::engine::complete(&mut Args::command(), args, line.len() - 1, None)
+                .unwrap();
+
+        return candidates
+            .iter()
+            .map(|candidate| candidate.get_value().to_string_lossy().into_owned())
+            .collect();
+    }
+
+    #[test]
+    fn test_complete_story_types() {
+        let expected: Vec<&str> = vec!["new", "top", "best", "ask", "show", "jobs"];
+
+        assert_eq!(complete(&["hn-stories", "-s", ""]), expected);
+        assert_eq!(complete(&["hn-stories", "export", "--story", ""]), expected);
+        assert_eq!(complete(&["hn-stories", "-s", "sh"]), vec!["show"]);
+    }
+
+    #[test]
+    fn test_complete_subcommands() {
+        let candidates: Vec<String> = complete(&["hn-stories", "comp"]);
+
+        assert_eq!(candidates, vec!["completions"]);
+    }
+
+    #[test]
+    fn test_latest_snapshot_candidates() {
+        let directory = tempfile::tempdir().unwrap();
+        let store: SnapshotStore = SnapshotStore::new(directory.path().join("snapshots.jsonl"));
+        let story = |id: u64, title: &str| StoryItem {
+            id,
+            title: Some(title.to_string()),
+            ..Default::default()
+        };
+
+        let taken_at = |hour: u32| Utc.with_ymd_and_hms(2026, 10, 19, hour, 0, 0).unwrap();
+        for snapshot in [
+            Snapshot::new(taken_at(8), HackerNewsStoryType::T, vec![story(1, "Old")]),
+            Snapshot::new(taken_at(9), HackerNewsStoryType::T, vec![story(2, "Newer")]),
+            Snapshot::new(
+                taken_at(9),
+                HackerNewsStoryType::S,
+                vec![story(3, "Show HN"), story(2, "Newer")],
+            ),
+        ] {
+            store.append(&snapshot).unwrap();
+        }
+
+        let candidates: Vec<(String, String)> = latest_snapshot_candidates(&store)
+            .iter()
+            .map(|candidate| {
+                (
+                    candidate.get_value().to_string_lossy().into_owned(),
+                    candidate
+                        .get_help()
+                        .map(|help| help.to_string())
+                        .unwrap_or_default(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            candidates,
+            vec![
+                ("2".to_string(), "Newer".to_string()),
+                ("3".to_string(), "Show HN".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_completion_script() {
+        for shell in CompletionShell::value_variants() {
+            let mut script: Vec<u8> = Vec::new();
+            write_completion_script(*shell, &mut script).unwrap();
+            let script: String = String::from_utf8(script).unwrap();
+
+            assert!(script.contains(BIN_NAME), "{shell:?}");
+            assert!(script.contains(COMPLETE_ENV), "{shell:?}");
+        }
+    }
+
+    #[test]
+    fn test_man_pages() {
+        let directory = tempfile::tempdir().unwrap();
+        let args: ManArgs = ManArgs {
+            output_dir: Some(directory.path().join("man")),
+        };
+
+        args.run(Args::command()).unwrap();
+
+        let page: String =
+            fs::read_to_string(directory.path().join("man").join("hn-stories.1")).unwrap();
+        assert!(page.contains(".TH hn-stories 1"));
+        assert!(
+            directory
+                .path()
+                .join("man")
+                .join("hn-stories-export.1")
+                .exists()
+        );
+    }
+}
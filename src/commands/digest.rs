@@ -0,0 +1,186 @@
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::Utc;
+use clap::Args as ClapArgs;
+use clap_complete::engine::ArgValueCandidates;
+
+use crate::api::HackerNewsGateway;
+use crate::commands::completions::story_type_candidates;
+use crate::commands::filter::StoryFilterArgs;
+use crate::digest::{DigestContext, DigestFormat, render_digest};
+use crate::models::story::{HackerNewsStoryType, StoryItem};
+use crate::print_success;
+use crate::types::HnResult;
+
+/// Arguments for generating a static HTML or Markdown digest
+#[derive(ClapArgs, Debug)]
+pub struct DigestArgs {
+    /// Story type (top, new, best, ask, show, job)
+    #[arg(short = 's', long = "story", ignore_case = true, default_value = "t", add = ArgValueCandidates::new(story_type_candidates))]
+    story: HackerNewsStoryType,
+
+    /// The number of stories to fetch before filtering
+    #[arg(short = 'l', long = "limit", default_value = "30")]
+    limit: u16,
+
+    /// Digest format (html, markdown)
+    #[arg(
+        short = 'f',
+        long = "format",
+        ignore_case = true,
+        default_value = "html"
+    )]
+    format: DigestFormat,
+
+    /// Jinja2 template file used instead of the built-in template
+    #[arg(short = 't', long = "template")]
+    template: Option<PathBuf>,
+
+    /// Digest title (defaults to "Hacker News <type> stories")
+    #[arg(long = "title")]
+    title: Option<String>,
+
+    /// Write the digest to this file instead of stdout
+    #[arg(short = 'o', long = "output")]
+    output: Option<PathBuf>,
+
+    #[command(flatten)]
+    filter: StoryFilterArgs,
+}
+
+impl DigestArgs {
+    /// Fetch, filter and render the story list as a digest document
+    pub async fn run<G>(self, gateway: G) -> HnResult<()>
+    where
+        G: HackerNewsGateway,
+    {
+        let max_limit: u16 = self.story.max_stories_limit();
+        if self.limit > max_limit {
+            return Err(format!(
+                "Max story limit for '{}' stories is {}",
+                self.story.full_name(),
+                max_limit
+            )
+            .into());
+        }
+
+        // Read the template first so a bad path fails before any requests
+        let template: Option<String> = match &self.template {
+            Some(path) => Some(fs::read_to_string(path).map_err(|error| {
+                format!("Failed to read template '{}': {error}", path.display())
+            })?),
+            None => None,
+        };
+
+        let stories: Vec<StoryItem> = self
+            .filter
+            .apply(gateway.fetch_stories(&self.story, self.limit).await?);
+
+        let title: String = self
+            .title
+            .unwrap_or_else(|| format!("Hacker News {} stories", self.story.full_name()));
+        let context: DigestContext =
+            DigestContext::new(title, self.story.full_name(), Utc::now(), &stories);
+        let document: String = render_digest(self.format, template.as_deref(), &context)?;
+
+        match &self.output {
+            Some(path) => {
+                fs::write(path, document)?;
+                print_success!(
+                    "Wrote digest of {} {} stories to {}",
+                    stories.len(),
+                    self.story.full_name(),
+                    path.display()
+                );
+            }
+            None => print!("{document}"),
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::MockHackerNewsGateway;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct FilterWrapper {
+        #[command(flatten)]
+        filter: StoryFilterArgs,
+    }
+
+    fn args(template: Option<PathBuf>, output: PathBuf) -> DigestArgs {
+        return DigestArgs {
+            story: HackerNewsStoryType::T,
+            limit: 30,
+            format: DigestFormat::Markdown,
+            template,
+            title: Some("Morning digest".to_string()),
+            output: Some(output),
+            filter: FilterWrapper::parse_from(["digest", "--min-score", "100"]).filter,
+        };
+    }
+
+    #[tokio::test]
+    async fn test_run_writes_filtered_digest() {
+        let directory = tempfile::tempdir().unwrap();
+        let template: PathBuf = directory.path().join("digest.md.j2");
+        let output: PathBuf = directory.path().join("digest.md");
+        fs::write(
+            &template,
+            "{{ title }}{% for story in stories %} #{{ story.id }}{% endfor %}",
+        )
+        .unwrap();
+
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_stories()
+            .times(1)
+            .returning(|_, _| {
+                Ok(vec![
+                    StoryItem {
+                        id: 1,
+                        score: Some(500),
+                        ..Default::default()
+                    },
+                    StoryItem {
+                        id: 2,
+                        score: Some(3),
+                        ..Default::default()
+                    },
+                ])
+            });
+
+        args(Some(template), output.clone())
+            .run(mock_gateway)
+            .await
+            .unwrap();
+
+        assert_eq!(fs::read_to_string(output).unwrap(), "Morning digest #1");
+    }
+
+    #[tokio::test]
+    async fn test_run_missing_template() {
+        let directory = tempfile::tempdir().unwrap();
+
+        // The gateway must not be called when the template cannot be read
+        let mock_gateway = MockHackerNewsGateway::new();
+        let result: HnResult<()> = args(
+            Some(directory.path().join("missing.j2")),
+            directory.path().join("digest.md"),
+        )
+        .run(mock_gateway)
+        .await;
+
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Failed to read template")
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use clap::Args as ClapArgs;
+
+use crate::api::HackerNewsGateway;
+use crate::models::domain::normalize_site;
+use crate::models::story::StoryItem;
+use crate::print_warn;
+use crate::types::HnResult;
+use crate::ui::UserInterface;
+use crate::utils::helpers::{clear_loading, show_loading};
+
+/// The most stories the search backend returns for a site
+const MAX_DOMAIN_STORIES_LIMIT: u16 = 1000;
+
+/// Arguments for listing recent stories from a site
+#[derive(ClapArgs, Debug)]
+pub struct DomainArgs {
+    /// Site to list stories from, e.g. example.com or github.com/rust-lang
+    name: String,
+
+    /// The number of stories to fetch and display in the terminal UI
+    #[arg(short = 'l', long = "limit", default_value = "30")]
+    limit: u16,
+}
+
+impl DomainArgs {
+    /// Search for the site's most recent stories and browse them in the terminal UI
+    pub async fn run<G, U>(self, gateway: G, ui: U) -> HnResult<()>
+    where
+        G: HackerNewsGateway,
+        U: UserInterface,
+    {
+        if self.limit > MAX_DOMAIN_STORIES_LIMIT {
+            return Err(
+                format!("Max story limit for a site is {}", MAX_DOMAIN_STORIES_LIMIT).into(),
+            );
+        }
+
+        let site: String =
+            normalize_site(&self.name).ok_or_else(|| format!("Invalid site: {}", self.name))?;
+
+        show_loading(Some(&format!(
+            "Searching stories from {site}...please wait"
+        )));
+
+        let result: HnResult<Vec<StoryItem>> =
+            gateway.fetch_domain_stories(&site, self.limit).await;
+
+        clear_loading();
+
+        let stories: Vec<StoryItem> = result?;
+
+        if stories.is_empty() {
+            print_warn!("No stories found from {}", site);
+            return Ok(());
+        }
+
+        ui.render_stories_list(stories, &site).await?;
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::MockHackerNewsGateway;
+    use crate::ui::MockUserInterface;
+
+    #[tokio::test]
+    async fn test_run_normalizes_site() {
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_domain_stories()
+            .withf(|site, limit| site == "github.com/rust-lang" && *limit == 10)
+            .times(1)
+            .returning(|_, _| {
+                Ok(vec![StoryItem {
+                    id: 1,
+                    url: Some("https://github.com/rust-lang/rust".to_string()),
+                    ..Default::default()
+                }])
+            });
+
+        let mut mock_ui = MockUserInterface::new();
+        mock_ui
+            .expect_render_stories_list()
+            .withf(|stories, list_name| stories.len() == 1 && list_name == "github.com/rust-lang")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let args: DomainArgs = DomainArgs {
+            name: "https://www.github.com/Rust-Lang/rust/pulls".to_string(),
+            limit: 10,
+        };
+
+        let result: HnResult<()> = args.run(mock_gateway, mock_ui).await;
+
+        assert!(result.is_ok(), "Error: {:?}", result.err());
+    }
+
+    #[tokio::test]
+    async fn test_run_no_stories() {
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_domain_stories()
+            .times(1)
+            .returning(|_, _| Ok(Vec::new()));
+
+        // The list is not shown when the search finds nothing
+        let mock_ui = MockUserInterface::new();
+
+        let args: DomainArgs = DomainArgs {
+            name: "example.com".to_string(),
+            limit: 30,
+        };
+
+        assert!(args.run(mock_gateway, mock_ui).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_run_invalid_limit() {
+        let args: DomainArgs = DomainArgs {
+            name: "example.com".to_string(),
+            limit: MAX_DOMAIN_STORIES_LIMIT + 1,
+        };
+
+        let result: HnResult<()> = args
+            .run(MockHackerNewsGateway::new(), MockUserInterface::new())
+            .await;
+
+        assert!(result.unwrap_err().to_string().contains("Max story limit"));
+    }
+}
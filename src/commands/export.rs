@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::Utc;
+use clap::Args as ClapArgs;
+use clap_complete::engine::ArgValueCandidates;
+
+use crate::api::HackerNewsGateway;
+use crate::commands::completions::story_type_candidates;
+use crate::commands::filter::StoryFilterArgs;
+use crate::export::{FeedFormat, FeedMetadata, render_feed};
+use crate::models::story::{HackerNewsStoryType, StoryItem};
+use crate::print_success;
+use crate::types::HnResult;
+
+/// Arguments for exporting a story list as a feed document
+#[derive(ClapArgs, Debug)]
+pub struct ExportArgs {
+    /// Feed format (rss, atom, jsonfeed)
+    #[arg(short = 'f', long = "feed", ignore_case = true)]
+    feed: FeedFormat,
+
+    /// Story type (top, new, best, ask, show, job)
+    #[arg(short = 's', long = "story", ignore_case = true, default_value = "t", add = ArgValueCandidates::new(story_type_candidates))]
+    story: HackerNewsStoryType,
+
+    /// The number of stories to fetch before filtering
+    #[arg(short = 'l', long = "limit", default_value = "30")]
+    limit: u16,
+
+    /// Write the feed to this file instead of stdout
+    #[arg(short = 'o', long = "output")]
+    output: Option<PathBuf>,
+
+    #[command(flatten)]
+    filter: StoryFilterArgs,
+}
+
+impl ExportArgs {
+    /// Fetch, filter and render the story list as a feed
+    pub async fn run<G>(self, gateway: G) -> HnResult<()>
+    where
+        G: HackerNewsGateway,
+    {
+        let max_limit: u16 = self.story.max_stories_limit();
+        if self.limit > max_limit {
+            return Err(format!(
+                "Max story limit for '{}' stories is {}",
+                self.story.full_name(),
+                max_limit
+            )
+            .into());
+        }
+
+        let stories: Vec<StoryItem> = self
+            .filter
+            .apply(gateway.fetch_stories(&self.story, self.limit).await?);
+
+        let metadata: FeedMetadata = FeedMetadata::new(&self.story, Utc::now());
+        let document: String = render_feed(self.feed, &metadata, &stories)?;
+
+        match &self.output {
+            Some(path) => {
+                fs::write(path, document)?;
+                print_success!(
+                    "Exported {} {} stories to {}",
+                    stories.len(),
+                    self.story.full_name(),
+                    path.display()
+                );
+            }
+            None => print!("{document}"),
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::MockHackerNewsGateway;
+    use crate::mutes::MuteKind;
+    use crate::mutes::tests::muting_gateway;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct FilterWrapper {
+        #[command(flatten)]
+        filter: StoryFilterArgs,
+    }
+
+    #[tokio::test]
+    async fn test_run_writes_filtered_feed() {
+        let directory = tempfile::tempdir().unwrap();
+        let output: PathBuf = directory.path().join("top.json");
+
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_stories()
+            .times(1)
+            .returning(|_, _| {
+                Ok(vec![
+                    StoryItem {
+                        id: 1,
+                        score: Some(500),
+                        ..Default::default()
+                    },
+                    StoryItem {
+                        id: 2,
+                        score: Some(3),
+                        ..Default::default()
+                    },
+                ])
+            });
+
+        let args: ExportArgs = ExportArgs {
+            feed: FeedFormat::JsonFeed,
+            story: HackerNewsStoryType::T,
+            limit: 30,
+            output: Some(output.clone()),
+            filter: FilterWrapper::parse_from(["export", "--min-score", "100"]).filter,
+        };
+        args.run(mock_gateway).await.unwrap();
+
+        let feed: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output).unwrap()).unwrap();
+        let items = feed["items"].as_array().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["id"], "https://news.ycombinator.com/item?id=1");
+    }
+
+    #[tokio::test]
+    async fn test_run_leaves_out_muted_stories() {
+        let directory = tempfile::tempdir().unwrap();
+        let output: PathBuf = directory.path().join("top.json");
+
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway.expect_fetch_stories().returning(|_, _| {
+            Ok(vec![
+                StoryItem {
+                    id: 1,
+                    url: Some("https://example.com/post".to_string()),
+                    ..Default::default()
+                },
+                StoryItem {
+                    id: 2,
+                    url: Some("https://kept.org/post".to_string()),
+                    ..Default::default()
+                },
+            ])
+        });
+        let gateway = muting_gateway(
+            mock_gateway,
+            directory.path(),
+            &[(MuteKind::Domain, "example.com")],
+        );
+
+        let args: ExportArgs = ExportArgs {
+            feed: FeedFormat::JsonFeed,
+            story: HackerNewsStoryType::T,
+            limit: 30,
+            output: Some(output.clone()),
+            filter: FilterWrapper::parse_from(["export"]).filter,
+        };
+        args.run(gateway).await.unwrap();
+
+        let feed: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output).unwrap()).unwrap();
+        let items = feed["items"].as_array().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["id"], "https://news.ycombinator.com/item?id=2");
+    }
+}
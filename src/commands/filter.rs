@@ -0,0 +1,97 @@
+use clap::Args as ClapArgs;
+
+use crate::models::story::StoryItem;
+use crate::utils::helpers::contains_phrase;
+
+/// Options for narrowing down a fetched story list
+#[derive(ClapArgs, Debug, Clone, Default)]
+pub struct StoryFilterArgs {
+    /// Only include stories with at least this many points
+    #[arg(long = "min-score")]
+    min_score: Option<i32>,
+
+    /// Only include stories with at least this many comments
+    #[arg(long = "min-comments")]
+    min_comments: Option<u32>,
+
+    /// Only include stories whose title contains this word or phrase (repeatable)
+    #[arg(short = 'k', long = "keyword")]
+    keywords: Vec<String>,
+}
+
+impl StoryFilterArgs {
+    /// Whether a story passes every filter
+    pub fn matches(&self, story: &StoryItem) -> bool {
+        if let Some(min_score) = self.min_score
+            && story.score.unwrap_or(0) < min_score
+        {
+            return false;
+        }
+
+        if let Some(min_comments) = self.min_comments
+            && story.descendants.unwrap_or(0) < min_comments
+        {
+            return false;
+        }
+
+        if !self.keywords.is_empty() {
+            let title: String = story.title.as_deref().unwrap_or("").to_lowercase();
+            return self
+                .keywords
+                .iter()
+                .any(|keyword| contains_phrase(&title, &keyword.to_lowercase()));
+        }
+
+        return true;
+    }
+
+    /// Keep only the stories that pass every filter
+    pub fn apply(&self, stories: Vec<StoryItem>) -> Vec<StoryItem> {
+        return stories
+            .into_iter()
+            .filter(|story| self.matches(story))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn story(id: u64, score: i32, comments: u32, title: &str) -> StoryItem {
+        return StoryItem {
+            id,
+            score: Some(score),
+            descendants: Some(comments),
+            title: Some(title.to_string()),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_apply() {
+        let stories: Vec<StoryItem> = vec![
+            story(1, 200, 10, "Rust 2.0 released"),
+            story(2, 5, 100, "Rust in the kernel"),
+            story(3, 300, 500, "Go generics"),
+        ];
+
+        let filter: StoryFilterArgs = StoryFilterArgs {
+            min_score: Some(100),
+            keywords: vec!["rust".to_string()],
+            ..Default::default()
+        };
+        let ids: Vec<u64> = filter.apply(stories.clone()).iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![1]);
+
+        let filter: StoryFilterArgs = StoryFilterArgs {
+            min_comments: Some(50),
+            ..Default::default()
+        };
+        let ids: Vec<u64> = filter.apply(stories.clone()).iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![2, 3]);
+
+        // No filters keeps everything
+        assert_eq!(StoryFilterArgs::default().apply(stories).len(), 3);
+    }
+}
@@ -0,0 +1,270 @@
+use chrono::{DateTime, Utc};
+use clap::Args as ClapArgs;
+use clap_complete::engine::ArgValueCandidates;
+
+use crate::api::HackerNewsGateway;
+use crate::commands::completions::{snapshot_story_candidates, story_type_candidates};
+use crate::history::Snapshot;
+use crate::history::store::SnapshotStore;
+use crate::history::trend::{RisingStory, TrendPoint, rising_stories, sparkline, story_trend};
+use crate::models::story::{HackerNewsStoryType, StoryItem};
+use crate::print_success;
+use crate::types::HnResult;
+use crate::utils::helpers::{clear_loading, show_loading};
+
+/// Format used for snapshot times
+const SNAPSHOT_TIME_FORMAT: &str = "%Y-%m-%d %H:%M UTC";
+
+/// Arguments for recording a story list snapshot
+#[derive(ClapArgs, Debug)]
+pub struct SnapshotArgs {
+    /// Story type (top, new, best, ask, show, job)
+    #[arg(short = 's', long = "story", ignore_case = true, default_value = "t", add = ArgValueCandidates::new(story_type_candidates))]
+    story: HackerNewsStoryType,
+
+    /// The number of stories to record (defaults to the whole list)
+    #[arg(short = 'l', long = "limit")]
+    limit: Option<u16>,
+}
+
+impl SnapshotArgs {
+    /// Fetch the story list and append it to the snapshot store
+    pub async fn run<G>(self, gateway: G, store: &SnapshotStore) -> HnResult<()>
+    where
+        G: HackerNewsGateway,
+    {
+        let max_limit: u16 = self.story.max_stories_limit();
+        let limit: u16 = self.limit.unwrap_or(max_limit);
+        if limit > max_limit {
+            return Err(format!(
+                "Max story limit for '{}' stories is {}",
+                self.story.full_name(),
+                max_limit
+            )
+            .into());
+        }
+
+        show_loading(Some(&format!(
+            "Fetching {} stories...please wait",
+            self.story.full_name()
+        )));
+
+        let result: HnResult<Vec<StoryItem>> = gateway.fetch_stories(&self.story, limit).await;
+
+        clear_loading();
+
+        let snapshot: Snapshot = Snapshot::new(Utc::now(), self.story, result?);
+        store.append(&snapshot)?;
+
+        print_success!(
+            "Recorded snapshot of {} {} stories",
+            snapshot.entries.len(),
+            self.story.full_name()
+        );
+
+        return Ok(());
+    }
+}
+
+/// Arguments for showing a story's trend
+#[derive(ClapArgs, Debug)]
+pub struct TrendArgs {
+    /// Hacker News story ID
+    #[arg(add = ArgValueCandidates::new(snapshot_story_candidates))]
+    id: u64,
+
+    /// Story type the snapshots were recorded for
+    #[arg(short = 's', long = "story", ignore_case = true, default_value = "t", add = ArgValueCandidates::new(story_type_candidates))]
+    story: HackerNewsStoryType,
+}
+
+impl TrendArgs {
+    /// Print sparklines and a table of the story's rank and score over time
+    pub fn run(self, store: &SnapshotStore) -> HnResult<()> {
+        let snapshots: Vec<Snapshot> = store.load(self.story)?;
+        let points: Vec<TrendPoint> = story_trend(&snapshots, self.id);
+
+        let (Some(first), Some(last)) = (points.first(), points.last()) else {
+            return Err(format!(
+                "Story {} was not found in any '{}' snapshots",
+                self.id,
+                self.story.full_name()
+            )
+            .into());
+        };
+
+        let title: &str = snapshots
+            .iter()
+            .rev()
+            .find_map(|snapshot| snapshot.entry(self.id)?.item.title.as_deref())
+            .unwrap_or("No Title");
+
+        println!("{} story {}: {}", self.story.full_name(), self.id, title);
+        println!(
+            "{} snapshot(s) from {} to {}",
+            points.len(),
+            first.taken_at.format(SNAPSHOT_TIME_FORMAT),
+            last.taken_at.format(SNAPSHOT_TIME_FORMAT)
+        );
+        println!();
+
+        // Ranks are inverted so the line rises as the story climbs the list
+        let ranks: Vec<f64> = points.iter().map(|point| -(point.rank as f64)).collect();
+        let scores: Vec<f64> = points.iter().map(|point| f64::from(point.score)).collect();
+        let comments: Vec<f64> = points
+            .iter()
+            .map(|point| f64::from(point.comments))
+            .collect();
+        let best_rank: usize = points.iter().map(|point| point.rank).min().unwrap_or(0);
+
+        println!(
+            "{:<10}{}  #{} -> #{} (best #{})",
+            "Rank",
+            sparkline(&ranks),
+            first.rank,
+            last.rank,
+            best_rank
+        );
+        println!(
+            "{:<10}{}  {} -> {}",
+            "Score",
+            sparkline(&scores),
+            first.score,
+            last.score
+        );
+        println!(
+            "{:<10}{}  {} -> {}",
+            "Comments",
+            sparkline(&comments),
+            first.comments,
+            last.comments
+        );
+        println!();
+
+        println!(
+            "{:<22}{:>6}{:>8}{:>10}",
+            "Time", "Rank", "Score", "Comments"
+        );
+        for point in &points {
+            println!(
+                "{:<22}{:>6}{:>8}{:>10}",
+                point.taken_at.format(SNAPSHOT_TIME_FORMAT).to_string(),
+                format!("#{}", point.rank),
+                point.score,
+                point.comments
+            );
+        }
+
+        return Ok(());
+    }
+}
+
+/// Arguments for listing rising stories
+#[derive(ClapArgs, Debug)]
+pub struct RisingArgs {
+    /// Story type the snapshots were recorded for
+    #[arg(short = 's', long = "story", ignore_case = true, default_value = "t", add = ArgValueCandidates::new(story_type_candidates))]
+    story: HackerNewsStoryType,
+
+    /// The number of rising stories to display
+    #[arg(short = 'l', long = "limit", default_value = "10")]
+    limit: usize,
+}
+
+impl RisingArgs {
+    /// Print the stories with the highest score velocity between the last two snapshots
+    pub fn run(self, store: &SnapshotStore) -> HnResult<()> {
+        let snapshots: Vec<Snapshot> = store.load(self.story)?;
+
+        let [.., previous, latest] = snapshots.as_slice() else {
+            return Err(format!(
+                "At least two '{}' snapshots are needed, record them with `hn-stories snapshot -s {}`",
+                self.story.full_name(),
+                self.story.full_name().to_lowercase()
+            )
+            .into());
+        };
+
+        let rising: Vec<RisingStory> = rising_stories(previous, latest);
+        let since: DateTime<Utc> = previous.taken_at;
+
+        println!(
+            "Rising {} stories since {}",
+            self.story.full_name(),
+            since.format(SNAPSHOT_TIME_FORMAT)
+        );
+
+        for (index, story) in rising.iter().take(self.limit).enumerate() {
+            println!(
+                "{:>3}. {:>+8.1} pts/h  #{} -> #{}  {} ({})",
+                index + 1,
+                story.points_per_hour,
+                story.previous_rank,
+                story.rank,
+                story.item.title.as_deref().unwrap_or("No Title"),
+                story.item.id
+            );
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::MockHackerNewsGateway;
+
+    #[tokio::test]
+    async fn test_snapshot_run_records_ranked_list() {
+        let directory = tempfile::tempdir().unwrap();
+        let store: SnapshotStore = SnapshotStore::new(directory.path().join("snapshots.jsonl"));
+
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_stories()
+            .withf(|story_type, limit| *story_type == HackerNewsStoryType::A && *limit == 200)
+            .times(1)
+            .returning(|_, _| {
+                Ok(vec![
+                    StoryItem {
+                        id: 7,
+                        score: Some(12),
+                        ..Default::default()
+                    },
+                    StoryItem {
+                        id: 3,
+                        score: Some(40),
+                        ..Default::default()
+                    },
+                ])
+            });
+
+        let args = SnapshotArgs {
+            story: HackerNewsStoryType::A,
+            limit: None,
+        };
+        args.run(mock_gateway, &store).await.unwrap();
+
+        let snapshots: Vec<Snapshot> = store.load(HackerNewsStoryType::A).unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].entry(3).unwrap().rank, 2);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_run_invalid_limit() {
+        let directory = tempfile::tempdir().unwrap();
+        let store: SnapshotStore = SnapshotStore::new(directory.path().join("snapshots.jsonl"));
+
+        let args = SnapshotArgs {
+            story: HackerNewsStoryType::J,
+            limit: Some(201),
+        };
+
+        assert!(
+            args.run(MockHackerNewsGateway::new(), &store)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use clap::Args as ClapArgs;
+
+use crate::api::HackerNewsGateway;
+use crate::models::item::{ItemThread, parse_item_reference};
+use crate::models::story::StoryItem;
+use crate::types::HnResult;
+use crate::ui::UserInterface;
+use crate::utils::helpers::{clear_loading, show_loading};
+
+/// The most parents followed from a comment up to its story
+const MAX_THREAD_DEPTH: usize = 500;
+
+/// Arguments for looking up a single item
+#[derive(ClapArgs, Debug)]
+pub struct ItemArgs {
+    /// Item ID, or its URL (e.g. https://news.ycombinator.com/item?id=8863)
+    item: String,
+}
+
+impl ItemArgs {
+    /// Fetch an item and show it with its story and the comments leading to it
+    pub async fn run<G, U>(self, gateway: G, ui: U) -> HnResult<()>
+    where
+        G: HackerNewsGateway,
+        U: UserInterface,
+    {
+        let id: u64 = parse_item_reference(&self.item)
+            .ok_or_else(|| format!("Invalid item ID or URL: {}", self.item))?;
+
+        show_loading(Some(&format!("Fetching item {id}...please wait")));
+        let result: HnResult<ItemThread> = fetch_thread(&gateway, id).await;
+        clear_loading();
+
+        ui.render_thread(result?).await?;
+
+        return Ok(());
+    }
+}
+
+/// Fetch an item and walk up its parents to the root story
+async fn fetch_thread<G: HackerNewsGateway>(gateway: &G, id: u64) -> HnResult<ItemThread> {
+    let item: StoryItem = gateway
+        .fetch_item(id)
+        .await?
+        .ok_or_else(|| format!("Item {id} not found"))?;
+
+    let mut ancestors: Vec<StoryItem> = Vec::new();
+    let mut parent: Option<u64> = item.parent;
+    while let Some(parent_id) = parent {
+        if ancestors.len() >= MAX_THREAD_DEPTH {
+            return Err(format!("Item {id} is nested too deeply to find its story").into());
+        }
+
+        let ancestor: StoryItem = gateway
+            .fetch_item(parent_id)
+            .await?
+            .ok_or_else(|| format!("Parent item {parent_id} of item {id} not found"))?;
+        parent = ancestor.parent;
+        ancestors.push(ancestor);
+    }
+
+    return Ok(ItemThread::new(item, ancestors));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::MockHackerNewsGateway;
+    use crate::ui::MockUserInterface;
+
+    /// Gateway serving a story (1) with a reply (2) and a nested reply (3)
+    fn thread_gateway() -> MockHackerNewsGateway {
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway.expect_fetch_item().returning(|id| {
+            let parent: Option<u64> = match id {
+                1 => None,
+                2 | 3 => Some(id - 1),
+                _ => return Ok(None),
+            };
+            return Ok(Some(StoryItem {
+                id,
+                parent,
+                ..Default::default()
+            }));
+        });
+
+        return mock_gateway;
+    }
+
+    #[tokio::test]
+    async fn test_run_walks_parents_of_comment() {
+        let mut mock_ui = MockUserInterface::new();
+        mock_ui
+            .expect_render_thread()
+            .withf(|thread| {
+                thread.story.id == 1
+                    && thread.context.len() == 1
+                    && thread.context[0].id == 2
+                    && thread
+                        .comment
+                        .as_ref()
+                        .is_some_and(|comment| comment.id == 3)
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let args: ItemArgs = ItemArgs {
+            item: "https://news.ycombinator.com/item?id=3".to_string(),
+        };
+
+        let result: HnResult<()> = args.run(thread_gateway(), mock_ui).await;
+
+        assert!(result.is_ok(), "Error: {:?}", result.err());
+    }
+
+    #[tokio::test]
+    async fn test_run_story() {
+        let mut mock_ui = MockUserInterface::new();
+        mock_ui
+            .expect_render_thread()
+            .withf(|thread| thread.story.id == 1 && thread.comment.is_none())
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let args: ItemArgs = ItemArgs {
+            item: "1".to_string(),
+        };
+
+        assert!(args.run(thread_gateway(), mock_ui).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_run_missing_or_invalid_item() {
+        let args: ItemArgs = ItemArgs {
+            item: "99".to_string(),
+        };
+        let error: String = args
+            .run(thread_gateway(), MockUserInterface::new())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Item 99 not found"));
+
+        let args: ItemArgs = ItemArgs {
+            item: "https://example.com/item?id=1".to_string(),
+        };
+        let error: String = args
+            .run(MockHackerNewsGateway::new(), MockUserInterface::new())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Invalid item ID or URL"));
+    }
+}
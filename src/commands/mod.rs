@@ -0,0 +1,150 @@
+pub mod account;
+pub mod archive;
+pub mod bookmarks;
+pub mod completions;
+pub mod digest;
+pub mod domain;
+pub mod export;
+pub mod filter;
+pub mod history;
+pub mod item;
+pub mod mutes;
+pub mod notify;
+pub mod query;
+pub mod serve;
+pub mod url;
+
+use clap::{CommandFactory, Subcommand};
+
+use crate::account::session::SessionStore;
+use crate::api::HackerNewsGateway;
+use crate::archive::store::ArchiveStore;
+use crate::args::Args;
+use crate::bookmarks::BookmarkStore;
+use crate::commands::account::{LoginArgs, LogoutArgs};
+use crate::commands::archive::ArchiveArgs;
+use crate::commands::bookmarks::BookmarksArgs;
+use crate::commands::completions::{CompletionsArgs, ManArgs};
+use crate::commands::digest::DigestArgs;
+use crate::commands::domain::DomainArgs;
+use crate::commands::export::ExportArgs;
+use crate::commands::history::{RisingArgs, SnapshotArgs, TrendArgs};
+use crate::commands::item::ItemArgs;
+use crate::commands::mutes::MutesArgs;
+use crate::commands::notify::NotifyArgs;
+use crate::commands::query::QueryArgs;
+use crate::commands::serve::ServeArgs;
+use crate::commands::url::UrlArgs;
+use crate::config::Config;
+use crate::history::store::SnapshotStore;
+use crate::mutes::MuteStore;
+use crate::types::HnResult;
+use crate::ui::UserInterface;
+use crate::utils::constants::Y_COMBINATOR_BASE_URL;
+
+/// Subcommands run instead of the interactive story list
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Record the current ranking, score and comment count of a story list
+    Snapshot(SnapshotArgs),
+
+    /// Show how a story's rank and score changed across snapshots
+    Trend(TrendArgs),
+
+    /// List stories whose score grew fastest between the last two snapshots
+    Rising(RisingArgs),
+
+    /// Poll story lists and send notifications for stories matching keyword, domain or author rules
+    Notify(NotifyArgs),
+
+    /// Export a story list as an RSS, Atom or JSON Feed document
+    Export(ExportArgs),
+
+    /// Generate a static HTML page or Markdown document from a story list
+    Digest(DigestArgs),
+
+    /// Serve story lists, items and users as JSON over HTTP with shared caching
+    Serve(ServeArgs),
+
+    /// Browse the most recent stories from a site, e.g. example.com or github.com/rust-lang
+    Domain(DomainArgs),
+
+    /// List bookmarked stories in the terminal UI, or remove bookmarks
+    Bookmarks(BookmarksArgs),
+
+    /// Show a story or comment by ID or URL, with the comments leading to it
+    Item(ItemArgs),
+
+    /// Check whether a link was submitted before, and browse its past discussions
+    Url(UrlArgs),
+
+    /// Mirror Hacker News items into a local database, resuming where the last run stopped
+    Archive(ArchiveArgs),
+
+    /// Query the local archive with SQL or filter terms, in the terminal UI or as JSON or CSV
+    Query(QueryArgs),
+
+    /// List, add or remove the rules that hide stories by author, site, title keyword or ID
+    Mutes(MutesArgs),
+
+    /// Log in to a Hacker News account to upvote, favorite, hide, flag and reply from the story list
+    Login(LoginArgs),
+
+    /// Log out of the Hacker News account
+    Logout(LogoutArgs),
+
+    /// Print a shell completion script (bash, elvish, fish, powershell, zsh)
+    Completions(CompletionsArgs),
+
+    /// Print the man page, or write pages for every command to a directory
+    Man(ManArgs),
+}
+
+impl Command {
+    /// Whether the subcommand keeps running until stopped, rather than finishing or
+    /// waiting on the terminal UI
+    pub fn is_long_running(&self) -> bool {
+        return matches!(
+            self,
+            Command::Notify(_) | Command::Serve(_) | Command::Archive(_)
+        );
+    }
+
+    /// Whether the subcommand stores the items it fetches in the archive itself
+    pub fn is_archive_crawl(&self) -> bool {
+        return matches!(self, Command::Archive(_));
+    }
+
+    /// Run/execute the subcommand
+    pub async fn run<G, U>(self, gateway: G, ui: U) -> HnResult<()>
+    where
+        G: HackerNewsGateway + 'static,
+        U: UserInterface,
+    {
+        return match self {
+            Command::Snapshot(args) => args.run(gateway, &SnapshotStore::open_default()?).await,
+            Command::Trend(args) => args.run(&SnapshotStore::open_default()?),
+            Command::Rising(args) => args.run(&SnapshotStore::open_default()?),
+            Command::Notify(args) => args.run(gateway, Config::load()?).await,
+            Command::Export(args) => args.run(gateway).await,
+            Command::Digest(args) => args.run(gateway).await,
+            Command::Serve(args) => args.run(gateway).await,
+            Command::Domain(args) => args.run(gateway, ui).await,
+            Command::Bookmarks(args) => args.run(ui, &BookmarkStore::open_default()?).await,
+            Command::Item(args) => args.run(gateway, ui).await,
+            Command::Url(args) => args.run(gateway, ui).await,
+            Command::Archive(args) => args.run(gateway, &mut ArchiveStore::open_default()?).await,
+            Command::Query(args) => args.run(ui, &ArchiveStore::open_default()?).await,
+            Command::Mutes(args) => args.run(&MuteStore::open_default()?),
+            Command::Login(args) => {
+                let config: Config = Config::load()?;
+                let store: SessionStore = SessionStore::open_default()?;
+                args.run(&config.account, &store, Y_COMBINATOR_BASE_URL)
+                    .await
+            }
+            Command::Logout(args) => args.run(&SessionStore::open_default()?),
+            Command::Completions(args) => args.run(),
+            Command::Man(args) => args.run(Args::command()),
+        };
+    }
+}
@@ -0,0 +1,133 @@
+use clap::{Args as ClapArgs, Subcommand};
+
+use crate::mutes::{MuteKind, MuteRules, MuteStore};
+use crate::types::HnResult;
+use crate::{print_success, print_warn};
+
+/// Arguments for managing the rules that hide stories from story lists
+#[derive(ClapArgs, Debug)]
+pub struct MutesArgs {
+    #[command(subcommand)]
+    action: Option<MutesAction>,
+}
+
+/// Changes to the mute rules (the rules are listed without one)
+#[derive(Subcommand, Debug)]
+pub enum MutesAction {
+    /// Mute stories by an author, from a site, with a word or phrase in the title, or by ID
+    Add {
+        /// What to match (author, domain, keyword, id)
+        #[arg(ignore_case = true)]
+        kind: MuteKind,
+
+        /// Username, site, word or phrase, or story ID
+        value: String,
+    },
+
+    /// Remove a mute rule
+    Remove {
+        /// What the rule matches (author, domain, keyword, id)
+        #[arg(ignore_case = true)]
+        kind: MuteKind,
+
+        /// Username, site, word or phrase, or story ID
+        value: String,
+    },
+
+    /// Remove every mute rule
+    Clear,
+}
+
+impl MutesArgs {
+    /// List, add or remove mute rules
+    pub fn run(self, store: &MuteStore) -> HnResult<()> {
+        match self.action {
+            None => {
+                let rules: MuteRules = store.load()?;
+                if rules.is_empty() {
+                    print_warn!(
+                        "No mute rules, add one with `hn-stories mutes add <KIND> <VALUE>`"
+                    );
+                }
+                for line in rules.describe() {
+                    println!("{line}");
+                }
+            }
+            Some(MutesAction::Add { kind, value }) => {
+                if store.add(kind, &value)? {
+                    print_success!("Muted {kind} {value}");
+                } else {
+                    print_warn!("{kind} {value} is already muted");
+                }
+            }
+            Some(MutesAction::Remove { kind, value }) => {
+                if store.remove(kind, &value)? {
+                    print_success!("Unmuted {kind} {value}");
+                } else {
+                    print_warn!("No mute rule for {kind} {value}");
+                }
+            }
+            Some(MutesAction::Clear) => {
+                store.save(&MuteRules::default())?;
+                print_success!("Removed all mute rules");
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_manages_rules() {
+        let directory = tempfile::tempdir().unwrap();
+        let store: MuteStore = MuteStore::new(directory.path().join("mutes.json"));
+        let run = |action: Option<MutesAction>| MutesArgs { action }.run(&store).unwrap();
+
+        run(Some(MutesAction::Add {
+            kind: MuteKind::Domain,
+            value: "example.com".to_string(),
+        }));
+        run(Some(MutesAction::Add {
+            kind: MuteKind::Author,
+            value: "pg".to_string(),
+        }));
+        run(None);
+        assert_eq!(
+            store.load().unwrap().describe(),
+            vec!["author pg", "domain example.com"]
+        );
+
+        run(Some(MutesAction::Remove {
+            kind: MuteKind::Author,
+            value: "pg".to_string(),
+        }));
+        assert_eq!(store.load().unwrap().describe(), vec!["domain example.com"]);
+
+        run(Some(MutesAction::Clear));
+        assert!(store.load().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_run_rejects_invalid_rules() {
+        let directory = tempfile::tempdir().unwrap();
+        let store: MuteStore = MuteStore::new(directory.path().join("mutes.json"));
+
+        let args: MutesArgs = MutesArgs {
+            action: Some(MutesAction::Add {
+                kind: MuteKind::Id,
+                value: "not-a-number".to_string(),
+            }),
+        };
+
+        assert!(
+            args.run(&store)
+                .unwrap_err()
+                .to_string()
+                .contains("Invalid story ID")
+        );
+    }
+}
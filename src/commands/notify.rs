@@ -0,0 +1,228 @@
+use std::time::Duration;
+
+use clap::Args as ClapArgs;
+use clap_complete::engine::ArgValueCandidates;
+
+use crate::api::HackerNewsGateway;
+use crate::commands::completions::story_type_candidates;
+use crate::config::Config;
+use crate::models::story::HackerNewsStoryType;
+use crate::notify::notifiers::NotifierConfig;
+use crate::notify::rules::NotifyRule;
+use crate::notify::state::NotifyState;
+use crate::notify::{Notifier, NotifyConfig, poll_once};
+use crate::types::HnResult;
+use crate::{print_error, print_info, print_success};
+
+/// Arguments for notify mode
+///
+/// Rules and notifiers from the `[notify]` config section are combined with the
+/// ones given on the command line.
+#[derive(ClapArgs, Debug)]
+pub struct NotifyArgs {
+    /// Story types to poll, separated by commas [default: new]
+    #[arg(short = 's', long = "story", ignore_case = true, value_delimiter = ',', add = ArgValueCandidates::new(story_type_candidates))]
+    story: Option<Vec<HackerNewsStoryType>>,
+
+    /// The number of stories to check in each list [default: 100]
+    #[arg(short = 'l', long = "limit")]
+    limit: Option<u16>,
+
+    /// Seconds between polls [default: 300]
+    #[arg(short = 'i', long = "interval")]
+    interval: Option<u64>,
+
+    /// Notify about titles containing this keyword (repeatable)
+    #[arg(short = 'k', long = "keyword")]
+    keywords: Vec<String>,
+
+    /// Notify about stories linking to this domain (repeatable)
+    #[arg(short = 'd', long = "domain")]
+    domains: Vec<String>,
+
+    /// Notify about stories by this author (repeatable)
+    #[arg(short = 'a', long = "author")]
+    authors: Vec<String>,
+
+    /// POST notifications as JSON to this URL (repeatable)
+    #[arg(short = 'w', long = "webhook")]
+    webhooks: Vec<String>,
+
+    /// Run this command for each notification, e.g. "notify-send {title}" (repeatable)
+    #[arg(short = 'e', long = "exec")]
+    commands: Vec<String>,
+
+    /// Poll once and exit instead of polling until interrupted
+    #[arg(long = "once")]
+    once: bool,
+}
+
+impl NotifyArgs {
+    /// Combine the config file settings with the command line arguments
+    fn into_config(self, mut config: NotifyConfig) -> NotifyConfig {
+        if let Some(story_types) = self.story {
+            config.story_types = story_types;
+        }
+        if let Some(limit) = self.limit {
+            config.limit = limit;
+        }
+        if let Some(interval) = self.interval {
+            config.interval = interval;
+        }
+
+        // Each command line criterion is its own rule
+        config
+            .rules
+            .extend(self.keywords.into_iter().map(|keyword| NotifyRule {
+                keywords: vec![keyword],
+                ..Default::default()
+            }));
+        config
+            .rules
+            .extend(self.domains.into_iter().map(|domain| NotifyRule {
+                domains: vec![domain],
+                ..Default::default()
+            }));
+        config
+            .rules
+            .extend(self.authors.into_iter().map(|author| NotifyRule {
+                authors: vec![author],
+                ..Default::default()
+            }));
+
+        config.notifiers.extend(
+            self.webhooks
+                .into_iter()
+                .map(|url| NotifierConfig::Webhook { url }),
+        );
+        config.notifiers.extend(
+            self.commands
+                .into_iter()
+                .map(|command| NotifierConfig::Command { command }),
+        );
+
+        if config.notifiers.is_empty() {
+            config.notifiers.push(NotifierConfig::Stdout);
+        }
+
+        return config;
+    }
+
+    /// Poll the story lists and notify about matching stories until interrupted
+    pub async fn run<G>(self, gateway: G, config: Config) -> HnResult<()>
+    where
+        G: HackerNewsGateway,
+    {
+        let once: bool = self.once;
+        let config: NotifyConfig = self.into_config(config.notify);
+
+        if config.rules.is_empty() {
+            return Err(
+                "No notify rules, add --keyword/--domain/--author or [[notify.rules]] to the config file"
+                    .into(),
+            );
+        }
+
+        for story_type in &config.story_types {
+            if config.limit > story_type.max_stories_limit() {
+                return Err(format!(
+                    "Max story limit for '{}' stories is {}",
+                    story_type.full_name(),
+                    story_type.max_stories_limit()
+                )
+                .into());
+            }
+        }
+
+        let notifiers: Vec<Box<dyn Notifier>> =
+            config.notifiers.iter().map(NotifierConfig::build).collect();
+        let mut state: NotifyState = NotifyState::load_default()?;
+
+        if !once {
+            print_info!(
+                "Watching {} stories every {}s with {} rule(s). Press Ctrl+C to stop.",
+                HackerNewsStoryType::join_full_names(&config.story_types),
+                config.interval,
+                config.rules.len()
+            );
+        }
+
+        loop {
+            // A failed poll (e.g. network blip) should not end a long-running watch
+            match poll_once(&gateway, &config, &notifiers, &mut state).await {
+                Ok(count) if once => print_success!("Sent {count} notification(s)"),
+                Ok(_) => {}
+                Err(error) if once => return Err(error),
+                Err(error) => print_error!("Poll failed: {error}"),
+            }
+
+            if once {
+                return Ok(());
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(config.interval)) => {}
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_args() -> NotifyArgs {
+        return NotifyArgs {
+            story: None,
+            limit: None,
+            interval: None,
+            keywords: Vec::new(),
+            domains: Vec::new(),
+            authors: Vec::new(),
+            webhooks: Vec::new(),
+            commands: Vec::new(),
+            once: true,
+        };
+    }
+
+    #[test]
+    fn test_into_config_merges_command_line() {
+        let file_config: NotifyConfig = NotifyConfig {
+            rules: vec![NotifyRule {
+                name: Some("from file".to_string()),
+                authors: vec!["pg".to_string()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let args: NotifyArgs = NotifyArgs {
+            story: Some(vec![HackerNewsStoryType::T]),
+            keywords: vec!["rust".to_string()],
+            domains: vec!["example.com".to_string()],
+            webhooks: vec!["https://hooks.example.com".to_string()],
+            ..empty_args()
+        };
+
+        let config: NotifyConfig = args.into_config(file_config);
+
+        assert_eq!(config.story_types, vec![HackerNewsStoryType::T]);
+        assert_eq!(config.interval, 300);
+        assert_eq!(config.rules.len(), 3);
+        assert_eq!(config.rules[1].keywords, vec!["rust".to_string()]);
+        assert_eq!(
+            config.notifiers,
+            vec![NotifierConfig::Webhook {
+                url: "https://hooks.example.com".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_into_config_defaults_to_stdout() {
+        let config: NotifyConfig = empty_args().into_config(NotifyConfig::default());
+
+        assert_eq!(config.notifiers, vec![NotifierConfig::Stdout]);
+    }
+}
@@ -0,0 +1,157 @@
+use clap::{Args as ClapArgs, ValueEnum};
+use rusqlite::types::Value;
+
+use crate::archive::query::{ItemFilter, QueryRows, is_sql};
+use crate::archive::store::ArchiveStore;
+use crate::models::story::StoryItem;
+use crate::print_warn;
+use crate::types::HnResult;
+use crate::ui::UserInterface;
+
+/// Output formats of query results
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum QueryFormat {
+    /// Browse the matching items in the terminal UI
+    List,
+    Json,
+    Csv,
+}
+
+/// Arguments for querying the local archive
+#[derive(ClapArgs, Debug)]
+pub struct QueryArgs {
+    /// A read-only SQL statement (tables `items` and `users`), or filter terms such as
+    /// `by:pg domain:github.com type:story score>100 comments>=10 after:2024-01-01 sort:score rust`
+    #[arg(required = true, num_args = 1..)]
+    query: Vec<String>,
+
+    /// The number of items to return for filter terms
+    #[arg(short = 'l', long = "limit", default_value = "30")]
+    limit: u16,
+
+    /// Output format, the list needing an `id` column in SQL results
+    #[arg(short = 'f', long = "format", value_enum, default_value = "list")]
+    format: QueryFormat,
+}
+
+impl QueryArgs {
+    /// Run the query against the archive and show or print the results
+    pub async fn run<U: UserInterface>(self, ui: U, store: &ArchiveStore) -> HnResult<()> {
+        let query: String = self.query.join(" ");
+        let (sql, parameters): (String, Vec<Value>) = if is_sql(&query) {
+            (query, Vec::new())
+        } else {
+            ItemFilter::parse(&self.query)?.to_sql(self.limit)
+        };
+
+        let rows: QueryRows = store.query(&sql, &parameters)?;
+
+        match self.format {
+            QueryFormat::Json => println!("{}", rows.to_json()?),
+            QueryFormat::Csv => println!("{}", rows.to_csv()),
+            QueryFormat::List => {
+                let ids: Vec<u64> = rows.ids().ok_or(
+                    "Query results need an `id` column to be listed, or use --format json or csv",
+                )?;
+                let items: Vec<StoryItem> = store.items(&ids)?;
+
+                if items.is_empty() {
+                    print_warn!("No archived items match the query");
+                    return Ok(());
+                }
+
+                ui.render_stories_list(items, "Query").await?;
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::MockUserInterface;
+
+    fn test_store(directory: &tempfile::TempDir) -> ArchiveStore {
+        let mut store: ArchiveStore =
+            ArchiveStore::open(&directory.path().join("archive.db")).unwrap();
+        let items: Vec<StoryItem> = (1..=4)
+            .map(|id| StoryItem {
+                id,
+                by: Some(if id % 2 == 0 { "alice" } else { "bob" }.to_string()),
+                score: Some(id as i32),
+                ..Default::default()
+            })
+            .collect();
+        store.save_items(&items).unwrap();
+
+        return store;
+    }
+
+    #[tokio::test]
+    async fn test_run_filter_terms_in_list() {
+        let directory = tempfile::tempdir().unwrap();
+        let store: ArchiveStore = test_store(&directory);
+
+        let mut mock_ui = MockUserInterface::new();
+        mock_ui
+            .expect_render_stories_list()
+            .withf(|stories, list_name| {
+                stories.iter().map(|story| story.id).collect::<Vec<u64>>() == [4, 2]
+                    && list_name == "Query"
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let args: QueryArgs = QueryArgs {
+            query: vec!["by:Alice".to_string(), "sort:score".to_string()],
+            limit: 30,
+            format: QueryFormat::List,
+        };
+
+        let result: HnResult<()> = args.run(mock_ui, &store).await;
+
+        assert!(result.is_ok(), "Error: {:?}", result.err());
+    }
+
+    #[tokio::test]
+    async fn test_run_sql() {
+        let directory = tempfile::tempdir().unwrap();
+        let store: ArchiveStore = test_store(&directory);
+
+        let mut mock_ui = MockUserInterface::new();
+        mock_ui
+            .expect_render_stories_list()
+            .withf(|stories, _| stories.len() == 1 && stories[0].id == 3)
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let args: QueryArgs = QueryArgs {
+            query: vec!["SELECT id FROM items WHERE score = 3".to_string()],
+            limit: 30,
+            format: QueryFormat::List,
+        };
+        assert!(args.run(mock_ui, &store).await.is_ok());
+
+        // Aggregates can only be printed
+        let args: QueryArgs = QueryArgs {
+            query: vec!["SELECT by, COUNT(*) FROM items GROUP BY by".to_string()],
+            limit: 30,
+            format: QueryFormat::List,
+        };
+        let error: String = args
+            .run(MockUserInterface::new(), &store)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("need an `id` column"));
+
+        let args: QueryArgs = QueryArgs {
+            query: vec!["SELECT by, COUNT(*) FROM items GROUP BY by".to_string()],
+            limit: 30,
+            format: QueryFormat::Csv,
+        };
+        assert!(args.run(MockUserInterface::new(), &store).await.is_ok());
+    }
+}
@@ -0,0 +1,58 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use clap::Args as ClapArgs;
+
+use crate::api::HackerNewsGateway;
+use crate::server::{ApiServer, ServerOptions};
+use crate::types::HnResult;
+use crate::{print_info, print_success};
+
+/// Arguments for the local HTTP/JSON API server
+#[derive(ClapArgs, Debug)]
+pub struct ServeArgs {
+    /// Address to listen on
+    #[arg(short = 'b', long = "bind", default_value = "127.0.0.1:8080")]
+    bind: SocketAddr,
+
+    /// Allow cross-origin requests from this origin, or "*" for any (repeatable)
+    #[arg(short = 'c', long = "cors-origin")]
+    cors_origins: Vec<String>,
+
+    /// Seconds to cache upstream responses
+    #[arg(short = 't', long = "cache-ttl", default_value = "60")]
+    cache_ttl: u64,
+}
+
+impl ServeArgs {
+    /// Serve story lists, items and users as JSON until interrupted
+    pub async fn run<G>(self, gateway: G) -> HnResult<()>
+    where
+        G: HackerNewsGateway + 'static,
+    {
+        let server: ApiServer<G> = ApiServer::bind(
+            gateway,
+            ServerOptions {
+                bind: self.bind,
+                cors_origins: self.cors_origins,
+                cache_ttl: Duration::from_secs(self.cache_ttl),
+            },
+        )
+        .await?;
+
+        print_info!(
+            "Serving /stories/{{type}}, /item/{{id}} and /user/{{id}} on http://{}. Press Ctrl+C to stop.",
+            server.local_addr()?
+        );
+
+        server
+            .run(async {
+                let _ = tokio::signal::ctrl_c().await;
+            })
+            .await?;
+
+        print_success!("Server stopped");
+
+        return Ok(());
+    }
+}
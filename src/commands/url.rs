@@ -0,0 +1,123 @@
+use clap::Args as ClapArgs;
+
+use crate::api::HackerNewsGateway;
+use crate::models::domain::normalize_url;
+use crate::models::story::StoryItem;
+use crate::print_info;
+use crate::types::HnResult;
+use crate::ui::UserInterface;
+use crate::utils::helpers::{clear_loading, show_loading};
+
+/// Arguments for looking up previous submissions of a link
+#[derive(ClapArgs, Debug)]
+pub struct UrlArgs {
+    /// Link to look up, e.g. https://example.com/post
+    link: String,
+}
+
+impl UrlArgs {
+    /// Search for earlier submissions of the link and browse their discussions in the terminal UI
+    pub async fn run<G, U>(self, gateway: G, ui: U) -> HnResult<()>
+    where
+        G: HackerNewsGateway,
+        U: UserInterface,
+    {
+        let url: String =
+            normalize_url(&self.link).ok_or_else(|| format!("Invalid URL: {}", self.link))?;
+
+        show_loading(Some(&format!(
+            "Searching submissions of {url}...please wait"
+        )));
+
+        let result: HnResult<Vec<StoryItem>> = gateway.fetch_url_submissions(&url).await;
+
+        clear_loading();
+
+        let stories: Vec<StoryItem> = result?;
+
+        if stories.is_empty() {
+            print_info!("{} has not been submitted before", url);
+            return Ok(());
+        }
+
+        ui.render_stories_list(stories, &url).await?;
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::MockHackerNewsGateway;
+    use crate::ui::MockUserInterface;
+
+    #[tokio::test]
+    async fn test_run_normalizes_link() {
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_url_submissions()
+            .withf(|url| url == "example.com/post")
+            .times(1)
+            .returning(|_| {
+                Ok(vec![
+                    StoryItem {
+                        id: 2,
+                        url: Some("https://example.com/post".to_string()),
+                        ..Default::default()
+                    },
+                    StoryItem {
+                        id: 1,
+                        url: Some("http://www.example.com/post/".to_string()),
+                        ..Default::default()
+                    },
+                ])
+            });
+
+        let mut mock_ui = MockUserInterface::new();
+        mock_ui
+            .expect_render_stories_list()
+            .withf(|stories, list_name| stories.len() == 2 && list_name == "example.com/post")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let args: UrlArgs = UrlArgs {
+            link: "https://www.example.com/post/?utm_source=newsletter".to_string(),
+        };
+
+        let result: HnResult<()> = args.run(mock_gateway, mock_ui).await;
+
+        assert!(result.is_ok(), "Error: {:?}", result.err());
+    }
+
+    #[tokio::test]
+    async fn test_run_not_submitted() {
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_url_submissions()
+            .times(1)
+            .returning(|_| Ok(Vec::new()));
+
+        // The list is not shown when the link is new
+        let mock_ui = MockUserInterface::new();
+
+        let args: UrlArgs = UrlArgs {
+            link: "example.com/new".to_string(),
+        };
+
+        assert!(args.run(mock_gateway, mock_ui).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_run_invalid_link() {
+        let args: UrlArgs = UrlArgs {
+            link: "ftp://example.com/file.txt".to_string(),
+        };
+
+        let result: HnResult<()> = args
+            .run(MockHackerNewsGateway::new(), MockUserInterface::new())
+            .await;
+
+        assert!(result.unwrap_err().to_string().contains("Invalid URL"));
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::account::AccountConfig;
+use crate::notify::NotifyConfig;
+use crate::types::HnResult;
+use crate::ui::prefetch::PrefetchConfig;
+use crate::ui::theme::ThemeConfig;
+use crate::utils::paths::config_file;
+use crate::utils::rate_limit::RateLimitConfig;
+
+/// User configuration loaded from the `config.toml` file
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Name of the built-in or user-defined theme to use
+    pub theme: Option<String>,
+    /// User-defined themes by name
+    pub themes: HashMap<String, ThemeConfig>,
+    pub account: AccountConfig,
+    pub notify: NotifyConfig,
+    pub rate_limit: RateLimitConfig,
+    pub prefetch: PrefetchConfig,
+}
+
+impl Config {
+    /// Load the config file, falling back to defaults when it does not exist
+    pub fn load() -> HnResult<Self> {
+        return Self::load_from(&config_file()?);
+    }
+
+    /// Load config from a specific file, falling back to defaults when it does not exist
+    pub fn load_from(path: &Path) -> HnResult<Self> {
+        let contents: String = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let config: Config = toml::from_str(&contents)
+            .map_err(|error| format!("Invalid config file {}: {error}", path.display()))?;
+
+        return Ok(config);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::story::HackerNewsStoryType;
+    use crate::notify::notifiers::NotifierConfig;
+
+    #[test]
+    fn test_load_from_missing_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let config: Config = Config::load_from(&directory.path().join("config.toml")).unwrap();
+
+        assert_eq!(config.notify, NotifyConfig::default());
+    }
+
+    #[test]
+    fn test_load_from_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("config.toml");
+        fs::write(
+            &path,
+            r##"
+            theme = "ocean"
+
+            [themes.ocean]
+            extends = "light"
+            accent = "#0087af"
+
+            [account]
+            username = "alice"
+            password_command = "pass show news.ycombinator.com"
+
+            [notify]
+            story_types = ["new", "show"]
+            interval = 60
+
+            [[notify.rules]]
+            name = "Our product"
+            keywords = ["hn-stories"]
+            domains = ["github.com"]
+
+            [[notify.notifiers]]
+            type = "webhook"
+            url = "https://hooks.example.com/hn"
+            "##,
+        )
+        .unwrap();
+
+        let config: Config = Config::load_from(&path).unwrap();
+
+        assert_eq!(config.theme.as_deref(), Some("ocean"));
+        assert_eq!(config.themes["ocean"].extends.as_deref(), Some("light"));
+        assert_eq!(config.account.username.as_deref(), Some("alice"));
+        assert_eq!(
+            config.account.password_command.as_deref(),
+            Some("pass show news.ycombinator.com")
+        );
+        assert_eq!(
+            config.notify.story_types,
+            vec![HackerNewsStoryType::N, HackerNewsStoryType::S]
+        );
+        assert_eq!(config.notify.interval, 60);
+        assert_eq!(config.notify.limit, 100);
+        assert_eq!(config.notify.rules[0].label(), "Our product");
+        assert_eq!(
+            config.notify.notifiers,
+            vec![NotifierConfig::Webhook {
+                url: "https://hooks.example.com/hn".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_load_from_invalid_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("config.toml");
+        fs::write(&path, "[notify]\nunknown_setting = true\n").unwrap();
+
+        let error: String = Config::load_from(&path).unwrap_err().to_string();
+
+        assert!(error.contains("Invalid config file"));
+    }
+}
@@ -0,0 +1,255 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use clap::ValueEnum;
+use minijinja::{AutoEscape, Environment, escape_formatter};
+use serde::Serialize;
+
+use crate::export::escape_xml;
+use crate::models::story::StoryItem;
+use crate::types::HnResult;
+use crate::utils::constants::Y_COMBINATOR_BASE_URL;
+use crate::utils::time::relative_age;
+
+/// Built-in HTML digest template
+const DEFAULT_HTML_TEMPLATE: &str = include_str!("templates/default.html");
+/// Built-in Markdown digest template
+const DEFAULT_MARKDOWN_TEMPLATE: &str = include_str!("templates/default.md");
+/// Name the digest template is registered under
+const TEMPLATE_NAME: &str = "digest";
+
+/// Digest document formats (html, markdown)
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum DigestFormat {
+    /// Self-contained HTML page
+    Html,
+
+    /// Markdown document
+    #[value(alias = "md")]
+    Markdown,
+}
+
+/// Values available to digest templates
+#[derive(Serialize)]
+pub struct DigestContext {
+    pub title: String,
+    pub story_type: String,
+    pub generated_at: String,
+    pub stories: Vec<DigestStory>,
+}
+
+/// A story as seen by digest templates
+#[derive(Serialize)]
+pub struct DigestStory {
+    pub rank: usize,
+    pub id: u64,
+    pub title: String,
+    pub url: Option<String>,
+    pub domain: Option<String>,
+    pub score: i32,
+    pub comments: u32,
+    pub by: Option<String>,
+    pub author_url: Option<String>,
+    pub discussion_url: String,
+    /// Creation time in RFC 3339 format
+    pub published: Option<String>,
+    /// Age when the digest was generated, e.g. "3 hours ago"
+    pub age: Option<String>,
+}
+
+impl DigestContext {
+    pub fn new(
+        title: String,
+        list_name: &str,
+        generated_at: DateTime<Utc>,
+        stories: &[StoryItem],
+    ) -> Self {
+        let stories: Vec<DigestStory> = stories
+            .iter()
+            .enumerate()
+            .map(|(index, story)| DigestStory {
+                rank: index + 1,
+                id: story.id,
+                title: story
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| "No Title".to_string()),
+                url: story.url.clone(),
+                domain: story.domain(),
+                score: story.score.unwrap_or(0),
+                comments: story.descendants.unwrap_or(0),
+                by: story.by.clone(),
+                author_url: story
+                    .by
+                    .as_ref()
+                    .map(|by| format!("{}/user?id={}", Y_COMBINATOR_BASE_URL, by)),
+                discussion_url: story.discussion_url(),
+                published: story
+                    .time
+                    .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true)),
+                age: story.time.map(|time| relative_age(time, generated_at)),
+            })
+            .collect();
+
+        return Self {
+            title,
+            story_type: list_name.to_string(),
+            generated_at: generated_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+            stories,
+        };
+    }
+}
+
+/// Render a digest with the built-in template for the format, or a custom template
+///
+/// Templates use Jinja2 syntax. Values are HTML-escaped for the HTML format, and the
+/// `md` filter escapes Markdown punctuation.
+pub fn render_digest(
+    format: DigestFormat,
+    template: Option<&str>,
+    context: &DigestContext,
+) -> HnResult<String> {
+    let source: &str = template.unwrap_or(match format {
+        DigestFormat::Html => DEFAULT_HTML_TEMPLATE,
+        DigestFormat::Markdown => DEFAULT_MARKDOWN_TEMPLATE,
+    });
+
+    let mut environment: Environment = Environment::new();
+    environment.set_auto_escape_callback(move |_| match format {
+        DigestFormat::Html => AutoEscape::Html,
+        DigestFormat::Markdown => AutoEscape::None,
+    });
+    // Escape strings without minijinja's `&#x2f;` so URLs stay readable in the source
+    environment.set_formatter(|output, state, value| {
+        if state.auto_escape() == AutoEscape::Html
+            && !value.is_safe()
+            && let Some(text) = value.as_str()
+        {
+            output.write_str(&escape_xml(text))?;
+            return Ok(());
+        }
+
+        return escape_formatter(output, state, value);
+    });
+    environment.add_filter("md", escape_markdown);
+    environment
+        .add_template(TEMPLATE_NAME, source)
+        .map_err(|error| format!("Invalid digest template: {error}"))?;
+
+    let rendered: String = environment
+        .get_template(TEMPLATE_NAME)?
+        .render(context)
+        .map_err(|error| format!("Failed to render digest template: {error}"))?;
+
+    return Ok(rendered);
+}
+
+/// Escape Markdown punctuation so text renders literally
+fn escape_markdown(text: String) -> String {
+    let mut escaped: String = String::with_capacity(text.len());
+    for character in text.chars() {
+        if matches!(
+            character,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+
+    return escaped;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    use crate::models::story::HackerNewsStoryType;
+
+    fn context() -> DigestContext {
+        let stories: Vec<StoryItem> = vec![
+            StoryItem {
+                id: 1,
+                by: Some("alice".to_string()),
+                url: Some("https://www.example.com/post".to_string()),
+                score: Some(120),
+                title: Some("Tips & tricks for <script> [tags]".to_string()),
+                descendants: Some(1),
+                time: Some(Utc.with_ymd_and_hms(2026, 10, 19, 6, 0, 0).unwrap()),
+                ..Default::default()
+            },
+            StoryItem {
+                id: 2,
+                score: Some(1),
+                title: Some("Ask HN: Text post".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        return DigestContext::new(
+            "Weekly digest".to_string(),
+            HackerNewsStoryType::B.full_name(),
+            Utc.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap(),
+            &stories,
+        );
+    }
+
+    #[test]
+    fn test_render_digest_html() {
+        let html: String = render_digest(DigestFormat::Html, None, &context()).unwrap();
+
+        assert!(html.contains("<title>Weekly digest</title>"));
+        assert!(html.contains("Tips &amp; tricks for &lt;script&gt; [tags]"));
+        assert!(html.contains(r#"<span class="domain">(example.com)</span>"#));
+        assert!(html.contains("120 points by"));
+        assert!(html.contains(r#"<time datetime="2026-10-19T06:00:00Z">3 hours ago</time>"#));
+        assert!(html.contains(">1 comment</a>"));
+        // Text posts link to the discussion
+        assert!(html.contains(r#"<a href="https://news.ycombinator.com/item?id=2">Ask HN"#));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn test_render_digest_markdown() {
+        let markdown: String = render_digest(DigestFormat::Markdown, None, &context()).unwrap();
+
+        assert!(markdown.starts_with("# Weekly digest\n"));
+        assert!(markdown.contains(
+            r"1. [Tips & tricks for \<script\> \[tags\]](https://www.example.com/post) (example.com)"
+        ));
+        assert!(markdown.contains("[1 comment](https://news.ycombinator.com/item?id=1)"));
+        assert!(
+            markdown.contains("2. [Ask HN: Text post](https://news.ycombinator.com/item?id=2)")
+        );
+        assert!(
+            markdown
+                .contains("by [alice](https://news.ycombinator.com/user?id=alice) 3 hours ago |")
+        );
+        assert!(markdown.contains("1 point | [0 comments]"));
+    }
+
+    #[test]
+    fn test_render_digest_custom_template() {
+        let template: &str =
+            "{% for story in stories %}{{ story.rank }}:{{ story.title }};{% endfor %}";
+        let rendered: String =
+            render_digest(DigestFormat::Html, Some(template), &context()).unwrap();
+
+        assert_eq!(
+            rendered,
+            "1:Tips &amp; tricks for &lt;script&gt; [tags];2:Ask HN: Text post;"
+        );
+    }
+
+    #[test]
+    fn test_render_digest_invalid_template() {
+        let result: HnResult<String> =
+            render_digest(DigestFormat::Markdown, Some("{% for %}"), &context());
+
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Invalid digest template")
+        );
+    }
+}
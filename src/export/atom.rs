@@ -0,0 +1,142 @@
+use chrono::SecondsFormat;
+
+use crate::export::{FeedEntry, FeedMetadata, GENERATOR, escape_xml};
+
+/// Render an Atom 1.0 document
+pub fn render(metadata: &FeedMetadata, entries: &[FeedEntry]) -> String {
+    let updated: String = metadata
+        .generated_at
+        .to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    let mut xml: String = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str(&format!(
+        "  <title>{}</title>\n",
+        escape_xml(&metadata.title)
+    ));
+    xml.push_str(&format!(
+        "  <subtitle>{}</subtitle>\n",
+        escape_xml(&metadata.description)
+    ));
+    xml.push_str(&format!(
+        "  <link rel=\"alternate\" href=\"{}\"/>\n",
+        escape_xml(&metadata.site_url)
+    ));
+    xml.push_str(&format!("  <id>{}</id>\n", escape_xml(&metadata.site_url)));
+    xml.push_str(&format!("  <updated>{}</updated>\n", updated));
+    xml.push_str("  <author><name>Hacker News</name></author>\n");
+    xml.push_str(&format!("  <generator>{}</generator>\n", GENERATOR));
+
+    for entry in entries {
+        xml.push_str("  <entry>\n");
+        xml.push_str(&format!(
+            "    <title>{}</title>\n",
+            escape_xml(&entry.title)
+        ));
+        xml.push_str(&format!(
+            "    <link rel=\"alternate\" href=\"{}\"/>\n",
+            escape_xml(entry.link())
+        ));
+        if entry.article_url.is_some() {
+            xml.push_str(&format!(
+                "    <link rel=\"related\" href=\"{}\"/>\n",
+                escape_xml(&entry.discussion_url)
+            ));
+        }
+        xml.push_str(&format!(
+            "    <id>{}</id>\n",
+            escape_xml(&entry.discussion_url)
+        ));
+        // Stories are not edited after they are posted, so they were last updated then
+        match entry.published {
+            Some(published) => {
+                let published: String = published.to_rfc3339_opts(SecondsFormat::Secs, true);
+                xml.push_str(&format!("    <published>{}</published>\n", published));
+                xml.push_str(&format!("    <updated>{}</updated>\n", published));
+            }
+            None => xml.push_str(&format!("    <updated>{}</updated>\n", updated)),
+        }
+        if let Some(author) = &entry.author {
+            xml.push_str(&format!(
+                "    <author><name>{}</name></author>\n",
+                escape_xml(author)
+            ));
+        }
+        xml.push_str(&format!(
+            "    <summary type=\"html\">{}</summary>\n",
+            escape_xml(&entry.summary_html())
+        ));
+        xml.push_str("  </entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+
+    return xml;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::tests::{sample_metadata, sample_stories};
+
+    #[test]
+    fn test_render_atom() {
+        let entries: Vec<FeedEntry> = sample_stories().iter().map(FeedEntry::new).collect();
+        let xml: String = render(&sample_metadata(), &entries);
+
+        let document = roxmltree::Document::parse(&xml).unwrap();
+        let root = document.root_element();
+        assert_eq!(
+            root.tag_name().namespace(),
+            Some("http://www.w3.org/2005/Atom")
+        );
+
+        let entries: Vec<roxmltree::Node> = root
+            .children()
+            .filter(|node| node.has_tag_name("entry"))
+            .collect();
+        assert_eq!(entries.len(), 2);
+
+        let links: Vec<(&str, &str)> = entries[0]
+            .children()
+            .filter(|node| node.has_tag_name("link"))
+            .map(|node| {
+                (
+                    node.attribute("rel").unwrap(),
+                    node.attribute("href").unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                ("alternate", "http://www.getdropbox.com/u/2/screencast.html"),
+                ("related", "https://news.ycombinator.com/item?id=8863"),
+            ]
+        );
+
+        let child_text = |node: roxmltree::Node, name: &str| {
+            return node
+                .children()
+                .find(|child| child.has_tag_name(name))
+                .and_then(|child| child.text())
+                .map(str::to_string);
+        };
+
+        // Entries use the story time, or the feed time when it is unknown
+        assert_eq!(
+            child_text(entries[0], "published").as_deref(),
+            Some("2007-04-04T19:16:40Z")
+        );
+        assert_eq!(
+            child_text(entries[0], "updated").as_deref(),
+            Some("2007-04-04T19:16:40Z")
+        );
+        assert_eq!(child_text(entries[1], "published"), None);
+        assert_eq!(
+            child_text(entries[1], "updated").as_deref(),
+            Some("2026-10-19T12:30:00Z")
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::SecondsFormat;
+use serde::Serialize;
+
+use crate::export::{FeedEntry, FeedMetadata};
+use crate::types::HnResult;
+
+/// JSON Feed version URL
+const JSON_FEED_VERSION: &str = "https://jsonfeed.org/version/1.1";
+
+#[derive(Serialize)]
+struct JsonFeed<'a> {
+    version: &'static str,
+    title: &'a str,
+    home_page_url: &'a str,
+    description: &'a str,
+    items: Vec<JsonFeedItem<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedItem<'a> {
+    id: &'a str,
+    url: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    external_url: Option<&'a str>,
+    title: &'a str,
+    content_html: String,
+    summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date_published: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    authors: Vec<JsonFeedAuthor<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedAuthor<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<&'a str>,
+}
+
+/// Render a JSON Feed 1.1 document
+pub fn render(metadata: &FeedMetadata, entries: &[FeedEntry]) -> HnResult<String> {
+    let items: Vec<JsonFeedItem> = entries
+        .iter()
+        .map(|entry| JsonFeedItem {
+            id: &entry.discussion_url,
+            url: &entry.discussion_url,
+            external_url: entry.article_url.as_deref(),
+            title: &entry.title,
+            content_html: entry.summary_html(),
+            summary: entry.summary_text(),
+            date_published: entry
+                .published
+                .map(|published| published.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            authors: entry
+                .author
+                .as_deref()
+                .map(|name| JsonFeedAuthor {
+                    name,
+                    url: entry.author_url.as_deref(),
+                })
+                .into_iter()
+                .collect(),
+        })
+        .collect();
+
+    let feed: JsonFeed = JsonFeed {
+        version: JSON_FEED_VERSION,
+        title: &metadata.title,
+        home_page_url: &metadata.site_url,
+        description: &metadata.description,
+        items,
+    };
+
+    return Ok(serde_json::to_string_pretty(&feed)?);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::tests::{sample_metadata, sample_stories};
+
+    #[test]
+    fn test_render_json_feed() {
+        let entries: Vec<FeedEntry> = sample_stories().iter().map(FeedEntry::new).collect();
+        let json: String = render(&sample_metadata(), &entries).unwrap();
+
+        let feed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(feed["version"], JSON_FEED_VERSION);
+        assert_eq!(feed["home_page_url"], "https://news.ycombinator.com/news");
+        assert_eq!(
+            feed["items"][0]["external_url"],
+            "http://www.getdropbox.com/u/2/screencast.html"
+        );
+        assert_eq!(
+            feed["items"][0]["url"],
+            "https://news.ycombinator.com/item?id=8863"
+        );
+        assert_eq!(feed["items"][0]["authors"][0]["name"], "dhouston");
+        assert_eq!(
+            feed["items"][0]["summary"],
+            "111 points by dhouston | 71 comments"
+        );
+        assert_eq!(feed["items"][0]["date_published"], "2007-04-04T19:16:40Z");
+        // Text posts have no external article
+        assert!(feed["items"][1].get("external_url").is_none());
+        assert!(feed["items"][1].get("date_published").is_none());
+    }
+}
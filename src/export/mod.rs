@@ -0,0 +1,240 @@
+pub mod atom;
+pub mod json_feed;
+pub mod rss;
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+
+use crate::models::story::{HackerNewsStoryType, StoryItem};
+use crate::types::HnResult;
+use crate::utils::constants::Y_COMBINATOR_BASE_URL;
+
+/// Generator name written into feed documents
+const GENERATOR: &str = concat!("hn-stories ", env!("CARGO_PKG_VERSION"));
+
+/// Feed document formats (rss, atom, jsonfeed)
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum FeedFormat {
+    /// RSS 2.0
+    Rss,
+
+    /// Atom 1.0
+    Atom,
+
+    /// JSON Feed 1.1
+    #[value(name = "jsonfeed", alias = "json")]
+    JsonFeed,
+}
+
+/// Feed-level metadata derived from the story type
+pub struct FeedMetadata {
+    pub title: String,
+    pub description: String,
+    pub site_url: String,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl FeedMetadata {
+    pub fn new(story_type: &HackerNewsStoryType, generated_at: DateTime<Utc>) -> Self {
+        return Self {
+            title: format!("Hacker News: {} stories", story_type.full_name()),
+            description: format!("{} stories from Hacker News", story_type.full_name()),
+            site_url: format!("{}{}", Y_COMBINATOR_BASE_URL, story_type.site_path()),
+            generated_at,
+        };
+    }
+}
+
+/// A feed entry built from a story item
+pub struct FeedEntry {
+    pub title: String,
+    pub article_url: Option<String>,
+    pub discussion_url: String,
+    pub author: Option<String>,
+    pub author_url: Option<String>,
+    pub score: i32,
+    pub comments: u32,
+    pub published: Option<DateTime<Utc>>,
+}
+
+impl FeedEntry {
+    pub fn new(story: &StoryItem) -> Self {
+        return Self {
+            title: story
+                .title
+                .clone()
+                .unwrap_or_else(|| "No Title".to_string()),
+            article_url: story.url.clone(),
+            discussion_url: story.discussion_url(),
+            author: story.by.clone(),
+            author_url: story
+                .by
+                .as_ref()
+                .map(|by| format!("{}/user?id={}", Y_COMBINATOR_BASE_URL, by)),
+            score: story.score.unwrap_or(0),
+            comments: story.descendants.unwrap_or(0),
+            published: story.time,
+        };
+    }
+
+    /// The entry's main link: the article, or the discussion for text posts
+    pub fn link(&self) -> &str {
+        return self.article_url.as_deref().unwrap_or(&self.discussion_url);
+    }
+
+    /// Plain text summary with the author, score and comment count
+    pub fn summary_text(&self) -> String {
+        return format!(
+            "{} by {} | {}",
+            pluralize(self.score.unsigned_abs(), "point"),
+            self.author.as_deref().unwrap_or("Unknown"),
+            pluralize(self.comments, "comment")
+        );
+    }
+
+    /// HTML summary linking to the article, the discussion and the author
+    pub fn summary_html(&self) -> String {
+        let author: String = match (&self.author, &self.author_url) {
+            (Some(author), Some(url)) => format!(
+                r#"<a href="{}">{}</a>"#,
+                escape_xml(url),
+                escape_xml(author)
+            ),
+            _ => "Unknown".to_string(),
+        };
+
+        let mut html: String = format!(
+            r#"<p>{} by {} | <a href="{}">{}</a></p>"#,
+            pluralize(self.score.unsigned_abs(), "point"),
+            author,
+            escape_xml(&self.discussion_url),
+            pluralize(self.comments, "comment")
+        );
+
+        if let Some(url) = &self.article_url {
+            html.push_str(&format!(
+                r#"<p>Article: <a href="{0}">{0}</a></p>"#,
+                escape_xml(url)
+            ));
+        }
+
+        return html;
+    }
+}
+
+/// Render stories as a feed document
+pub fn render_feed(
+    format: FeedFormat,
+    metadata: &FeedMetadata,
+    stories: &[StoryItem],
+) -> HnResult<String> {
+    let entries: Vec<FeedEntry> = stories.iter().map(FeedEntry::new).collect();
+
+    return match format {
+        FeedFormat::Rss => Ok(rss::render(metadata, &entries)),
+        FeedFormat::Atom => Ok(atom::render(metadata, &entries)),
+        FeedFormat::JsonFeed => json_feed::render(metadata, &entries),
+    };
+}
+
+/// Format a count with a singular or plural noun (e.g. "1 point", "2 points")
+fn pluralize(count: u32, noun: &str) -> String {
+    let suffix: &str = if count == 1 { "" } else { "s" };
+    return format!("{count} {noun}{suffix}");
+}
+
+/// Escape text for use in XML/HTML content and attribute values
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped: String = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(character),
+        }
+    }
+
+    return escaped;
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Stories used by the feed format tests
+    pub fn sample_stories() -> Vec<StoryItem> {
+        return vec![
+            StoryItem {
+                id: 8863,
+                by: Some("dhouston".to_string()),
+                url: Some("http://www.getdropbox.com/u/2/screencast.html".to_string()),
+                score: Some(111),
+                title: Some("My YC app: Dropbox - Throw away your USB drive".to_string()),
+                descendants: Some(71),
+                time: Some(Utc.with_ymd_and_hms(2007, 4, 4, 19, 16, 40).unwrap()),
+                ..Default::default()
+            },
+            StoryItem {
+                id: 121003,
+                by: Some("tel".to_string()),
+                score: Some(1),
+                title: Some("Ask HN: The Arc <Effect> & \"quotes\"".to_string()),
+                descendants: Some(1),
+                ..Default::default()
+            },
+        ];
+    }
+
+    /// Metadata used by the feed format tests
+    pub fn sample_metadata() -> FeedMetadata {
+        return FeedMetadata::new(
+            &HackerNewsStoryType::T,
+            Utc.with_ymd_and_hms(2026, 10, 19, 12, 30, 0).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_feed_metadata() {
+        let metadata: FeedMetadata = sample_metadata();
+
+        assert_eq!(metadata.title, "Hacker News: Top stories");
+        assert_eq!(metadata.site_url, "https://news.ycombinator.com/news");
+    }
+
+    #[test]
+    fn test_feed_entry_summary() {
+        let stories: Vec<StoryItem> = sample_stories();
+        let entry: FeedEntry = FeedEntry::new(&stories[0]);
+
+        assert_eq!(
+            entry.link(),
+            "http://www.getdropbox.com/u/2/screencast.html"
+        );
+        assert_eq!(entry.summary_text(), "111 points by dhouston | 71 comments");
+        assert!(
+            entry
+                .summary_html()
+                .contains(r#"<a href="https://news.ycombinator.com/item?id=8863">71 comments</a>"#)
+        );
+
+        // Text posts link to the discussion
+        let text_post: FeedEntry = FeedEntry::new(&stories[1]);
+        assert_eq!(
+            text_post.link(),
+            "https://news.ycombinator.com/item?id=121003"
+        );
+        assert_eq!(text_post.summary_text(), "1 point by tel | 1 comment");
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(
+            escape_xml(r#"<a href="x">Tom & Jerry's</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&apos;s&lt;/a&gt;"
+        );
+    }
+}
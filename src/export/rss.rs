@@ -0,0 +1,131 @@
+use crate::export::{FeedEntry, FeedMetadata, GENERATOR, escape_xml};
+
+/// Render an RSS 2.0 document
+pub fn render(metadata: &FeedMetadata, entries: &[FeedEntry]) -> String {
+    let mut xml: String = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n");
+    xml.push_str("  <channel>\n");
+    xml.push_str(&format!(
+        "    <title>{}</title>\n",
+        escape_xml(&metadata.title)
+    ));
+    xml.push_str(&format!(
+        "    <link>{}</link>\n",
+        escape_xml(&metadata.site_url)
+    ));
+    xml.push_str(&format!(
+        "    <description>{}</description>\n",
+        escape_xml(&metadata.description)
+    ));
+    xml.push_str(&format!(
+        "    <lastBuildDate>{}</lastBuildDate>\n",
+        metadata.generated_at.to_rfc2822()
+    ));
+    xml.push_str(&format!("    <generator>{}</generator>\n", GENERATOR));
+
+    for entry in entries {
+        xml.push_str("    <item>\n");
+        xml.push_str(&format!(
+            "      <title>{}</title>\n",
+            escape_xml(&entry.title)
+        ));
+        xml.push_str(&format!(
+            "      <link>{}</link>\n",
+            escape_xml(entry.link())
+        ));
+        xml.push_str(&format!(
+            "      <comments>{}</comments>\n",
+            escape_xml(&entry.discussion_url)
+        ));
+        xml.push_str(&format!(
+            "      <guid isPermaLink=\"true\">{}</guid>\n",
+            escape_xml(&entry.discussion_url)
+        ));
+        if let Some(published) = entry.published {
+            xml.push_str(&format!(
+                "      <pubDate>{}</pubDate>\n",
+                published.to_rfc2822()
+            ));
+        }
+        if let Some(author) = &entry.author {
+            xml.push_str(&format!(
+                "      <dc:creator>{}</dc:creator>\n",
+                escape_xml(author)
+            ));
+        }
+        xml.push_str(&format!(
+            "      <description>{}</description>\n",
+            escape_xml(&entry.summary_html())
+        ));
+        xml.push_str("    </item>\n");
+    }
+
+    xml.push_str("  </channel>\n");
+    xml.push_str("</rss>\n");
+
+    return xml;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::tests::{sample_metadata, sample_stories};
+
+    #[test]
+    fn test_render_rss() {
+        let entries: Vec<FeedEntry> = sample_stories().iter().map(FeedEntry::new).collect();
+        let xml: String = render(&sample_metadata(), &entries);
+
+        let document = roxmltree::Document::parse(&xml).unwrap();
+        let channel = document
+            .descendants()
+            .find(|node| node.has_tag_name("channel"))
+            .unwrap();
+        let child_text = |node: roxmltree::Node, name: &str| {
+            return node
+                .children()
+                .find(|child| child.tag_name().name() == name)
+                .and_then(|child| child.text())
+                .map(str::to_string);
+        };
+
+        assert_eq!(
+            child_text(channel, "title").as_deref(),
+            Some("Hacker News: Top stories")
+        );
+        assert_eq!(
+            child_text(channel, "lastBuildDate").as_deref(),
+            Some("Mon, 19 Oct 2026 12:30:00 +0000")
+        );
+
+        let items: Vec<roxmltree::Node> = channel
+            .children()
+            .filter(|node| node.has_tag_name("item"))
+            .collect();
+        assert_eq!(items.len(), 2);
+        assert_eq!(
+            child_text(items[0], "link").as_deref(),
+            Some("http://www.getdropbox.com/u/2/screencast.html")
+        );
+        assert_eq!(
+            child_text(items[0], "comments").as_deref(),
+            Some("https://news.ycombinator.com/item?id=8863")
+        );
+        assert_eq!(child_text(items[0], "creator").as_deref(), Some("dhouston"));
+        assert_eq!(
+            child_text(items[0], "pubDate").as_deref(),
+            Some("Wed, 4 Apr 2007 19:16:40 +0000")
+        );
+        assert_eq!(child_text(items[1], "pubDate"), None);
+        assert!(
+            child_text(items[0], "description")
+                .unwrap()
+                .contains("111 points")
+        );
+        assert_eq!(
+            child_text(items[1], "title").as_deref(),
+            Some("Ask HN: The Arc <Effect> & \"quotes\"")
+        );
+    }
+}
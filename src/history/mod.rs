@@ -0,0 +1,53 @@
+pub mod store;
+pub mod trend;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::story::{HackerNewsStoryType, StoryItem};
+
+/// A story's rank and stats at the time of a snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    /// 1-based rank within the story list
+    pub rank: usize,
+    #[serde(flatten)]
+    pub item: StoryItem,
+}
+
+/// A ranked story list recorded at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub taken_at: DateTime<Utc>,
+    pub story_type: HackerNewsStoryType,
+    pub entries: Vec<SnapshotEntry>,
+}
+
+impl Snapshot {
+    /// Create a snapshot from a ranked list of stories
+    pub fn new(
+        taken_at: DateTime<Utc>,
+        story_type: HackerNewsStoryType,
+        stories: Vec<StoryItem>,
+    ) -> Self {
+        let entries: Vec<SnapshotEntry> = stories
+            .into_iter()
+            .enumerate()
+            .map(|(index, item)| SnapshotEntry {
+                rank: index + 1,
+                item,
+            })
+            .collect();
+
+        return Self {
+            taken_at,
+            story_type,
+            entries,
+        };
+    }
+
+    /// Find a story's entry in the snapshot
+    pub fn entry(&self, id: u64) -> Option<&SnapshotEntry> {
+        return self.entries.iter().find(|entry| entry.item.id == id);
+    }
+}
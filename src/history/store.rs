@@ -0,0 +1,142 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::history::Snapshot;
+use crate::models::story::HackerNewsStoryType;
+use crate::types::HnResult;
+use crate::utils::json_store::read_if_exists;
+use crate::utils::paths::data_dir;
+
+/// File name of the snapshot store within the data directory
+const SNAPSHOTS_FILE_NAME: &str = "snapshots.jsonl";
+
+/// Append-only time-series store of story list snapshots (one JSON document per line)
+pub struct SnapshotStore {
+    path: PathBuf,
+}
+
+impl SnapshotStore {
+    pub fn new(path: PathBuf) -> Self {
+        return Self { path };
+    }
+
+    /// Open the snapshots in the data directory
+    pub fn open_default() -> HnResult<Self> {
+        return Ok(Self::new(data_dir()?.join(SNAPSHOTS_FILE_NAME)));
+    }
+
+    /// Append a snapshot to the store
+    pub fn append(&self, snapshot: &Snapshot) -> HnResult<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+
+        writeln!(file, "{}", serde_json::to_string(snapshot)?)?;
+
+        return Ok(());
+    }
+
+    /// Load all snapshots of a story type, oldest first
+    pub fn load(&self, story_type: HackerNewsStoryType) -> HnResult<Vec<Snapshot>> {
+        let mut snapshots: Vec<Snapshot> = self.load_all()?;
+        snapshots.retain(|snapshot| snapshot.story_type == story_type);
+
+        return Ok(snapshots);
+    }
+
+    /// Load the snapshots of every story type, oldest first
+    pub fn load_all(&self) -> HnResult<Vec<Snapshot>> {
+        let Some(contents) = read_if_exists(&self.path)? else {
+            return Ok(Vec::new());
+        };
+
+        let mut snapshots: Vec<Snapshot> = Vec::new();
+        for (index, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let snapshot: Snapshot = serde_json::from_str(line).map_err(|error| {
+                format!(
+                    "Corrupt snapshot on line {} of {}: {error}",
+                    index + 1,
+                    self.path.display()
+                )
+            })?;
+
+            snapshots.push(snapshot);
+        }
+
+        snapshots.sort_by_key(|snapshot| snapshot.taken_at);
+
+        return Ok(snapshots);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use std::fs;
+
+    use crate::models::story::StoryItem;
+
+    fn story(id: u64, score: i32) -> StoryItem {
+        return StoryItem {
+            id,
+            score: Some(score),
+            descendants: Some(2),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_snapshot_store_round_trip() {
+        let directory = tempfile::tempdir().unwrap();
+        let store: SnapshotStore = SnapshotStore::new(directory.path().join("snapshots.jsonl"));
+
+        // Missing file means no snapshots yet
+        assert!(store.load(HackerNewsStoryType::T).unwrap().is_empty());
+
+        let later: Snapshot = Snapshot::new(
+            Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap(),
+            HackerNewsStoryType::T,
+            vec![story(2, 50), story(1, 40)],
+        );
+        let earlier: Snapshot = Snapshot::new(
+            Utc.with_ymd_and_hms(2026, 10, 19, 11, 0, 0).unwrap(),
+            HackerNewsStoryType::T,
+            vec![story(1, 30)],
+        );
+        let other_type: Snapshot = Snapshot::new(
+            Utc.with_ymd_and_hms(2026, 10, 19, 11, 30, 0).unwrap(),
+            HackerNewsStoryType::S,
+            vec![story(3, 5)],
+        );
+
+        store.append(&later).unwrap();
+        store.append(&earlier).unwrap();
+        store.append(&other_type).unwrap();
+
+        let snapshots: Vec<Snapshot> = store.load(HackerNewsStoryType::T).unwrap();
+
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].taken_at, earlier.taken_at);
+        assert_eq!(snapshots[1].entries[1].rank, 2);
+        assert_eq!(snapshots[1].entries[1].item.id, 1);
+        assert_eq!(snapshots[1].entries[1].item.descendants, Some(2));
+    }
+
+    #[test]
+    fn test_snapshot_store_corrupt_line() {
+        let directory = tempfile::tempdir().unwrap();
+        let path: PathBuf = directory.path().join("snapshots.jsonl");
+        fs::write(&path, "not json\n").unwrap();
+
+        let result: HnResult<Vec<Snapshot>> = SnapshotStore::new(path).load(HackerNewsStoryType::T);
+
+        assert!(result.unwrap_err().to_string().contains("line 1"));
+    }
+}
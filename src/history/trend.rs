@@ -0,0 +1,167 @@
+use chrono::{DateTime, Utc};
+
+use crate::history::{Snapshot, SnapshotEntry};
+use crate::models::story::StoryItem;
+
+/// Characters used to draw sparklines, lowest to highest
+const SPARKLINE_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// A story's rank and stats in a single snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrendPoint {
+    pub taken_at: DateTime<Utc>,
+    pub rank: usize,
+    pub score: i32,
+    pub comments: u32,
+}
+
+/// A story whose score grew between two snapshots
+#[derive(Debug, Clone)]
+pub struct RisingStory {
+    pub item: StoryItem,
+    pub rank: usize,
+    pub previous_rank: usize,
+    /// Score velocity in points per hour
+    pub points_per_hour: f64,
+}
+
+/// Get a story's rank and stats over time from a list of snapshots (oldest first)
+pub fn story_trend(snapshots: &[Snapshot], id: u64) -> Vec<TrendPoint> {
+    return snapshots
+        .iter()
+        .filter_map(|snapshot| {
+            let entry: &SnapshotEntry = snapshot.entry(id)?;
+            return Some(TrendPoint {
+                taken_at: snapshot.taken_at,
+                rank: entry.rank,
+                score: entry.item.score.unwrap_or(0),
+                comments: entry.item.descendants.unwrap_or(0),
+            });
+        })
+        .collect();
+}
+
+/// Rank stories present in both snapshots by score velocity, fastest rising first
+pub fn rising_stories(previous: &Snapshot, latest: &Snapshot) -> Vec<RisingStory> {
+    let elapsed_seconds: i64 = (latest.taken_at - previous.taken_at).num_seconds();
+    if elapsed_seconds <= 0 {
+        return Vec::new();
+    }
+
+    let elapsed_hours: f64 = elapsed_seconds as f64 / 3600.0;
+
+    let mut rising: Vec<RisingStory> = latest
+        .entries
+        .iter()
+        .filter_map(|entry| {
+            let previous_entry: &SnapshotEntry = previous.entry(entry.item.id)?;
+            let gained: i32 =
+                entry.item.score.unwrap_or(0) - previous_entry.item.score.unwrap_or(0);
+
+            return Some(RisingStory {
+                item: entry.item.clone(),
+                rank: entry.rank,
+                previous_rank: previous_entry.rank,
+                points_per_hour: f64::from(gained) / elapsed_hours,
+            });
+        })
+        .collect();
+
+    rising.sort_by(|a, b| b.points_per_hour.total_cmp(&a.points_per_hour));
+
+    return rising;
+}
+
+/// Draw values as a single-line sparkline, scaled between their min and max
+pub fn sparkline(values: &[f64]) -> String {
+    let min: f64 = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max: f64 = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range: f64 = max - min;
+    let top: usize = SPARKLINE_BARS.len() - 1;
+
+    return values
+        .iter()
+        .map(|value| {
+            if range <= 0.0 {
+                return SPARKLINE_BARS[top / 2];
+            }
+
+            let level: usize = (((value - min) / range) * top as f64).round() as usize;
+            return SPARKLINE_BARS[level.min(top)];
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    use crate::models::story::HackerNewsStoryType;
+
+    fn story(id: u64, score: i32) -> StoryItem {
+        return StoryItem {
+            id,
+            score: Some(score),
+            ..Default::default()
+        };
+    }
+
+    fn snapshot(hours: i64, stories: Vec<StoryItem>) -> Snapshot {
+        let start: DateTime<Utc> = Utc.with_ymd_and_hms(2026, 10, 19, 8, 0, 0).unwrap();
+        return Snapshot::new(
+            start + Duration::hours(hours),
+            HackerNewsStoryType::T,
+            stories,
+        );
+    }
+
+    #[test]
+    fn test_story_trend() {
+        let snapshots: Vec<Snapshot> = vec![
+            snapshot(0, vec![story(1, 10), story(2, 5)]),
+            snapshot(1, vec![story(3, 1)]),
+            snapshot(2, vec![story(2, 50), story(1, 20)]),
+        ];
+
+        let trend: Vec<TrendPoint> = story_trend(&snapshots, 2);
+
+        assert_eq!(trend.len(), 2);
+        assert_eq!((trend[0].rank, trend[0].score), (2, 5));
+        assert_eq!((trend[1].rank, trend[1].score), (1, 50));
+        assert!(story_trend(&snapshots, 99).is_empty());
+    }
+
+    #[test]
+    fn test_rising_stories() {
+        let previous: Snapshot = snapshot(0, vec![story(1, 100), story(2, 10), story(3, 5)]);
+        let latest: Snapshot = snapshot(2, vec![story(2, 70), story(1, 120), story(4, 500)]);
+
+        let rising: Vec<RisingStory> = rising_stories(&previous, &latest);
+        let ids: Vec<u64> = rising.iter().map(|story| story.item.id).collect();
+
+        // Story 4 is new and story 3 dropped off, so neither has a velocity
+        assert_eq!(ids, vec![2, 1]);
+        assert_eq!(rising[0].points_per_hour, 30.0);
+        assert_eq!((rising[0].previous_rank, rising[0].rank), (2, 1));
+        assert_eq!(rising[1].points_per_hour, 10.0);
+    }
+
+    #[test]
+    fn test_rising_stories_requires_elapsed_time() {
+        let previous: Snapshot = snapshot(1, vec![story(1, 100)]);
+        let latest: Snapshot = snapshot(1, vec![story(1, 120)]);
+
+        assert!(rising_stories(&previous, &latest).is_empty());
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(
+            sparkline(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]),
+            "▁▂▃▄▅▆▇█"
+        );
+        assert_eq!(sparkline(&[5.0, 5.0]), "▄▄");
+        assert_eq!(sparkline(&[]), "");
+    }
+}
@@ -0,0 +1,239 @@
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use bytes::Bytes;
+use chrono::{TimeDelta, Utc};
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::types::HnResult;
+use crate::utils::paths::data_dir;
+
+/// File name of the HTTP cache within the data directory
+const HTTP_CACHE_FILE_NAME: &str = "http-cache.db";
+
+/// How long to wait for another process to finish writing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Days a response is kept without being used before it is pruned
+const MAX_UNUSED_DAYS: i64 = 30;
+
+/// Total size of the stored bodies, beyond which the least recently used are pruned
+const MAX_CACHE_BYTES: i64 = 64 * 1024 * 1024;
+
+/// Responses stored between size checks, so long crawls stay within the cap
+const PRUNE_INTERVAL: u64 = 1000;
+
+/// A stored response and the validators to revalidate it with
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedResponse {
+    /// `ETag` header, sent back as `If-None-Match`
+    pub etag: Option<String>,
+    /// `Last-Modified` header, sent back as `If-Modified-Since`
+    pub last_modified: Option<String>,
+    pub body: Bytes,
+}
+
+/// Responses with an `ETag` or `Last-Modified` header, kept in a SQLite database so
+/// repeat runs can send conditional requests and reuse the body on `304 Not Modified`
+pub struct HttpCache {
+    connection: Mutex<Connection>,
+    /// Responses stored since the cache was opened
+    writes: AtomicU64,
+}
+
+impl HttpCache {
+    /// Open (and create if needed) the cache at a path, pruning long unused responses
+    pub fn open(path: &Path) -> HnResult<Self> {
+        let connection: Connection = Connection::open(path)
+            .map_err(|error| format!("Unable to open HTTP cache {}: {error}", path.display()))?;
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS responses (
+                url TEXT PRIMARY KEY,
+                etag TEXT,
+                last_modified TEXT,
+                body BLOB NOT NULL,
+                used_at INTEGER NOT NULL
+            );",
+        )?;
+
+        let cutoff: i64 = (Utc::now() - TimeDelta::days(MAX_UNUSED_DAYS)).timestamp();
+        connection.execute("DELETE FROM responses WHERE used_at < ?1", params![cutoff])?;
+        prune(&connection, MAX_CACHE_BYTES)?;
+
+        return Ok(Self {
+            connection: Mutex::new(connection),
+            writes: AtomicU64::new(0),
+        });
+    }
+
+    /// Open the cache in the local data directory
+    pub fn open_default() -> HnResult<Self> {
+        return Self::open(&data_dir()?.join(HTTP_CACHE_FILE_NAME));
+    }
+
+    /// Get the stored response for a URL
+    pub fn get(&self, url: &str) -> HnResult<Option<CachedResponse>> {
+        let connection = self.connection.lock().unwrap();
+        let response: Option<CachedResponse> = connection
+            .query_row(
+                "SELECT etag, last_modified, body FROM responses WHERE url = ?1",
+                params![url],
+                |row| {
+                    return Ok(CachedResponse {
+                        etag: row.get(0)?,
+                        last_modified: row.get(1)?,
+                        body: Bytes::from(row.get::<_, Vec<u8>>(2)?),
+                    });
+                },
+            )
+            .optional()?;
+
+        return Ok(response);
+    }
+
+    /// Store the response for a URL, replacing an older one
+    pub fn put(&self, url: &str, response: &CachedResponse) -> HnResult<()> {
+        let connection = self.connection.lock().unwrap();
+        connection.execute(
+            "INSERT OR REPLACE INTO responses (url, etag, last_modified, body, used_at)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                url,
+                response.etag,
+                response.last_modified,
+                response.body.as_ref(),
+                Utc::now().timestamp()
+            ],
+        )?;
+
+        if (self.writes.fetch_add(1, Ordering::Relaxed) + 1).is_multiple_of(PRUNE_INTERVAL) {
+            prune(&connection, MAX_CACHE_BYTES)?;
+        }
+
+        return Ok(());
+    }
+
+    /// Mark the response for a URL as still in use, after the server confirmed it
+    pub fn touch(&self, url: &str) -> HnResult<()> {
+        self.connection.lock().unwrap().execute(
+            "UPDATE responses SET used_at = ?2 WHERE url = ?1",
+            params![url, Utc::now().timestamp()],
+        )?;
+
+        return Ok(());
+    }
+}
+
+/// Remove the least recently used responses until the bodies fit in `max_bytes`
+fn prune(connection: &Connection, max_bytes: i64) -> HnResult<()> {
+    connection.execute(
+        "DELETE FROM responses WHERE url IN (
+            SELECT url FROM (
+                SELECT url, SUM(LENGTH(body)) OVER (ORDER BY used_at DESC, url) AS total
+                FROM responses
+            )
+            WHERE total > ?1
+        )",
+        params![max_bytes],
+    )?;
+
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_cache_round_trip() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache: HttpCache = HttpCache::open(&directory.path().join("cache.db")).unwrap();
+        let url: &str = "https://example.com/item/1.json";
+        assert_eq!(cache.get(url).unwrap(), None);
+
+        let response: CachedResponse = CachedResponse {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            body: Bytes::from_static(b"{\"id\":1}"),
+        };
+        cache.put(url, &response).unwrap();
+        cache.touch(url).unwrap();
+        assert_eq!(cache.get(url).unwrap(), Some(response));
+
+        let updated: CachedResponse = CachedResponse {
+            etag: None,
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+            body: Bytes::from_static(b"{\"id\":1,\"score\":2}"),
+        };
+        cache.put(url, &updated).unwrap();
+        assert_eq!(cache.get(url).unwrap(), Some(updated));
+    }
+
+    #[test]
+    fn test_http_cache_prunes_unused_responses() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("cache.db");
+        let cache: HttpCache = HttpCache::open(&path).unwrap();
+        let response: CachedResponse = CachedResponse {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            body: Bytes::from_static(b"[]"),
+        };
+        cache.put("https://example.com/old", &response).unwrap();
+        cache.put("https://example.com/new", &response).unwrap();
+        let stale: i64 = (Utc::now() - TimeDelta::days(MAX_UNUSED_DAYS + 1)).timestamp();
+        cache
+            .connection
+            .lock()
+            .unwrap()
+            .execute(
+                "UPDATE responses SET used_at = ?1 WHERE url = ?2",
+                params![stale, "https://example.com/old"],
+            )
+            .unwrap();
+        drop(cache);
+
+        let cache: HttpCache = HttpCache::open(&path).unwrap();
+        assert_eq!(cache.get("https://example.com/old").unwrap(), None);
+        assert!(cache.get("https://example.com/new").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_http_cache_prunes_least_recently_used_beyond_size() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache: HttpCache = HttpCache::open(&directory.path().join("cache.db")).unwrap();
+        let response: CachedResponse = CachedResponse {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            body: Bytes::from_static(b"0123456789"),
+        };
+        for (index, url) in [
+            "https://example.com/a",
+            "https://example.com/b",
+            "https://example.com/c",
+        ]
+        .iter()
+        .enumerate()
+        {
+            cache.put(url, &response).unwrap();
+            cache
+                .connection
+                .lock()
+                .unwrap()
+                .execute(
+                    "UPDATE responses SET used_at = ?1 WHERE url = ?2",
+                    params![index as i64, url],
+                )
+                .unwrap();
+        }
+
+        prune(&cache.connection.lock().unwrap(), 25).unwrap();
+
+        assert_eq!(cache.get("https://example.com/a").unwrap(), None);
+        assert!(cache.get("https://example.com/b").unwrap().is_some());
+        assert!(cache.get("https://example.com/c").unwrap().is_some());
+    }
+}
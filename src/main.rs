@@ -12,17 +12,103 @@
 //!
 //! ## Usage
 //! ```console
-//! $ hn-stories <OPTIONS>
+//! $ hn-stories [OPTIONS] [COMMAND]
 //! ```
 //!
+//! Commands:
+//!
+//!   snapshot
+//!           Record the current ranking, score and comment count of a story list
+//!
+//!   trend
+//!           Show how a story's rank and score changed across snapshots
+//!
+//!   rising
+//!           List stories whose score grew fastest between the last two snapshots
+//!
+//!   notify
+//!           Poll story lists and send notifications for stories matching keyword, domain or author rules
+//!
+//!   export
+//!           Export a story list as an RSS, Atom or JSON Feed document
+//!
+//!   digest
+//!           Generate a static HTML page or Markdown document from a story list
+//!
+//!   serve
+//!           Serve story lists, items and users as JSON over HTTP with shared caching
+//!
+//!   domain
+//!           Browse the most recent stories from a site, e.g. example.com or github.com/rust-lang
+//!
+//!   bookmarks
+//!           List bookmarked stories in the terminal UI, or remove bookmarks
+//!
+//!   item
+//!           Show a story or comment by ID or URL, with the comments leading to it
+//!
+//!   url
+//!           Check whether a link was submitted before, and browse its past discussions
+//!
+//!   archive
+//!           Mirror Hacker News items into a local database, resuming where the last run stopped
+//!
+//!   query
+//!           Query the local archive with SQL or filter terms, in the terminal UI or as JSON or CSV
+//!
+//!   mutes
+//!           List, add or remove the rules that hide stories by author, site, title keyword or ID
+//!
+//!   login
+//!           Log in to a Hacker News account to upvote, favorite, hide, flag and reply from the story list
+//!
+//!   logout
+//!           Log out of the Hacker News account
+//!
+//!   completions
+//!           Print a shell completion script (bash, elvish, fish, powershell, zsh)
+//!
+//!   man
+//!           Print the man page, or write pages for every command to a directory
+//!
 //! Options:
 //!   
 //!   -s, --story
-//!           Story type (top, new, best, ask, show, job)
+//!           Story type (top, new, best, ask, show, job). Separate several types with commas to merge them (e.g. top,show,ask)
 //!
 //!   -l, --limit
 //!           The number of stories to fetch and display in the terminal UI
 //!
+//!   -o, --open
+//!           How links are opened (browser, print, clipboard, hyperlink)
+//!
+//!   -b, --browser
+//!           Custom command used to open links in browser mode, e.g. "firefox --new-tab {url}"
+//!
+//!   -t, --time
+//!           How story times are shown (relative, absolute)
+//!
+//!   -z, --timezone
+//!           Timezone for absolute times, e.g. UTC or Europe/Berlin
+//!
+//!   --theme
+//!           Color theme (dark, light, high-contrast, monochrome or a theme from the config file)
+//!
+//!   --rate-limit
+//!           Most requests per second to Hacker News
+//!
+//!   --burst
+//!           Requests that may be sent at once before the rate limit applies
+//!
+//!   -v, --verbose
+//!           Log each request to stderr, with more detail when repeated (-vv)
+//!
+//!   --debug
+//!           Log debug details of every library, including the HTTP client
+//!
+//!   --log-file
+//!           Append log records to a file instead of stderr
+//!
 //!   -h, --help
 //!           Print help (see a summary with '-h')
 //!
@@ -30,39 +116,149 @@
 //!           Print version
 //!
 
+mod account;
 mod api;
+mod archive;
 mod args;
+mod bookmarks;
 mod client;
+mod commands;
+mod config;
+mod digest;
+mod export;
+mod history;
+mod http_cache;
 mod models;
+mod mutes;
+mod notify;
+mod reader;
+mod server;
 mod types;
 mod ui;
 mod utils;
 
+use std::env;
+use std::fmt::Display;
 use std::process;
+use std::sync::Arc;
 
-use chroma_print::{print_error, print_info};
-use clap::{Error, Parser, error::ErrorKind};
+use clap::{CommandFactory, Error, Parser, error::ErrorKind};
+use clap_complete::CompleteEnv;
 
+use crate::api::HackerNewsGateway;
+use crate::archive::recorder::RecordingGateway;
+use crate::archive::store::ArchiveStore;
+use crate::commands::completions::{BIN_NAME, COMPLETE_ENV};
+use crate::config::Config;
+use crate::http_cache::HttpCache;
+use crate::mutes::{MuteStore, MutingGateway};
+use crate::types::HnResult;
+use crate::ui::theme;
+use crate::utils::logging::{self, HTTP_TARGET, LogConfig};
+use crate::utils::rate_limit::RateLimiter;
 use crate::{args::Args, client::HackerNewsClient, ui::terminal::TerminalUserInterface};
 
 #[tokio::main]
 async fn main() {
+    // Answer shell completion requests from the `completions` scripts and exit
+    CompleteEnv::with_factory(Args::command)
+        .var(COMPLETE_ENV)
+        .bin(BIN_NAME)
+        .complete();
+
     let args = Args::try_parse().unwrap_or_else(|error: Error| match error.kind() {
         ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => {
             print_info!("{}", error);
             process::exit(0);
         }
-        _ => {
-            print_error!("Error: {}", error);
-            process::exit(1);
-        }
+        _ => fail(error),
     });
 
-    let gateway = HackerNewsClient::new(None);
-    let ui = TerminalUserInterface;
+    let config: Config = Config::load().unwrap_or_else(|error| fail(error));
+    theme::init(args.theme(&config).unwrap_or_else(|error| fail(error)));
 
-    args.run(gateway, ui).await.unwrap_or_else(|error| {
-        print_error!("Error: {error}");
-        process::exit(1);
-    });
+    let log_config: Option<LogConfig> = args
+        .log_config(env::var("RUST_LOG").ok().as_deref())
+        .unwrap_or_else(|error| fail(error));
+    if let Some(log_config) = log_config {
+        logging::init(log_config).unwrap_or_else(|error| fail(error));
+    }
+
+    // Keep every item and user fetched in the local archive, browsing without it if it
+    // cannot be opened. The crawler writes its items itself, with its checkpoints
+    let store: Option<ArchiveStore> = if args.records_items() {
+        ArchiveStore::open_default()
+            .inspect_err(|error| log::warn!("Archive disabled: {error}"))
+            .ok()
+    } else {
+        None
+    };
+    // Every request shares one rate limit, so concurrent fetches stay polite
+    let limiter: Arc<RateLimiter> = Arc::new(RateLimiter::new(
+        args.rate_limit(&config).unwrap_or_else(|error| fail(error)),
+    ));
+    let mut client: HackerNewsClient =
+        HackerNewsClient::new(None).with_rate_limiter(Arc::clone(&limiter));
+    // Without the cache every response is fetched in full, which is slower but still works
+    match HttpCache::open_default() {
+        Ok(cache) => client = client.with_cache(Arc::new(cache)),
+        Err(error) => log::warn!("HTTP cache disabled: {error}"),
+    }
+
+    let result: HnResult<()> = match store {
+        Some(store) => run(RecordingGateway::new(client, store), args, &config).await,
+        None => run(client, args, &config).await,
+    };
+    log::info!(target: HTTP_TARGET, "{}", limiter.stats());
+    logging::release();
+
+    result.unwrap_or_else(|error| fail(error));
+}
+
+/// Print an error and exit, first writing out the log records held back for the terminal UI
+fn fail<E: Display>(error: E) -> ! {
+    logging::release();
+    print_error!("Error: {error}");
+    process::exit(1);
+}
+
+/// Run the command with muted stories left out of every story list
+async fn run<G: HackerNewsGateway + Clone + 'static>(
+    gateway: G,
+    args: Args,
+    config: &Config,
+) -> HnResult<()> {
+    // Muting wraps the recording gateway, so muted stories are still archived. Without
+    // the rules every story is shown, which is noisier but still works
+    return match MuteStore::open_default() {
+        Ok(mutes) => {
+            let gateway: MutingGateway<G> = MutingGateway::new(gateway, mutes.clone());
+            let ui = terminal_ui(gateway.clone(), Some(mutes), &args, config);
+            args.run(gateway, ui).await
+        }
+        Err(error) => {
+            log::warn!("Mutes disabled: {error}");
+            let ui = terminal_ui(gateway.clone(), None, &args, config);
+            args.run(gateway, ui).await
+        }
+    };
+}
+
+/// Create the terminal UI, offering to mute stories, prefetching story details and offering
+/// the logged-in account's actions
+fn terminal_ui<G: HackerNewsGateway + 'static>(
+    gateway: G,
+    mutes: Option<MuteStore>,
+    args: &Args,
+    config: &Config,
+) -> TerminalUserInterface<G> {
+    let mut ui = TerminalUserInterface::new(gateway, args.link_opener(), args.time_formatter())
+        .with_prefetch(config.prefetch)
+        .with_account(account::logged_in_client);
+
+    if let Some(mutes) = mutes {
+        ui = ui.with_mutes(mutes);
+    }
+
+    return ui;
 }
@@ -0,0 +1,236 @@
+use reqwest::Url;
+
+/// Two-part public suffixes, under which the registrable domain has three labels
+const SECOND_LEVEL_SUFFIXES: [&str; 24] = [
+    "ac.jp", "ac.uk", "co.il", "co.in", "co.jp", "co.kr", "co.nz", "co.uk", "co.za", "com.au",
+    "com.br", "com.cn", "com.hk", "com.mx", "com.sg", "com.tr", "com.tw", "gov.au", "gov.uk",
+    "ne.jp", "net.au", "or.jp", "org.au", "org.uk",
+];
+
+/// Hosting sites where each subdomain is a different author (e.g. `user.github.io`)
+const USER_SUBDOMAIN_SITES: [&str; 14] = [
+    "blogspot.com",
+    "bearblog.dev",
+    "github.io",
+    "gitlab.io",
+    "herokuapp.com",
+    "medium.com",
+    "neocities.org",
+    "netlify.app",
+    "pages.dev",
+    "substack.com",
+    "tumblr.com",
+    "vercel.app",
+    "wordpress.com",
+    "writeas.com",
+];
+
+/// Sites where the first path segment is the author (e.g. `github.com/rust-lang`)
+const USER_PATH_SITES: [&str; 7] = [
+    "bitbucket.org",
+    "codeberg.org",
+    "github.com",
+    "gitlab.com",
+    "medium.com",
+    "twitter.com",
+    "x.com",
+];
+
+/// Get the site of a URL the way Hacker News shows it next to a title
+///
+/// Subdomains are dropped down to the registrable domain (`blog.example.com` is
+/// `example.com`), except on hosting sites where they name the author
+/// (`user.github.io`). On code hosts and social sites the author path is kept
+/// (`github.com/rust-lang`).
+pub fn site_domain(url: &str) -> Option<String> {
+    let url: Url = Url::parse(url).ok()?;
+    let host: String = url.host_str()?.trim_end_matches('.').to_lowercase();
+
+    // IP addresses have no subdomains to drop
+    if url.host_str()?.starts_with('[') || host.parse::<std::net::Ipv4Addr>().is_ok() {
+        return Some(host);
+    }
+
+    let labels: Vec<&str> = host.split('.').filter(|label| !label.is_empty()).collect();
+    let mut length: usize = if labels.len() >= 3
+        && SECOND_LEVEL_SUFFIXES.contains(&labels[labels.len() - 2..].join(".").as_str())
+    {
+        3
+    } else {
+        2
+    };
+
+    let registrable: String = labels[labels.len().saturating_sub(length)..].join(".");
+    if USER_SUBDOMAIN_SITES.contains(&registrable.as_str())
+        && labels.len() > length
+        && labels[labels.len() - length - 1] != "www"
+    {
+        length += 1;
+    }
+
+    let domain: String = labels[labels.len().saturating_sub(length)..].join(".");
+
+    if USER_PATH_SITES.contains(&domain.as_str())
+        && let Some(user) = url
+            .path_segments()
+            .and_then(|mut segments| segments.next())
+            .filter(|segment| !segment.is_empty())
+    {
+        return Some(format!("{domain}/{}", user.to_lowercase()));
+    }
+
+    return Some(domain);
+}
+
+/// Normalize a site typed by the user (e.g. `https://www.GitHub.com/rust-lang/rust`)
+/// to the form returned by [`site_domain`]
+pub fn normalize_site(site: &str) -> Option<String> {
+    let site: &str = site.trim();
+    if site.contains("://") {
+        return site_domain(site);
+    }
+
+    return site_domain(&format!("https://{site}"));
+}
+
+/// Query parameters that only track where a visitor came from
+const TRACKING_PARAMETERS: [&str; 12] = [
+    "fbclid", "gclid", "dclid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid", "ref", "ref_src",
+    "_hsenc", "_hsmi",
+];
+
+/// Normalize a link so different spellings of the same page compare equal
+///
+/// The scheme, a leading `www.`, the fragment, tracking parameters (`utm_*`,
+/// `fbclid`, ...) and a trailing slash are dropped, and the host is lowercased:
+/// `https://www.Example.com/post/?utm_source=x#top` is `example.com/post`.
+pub fn normalize_url(url: &str) -> Option<String> {
+    let url: &str = url.trim();
+    let url: Url = if url.contains("://") {
+        Url::parse(url).ok()?
+    } else {
+        Url::parse(&format!("https://{url}")).ok()?
+    };
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+
+    let host: String = url.host_str()?.trim_end_matches('.').to_lowercase();
+    let host: &str = host.strip_prefix("www.").unwrap_or(&host);
+
+    let mut normalized: String = host.to_string();
+    if let Some(port) = url.port() {
+        normalized.push_str(&format!(":{port}"));
+    }
+    normalized.push_str(url.path().trim_end_matches('/'));
+
+    let query: Vec<String> = url
+        .query()
+        .unwrap_or("")
+        .split('&')
+        .filter(|pair| {
+            let name: String = pair.split('=').next().unwrap_or("").to_lowercase();
+            return !name.is_empty()
+                && !name.starts_with("utm_")
+                && !TRACKING_PARAMETERS.contains(&name.as_str());
+        })
+        .map(str::to_string)
+        .collect();
+    if !query.is_empty() {
+        normalized.push_str(&format!("?{}", query.join("&")));
+    }
+
+    return Some(normalized);
+}
+
+/// Check whether a story's site is the given site, or an author on it
+/// (`github.com/rust-lang` is part of `github.com`)
+pub fn site_matches(domain: &str, site: &str) -> bool {
+    return domain == site
+        || domain
+            .strip_prefix(site)
+            .is_some_and(|rest| rest.starts_with('/'));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_site_domain() {
+        let cases: [(&str, &str); 14] = [
+            ("https://example.com/post", "example.com"),
+            ("https://www.example.com/post", "example.com"),
+            ("https://blog.Example.com:8080/post", "example.com"),
+            ("http://news.bbc.co.uk/story", "bbc.co.uk"),
+            ("https://www.abc.net.au/news", "abc.net.au"),
+            ("https://rust-lang.github.io/book", "rust-lang.github.io"),
+            ("https://www.github.io/", "github.io"),
+            (
+                "https://someone.substack.com/p/essay",
+                "someone.substack.com",
+            ),
+            ("https://github.com/Rust-Lang/rust", "github.com/rust-lang"),
+            ("https://www.github.com/", "github.com"),
+            ("https://x.com/someone/status/1", "x.com/someone"),
+            ("https://medium.com/@writer/post-123", "medium.com/@writer"),
+            ("http://192.168.1.10/admin", "192.168.1.10"),
+            ("https://localhost/", "localhost"),
+        ];
+
+        for (url, expected) in cases {
+            assert_eq!(site_domain(url).as_deref(), Some(expected), "{url}");
+        }
+
+        assert_eq!(site_domain("not a url"), None);
+    }
+
+    #[test]
+    fn test_normalize_site() {
+        assert_eq!(
+            normalize_site("www.Example.com").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            normalize_site("https://github.com/rust-lang/rust").as_deref(),
+            Some("github.com/rust-lang")
+        );
+        assert_eq!(
+            normalize_site(" github.com ").as_deref(),
+            Some("github.com")
+        );
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        let cases: [(&str, &str); 7] = [
+            ("https://www.Example.com/post/", "example.com/post"),
+            ("http://example.com/post#comments", "example.com/post"),
+            ("example.com", "example.com"),
+            (
+                "https://example.com/post?utm_source=hn&id=7&fbclid=abc&UTM_MEDIUM=x",
+                "example.com/post?id=7",
+            ),
+            ("https://example.com:8080/a/?ref=hn", "example.com:8080/a"),
+            ("https://example.com:443/a", "example.com/a"),
+            ("https://blog.example.com/Post", "blog.example.com/Post"),
+        ];
+
+        for (url, expected) in cases {
+            assert_eq!(normalize_url(url).as_deref(), Some(expected), "{url}");
+        }
+
+        assert_eq!(normalize_url("ftp://example.com/file"), None);
+        assert_eq!(normalize_url("not a url"), None);
+    }
+
+    #[test]
+    fn test_site_matches() {
+        assert!(site_matches("example.com", "example.com"));
+        assert!(site_matches("github.com/rust-lang", "github.com"));
+        assert!(site_matches("github.com/rust-lang", "github.com/rust-lang"));
+        assert!(!site_matches("github.com/rust-lang", "github.com/rust"));
+        assert!(!site_matches("notexample.com", "example.com"));
+        assert!(!site_matches("example.com.evil", "example.com"));
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use crate::models::story::{HackerNewsStoryType, StoryRanking};
+
+/// Merge several ranked story ID lists into one de-duplicated list
+///
+/// Each story ID is returned once, together with its 1-based rank in every list it
+/// appears in. Stories are ordered by their best rank, then by how many lists they
+/// appear in, then by the order the lists were given in.
+pub fn merge_story_lists(
+    lists: &[(HackerNewsStoryType, Vec<u64>)],
+) -> Vec<(u64, Vec<StoryRanking>)> {
+    let mut order: Vec<u64> = Vec::new();
+    let mut rankings: HashMap<u64, Vec<StoryRanking>> = HashMap::new();
+
+    for (story_type, ids) in lists {
+        for (position, id) in ids.iter().enumerate() {
+            let entry: &mut Vec<StoryRanking> = rankings.entry(*id).or_insert_with(|| {
+                order.push(*id);
+                return Vec::new();
+            });
+
+            // A list should not contain duplicates, but keep the best rank if it does
+            if entry
+                .iter()
+                .any(|ranking| ranking.story_type == *story_type)
+            {
+                continue;
+            }
+
+            entry.push(StoryRanking {
+                story_type: *story_type,
+                rank: position + 1,
+            });
+        }
+    }
+
+    let mut merged: Vec<(u64, Vec<StoryRanking>)> = order
+        .into_iter()
+        .map(|id| (id, rankings.remove(&id).unwrap_or_default()))
+        .collect();
+
+    // Stable sort keeps first-seen (list) order for ties
+    merged.sort_by_key(|(_, rankings)| {
+        let best_rank: usize = rankings
+            .iter()
+            .map(|ranking| ranking.rank)
+            .min()
+            .unwrap_or(usize::MAX);
+        return (best_rank, usize::MAX - rankings.len());
+    });
+
+    return merged;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranks(rankings: &[StoryRanking]) -> Vec<(&str, usize)> {
+        return rankings
+            .iter()
+            .map(|ranking| (ranking.story_type.full_name(), ranking.rank))
+            .collect();
+    }
+
+    #[test]
+    fn test_merge_story_lists_deduplicates_and_tags() {
+        let lists: Vec<(HackerNewsStoryType, Vec<u64>)> = vec![
+            (HackerNewsStoryType::T, vec![10, 20, 30]),
+            (HackerNewsStoryType::S, vec![40, 20]),
+            (HackerNewsStoryType::A, vec![50]),
+        ];
+
+        let merged: Vec<(u64, Vec<StoryRanking>)> = merge_story_lists(&lists);
+        let ids: Vec<u64> = merged.iter().map(|(id, _)| *id).collect();
+
+        assert_eq!(ids, vec![10, 40, 50, 20, 30]);
+        assert_eq!(ranks(&merged[3].1), vec![("Top", 2), ("Show", 2)]);
+        assert_eq!(ranks(&merged[1].1), vec![("Show", 1)]);
+    }
+
+    #[test]
+    fn test_merge_story_lists_prefers_stories_in_more_lists() {
+        let lists: Vec<(HackerNewsStoryType, Vec<u64>)> = vec![
+            (HackerNewsStoryType::N, vec![1, 2]),
+            (HackerNewsStoryType::B, vec![3, 2]),
+            (HackerNewsStoryType::T, vec![4, 3]),
+        ];
+
+        let merged: Vec<(u64, Vec<StoryRanking>)> = merge_story_lists(&lists);
+        let ids: Vec<u64> = merged.iter().map(|(id, _)| *id).collect();
+
+        // 3 is first in one list and second in another, so it leads the rank-1 ties
+        assert_eq!(ids, vec![3, 1, 4, 2]);
+    }
+
+    #[test]
+    fn test_merge_story_lists_empty() {
+        assert!(merge_story_lists(&[]).is_empty());
+        assert!(merge_story_lists(&[(HackerNewsStoryType::J, Vec::new())]).is_empty());
+    }
+}
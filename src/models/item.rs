@@ -0,0 +1,111 @@
+use reqwest::Url;
+
+use crate::models::story::StoryItem;
+
+/// Host of the Hacker News site, which item URLs must point to
+const HACKER_NEWS_HOST: &str = "news.ycombinator.com";
+
+/// An item with the comments between it and its story
+#[derive(Debug, Clone)]
+pub struct ItemThread {
+    /// Root of the thread (a story, job or poll)
+    pub story: StoryItem,
+    /// Comments from the story's direct reply down to the comment's parent
+    pub context: Vec<StoryItem>,
+    /// The looked-up comment, `None` when the story itself was looked up
+    pub comment: Option<StoryItem>,
+}
+
+impl ItemThread {
+    /// Build a thread from an item and its ancestors, nearest parent first
+    ///
+    /// The last ancestor is the root, or the item itself is when it has none.
+    pub fn new(item: StoryItem, mut ancestors: Vec<StoryItem>) -> Self {
+        let Some(story) = ancestors.pop() else {
+            return Self {
+                story: item,
+                context: Vec::new(),
+                comment: None,
+            };
+        };
+
+        ancestors.reverse();
+
+        return Self {
+            story,
+            context: ancestors,
+            comment: Some(item),
+        };
+    }
+}
+
+/// Get the item ID from an ID or a Hacker News item URL
+/// (e.g. `news.ycombinator.com/item?id=8863`)
+pub fn parse_item_reference(reference: &str) -> Option<u64> {
+    let reference: &str = reference.trim();
+    if let Ok(id) = reference.parse::<u64>() {
+        return Some(id);
+    }
+
+    let url: Url = if reference.contains("://") {
+        Url::parse(reference).ok()?
+    } else {
+        Url::parse(&format!("https://{reference}")).ok()?
+    };
+
+    let host: &str = url.host_str()?;
+    if host != HACKER_NEWS_HOST || url.path() != "/item" {
+        return None;
+    }
+
+    return url
+        .query_pairs()
+        .find(|(name, _)| name == "id")
+        .and_then(|(_, id)| id.parse().ok());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: u64) -> StoryItem {
+        return StoryItem {
+            id,
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_parse_item_reference() {
+        let cases: [(&str, Option<u64>); 8] = [
+            ("8863", Some(8863)),
+            (" 8863 ", Some(8863)),
+            ("https://news.ycombinator.com/item?id=8863", Some(8863)),
+            ("news.ycombinator.com/item?id=8863&p=2#8870", Some(8863)),
+            ("https://news.ycombinator.com/user?id=pg", None),
+            ("https://example.com/item?id=8863", None),
+            ("news.ycombinator.com/item?id=abc", None),
+            ("not an item", None),
+        ];
+
+        for (reference, expected) in cases {
+            assert_eq!(parse_item_reference(reference), expected, "{reference}");
+        }
+    }
+
+    #[test]
+    fn test_item_thread_new() {
+        // A story has no ancestors
+        let thread: ItemThread = ItemThread::new(item(1), Vec::new());
+        assert_eq!(thread.story.id, 1);
+        assert!(thread.context.is_empty());
+        assert!(thread.comment.is_none());
+
+        // A nested comment, with its ancestors from the nearest up to the story
+        let thread: ItemThread = ItemThread::new(item(4), vec![item(3), item(2), item(1)]);
+        assert_eq!(thread.story.id, 1);
+        let context: Vec<u64> = thread.context.iter().map(|comment| comment.id).collect();
+        assert_eq!(context, vec![2, 3]);
+        assert_eq!(thread.comment.map(|comment| comment.id), Some(4));
+    }
+}
@@ -1 +1,6 @@
+pub mod domain;
+pub mod feed;
+pub mod item;
+pub mod search;
 pub mod story;
+pub mod user;
@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::models::story::StoryItem;
+
+/// A page of results from the Hacker News search API
+#[derive(Debug, Deserialize)]
+pub struct SearchResponse {
+    pub hits: Vec<SearchHit>,
+}
+
+/// A story found by the Hacker News search API
+#[derive(Debug, Deserialize)]
+pub struct SearchHit {
+    #[serde(rename = "objectID")]
+    pub object_id: String,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub author: Option<String>,
+    pub points: Option<i32>,
+    pub num_comments: Option<u32>,
+    /// Creation time in Unix seconds
+    pub created_at_i: Option<i64>,
+}
+
+impl SearchHit {
+    /// Convert the hit to a story item, or `None` if its ID is not numeric
+    pub fn into_story_item(self) -> Option<StoryItem> {
+        return Some(StoryItem {
+            id: self.object_id.parse().ok()?,
+            by: self.author,
+            url: self.url,
+            score: self.points,
+            title: self.title,
+            descendants: self.num_comments,
+            time: self
+                .created_at_i
+                .and_then(|seconds| DateTime::<Utc>::from_timestamp(seconds, 0)),
+            ..Default::default()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_response_into_story_items() {
+        let response: SearchResponse = serde_json::from_str(
+            r#"{
+                "hits": [
+                    {
+                        "objectID": "42",
+                        "title": "Example post",
+                        "url": "https://example.com/post",
+                        "author": "alice",
+                        "points": 120,
+                        "num_comments": 30,
+                        "created_at_i": 1760860800,
+                        "_tags": ["story", "author_alice", "story_42"]
+                    },
+                    {"objectID": "not-a-number", "title": null, "url": null}
+                ],
+                "nbHits": 2
+            }"#,
+        )
+        .unwrap();
+
+        let stories: Vec<StoryItem> = response
+            .hits
+            .into_iter()
+            .filter_map(SearchHit::into_story_item)
+            .collect();
+
+        assert_eq!(stories.len(), 1);
+        assert_eq!(stories[0].id, 42);
+        assert_eq!(stories[0].by.as_deref(), Some("alice"));
+        assert_eq!(stories[0].score, Some(120));
+        assert_eq!(stories[0].descendants, Some(30));
+        assert_eq!(
+            stories[0].time.map(|time| time.timestamp()),
+            Some(1760860800)
+        );
+    }
+}
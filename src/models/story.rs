@@ -1,30 +1,38 @@
 use std::fmt;
 
-use chroma_print::Color;
+use chrono::{DateTime, Utc};
 use clap::ValueEnum;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::utils::constants::{ORANGE_COLOR, Y_COMBINATOR_BASE_URL};
+use crate::models::domain::site_domain;
+use crate::utils::constants::Y_COMBINATOR_BASE_URL;
+use crate::utils::time::TimeFormatter;
 
 /// Hacker News story type (new, top, best, ask, show, jobs)
-#[derive(ValueEnum, Clone, Copy, Debug)]
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum HackerNewsStoryType {
     #[value(alias = "new")]
+    #[serde(rename = "new")]
     N,
 
     #[value(alias = "top")]
+    #[serde(rename = "top")]
     T,
 
     #[value(alias = "best")]
+    #[serde(rename = "best")]
     B,
 
     #[value(alias = "ask")]
+    #[serde(rename = "ask")]
     A,
 
     #[value(alias = "show")]
+    #[serde(rename = "show")]
     S,
 
     #[value(alias = "jobs")]
+    #[serde(rename = "jobs")]
     J,
 }
 
@@ -53,6 +61,27 @@ impl HackerNewsStoryType {
         };
     }
 
+    /// Get the path of the story kind's listing page on the Hacker News site
+    pub fn site_path(&self) -> &str {
+        return match self {
+            HackerNewsStoryType::N => "/newest",
+            HackerNewsStoryType::T => "/news",
+            HackerNewsStoryType::B => "/best",
+            HackerNewsStoryType::A => "/ask",
+            HackerNewsStoryType::S => "/show",
+            HackerNewsStoryType::J => "/jobs",
+        };
+    }
+
+    /// Join the full names of several story kinds for display (e.g. "Top, Show")
+    pub fn join_full_names(story_types: &[HackerNewsStoryType]) -> String {
+        return story_types
+            .iter()
+            .map(|story_type| story_type.full_name())
+            .collect::<Vec<&str>>()
+            .join(", ");
+    }
+
     /// Get the maximum story limit for the story kind
     pub fn max_stories_limit(&self) -> u16 {
         return match self {
@@ -65,8 +94,21 @@ impl HackerNewsStoryType {
 /// Actions available to the user when interacting with a story item
 pub enum StoryAction {
     OpenUrl(String),
+    ReadArticle(String),
+    ReadComments,
     ViewStoryItem(u64),
     ViewAuthor(String),
+    ViewDomain(String),
+    Bookmark,
+    HideStory(u64),
+    MuteDomain(String),
+    MuteAuthor(String),
+    /// Logged-in actions, only offered with an account session
+    Upvote(u64),
+    Favorite(u64),
+    Hide(u64),
+    Flag(u64),
+    Reply(u64),
     Back,
 }
 
@@ -75,6 +117,8 @@ impl fmt::Display for StoryAction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         return match self {
             Self::OpenUrl(url) => write!(f, "Open URL: {}", url),
+            Self::ReadArticle(_) => write!(f, "Read article in terminal"),
+            Self::ReadComments => write!(f, "Read comments in terminal"),
             Self::ViewStoryItem(id) => write!(
                 f,
                 "View story on Hacker News: {}/item?id={}",
@@ -85,12 +129,81 @@ impl fmt::Display for StoryAction {
                 "View author on Hacker News: {}/user?id={}",
                 Y_COMBINATOR_BASE_URL, author
             ),
+            Self::ViewDomain(domain) => write!(f, "More stories from {}", domain),
+            Self::Bookmark => write!(f, "Bookmark story"),
+            Self::HideStory(_) => write!(f, "Hide story"),
+            Self::MuteDomain(domain) => write!(f, "Mute stories from {}", domain),
+            Self::MuteAuthor(author) => write!(f, "Mute stories by {}", author),
+            Self::Upvote(_) => write!(f, "Upvote"),
+            Self::Favorite(_) => write!(f, "Add to favorites"),
+            Self::Hide(_) => write!(f, "Hide on Hacker News"),
+            Self::Flag(_) => write!(f, "Flag"),
+            Self::Reply(_) => write!(f, "Reply"),
             Self::Back => write!(f, "<- Back"),
         };
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Actions available to the user for several marked stories at once
+pub enum BatchAction {
+    /// Open the URLs of the marked stories that link somewhere
+    OpenUrls(usize),
+    OpenDiscussions(usize),
+    Bookmark(usize),
+    ExportJson,
+    ExportMarkdown,
+    ClearMarks,
+    Back,
+}
+
+impl fmt::Display for BatchAction {
+    /// Formats the action for readable output
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Self::OpenUrls(count) => write!(f, "Open {} URLs", count),
+            Self::OpenDiscussions(count) => write!(f, "Open {} discussions on Hacker News", count),
+            Self::Bookmark(count) => write!(f, "Bookmark {} stories", count),
+            Self::ExportJson => write!(f, "Export selection to JSON"),
+            Self::ExportMarkdown => write!(f, "Export selection to Markdown"),
+            Self::ClearMarks => write!(f, "Unmark all stories"),
+            Self::Back => write!(f, "<- Back"),
+        };
+    }
+}
+
+/// Position of a story within one of the Hacker News story lists
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StoryRanking {
+    pub story_type: HackerNewsStoryType,
+    /// 1-based rank within the list
+    pub rank: usize,
+}
+
+/// Hacker News item types
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemType {
+    Job,
+    Story,
+    Comment,
+    Poll,
+    PollOpt,
+}
+
+impl fmt::Display for ItemType {
+    /// Formats the type the way the API names it
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Self::Job => write!(f, "job"),
+            Self::Story => write!(f, "story"),
+            Self::Comment => write!(f, "comment"),
+            Self::Poll => write!(f, "poll"),
+            Self::PollOpt => write!(f, "pollopt"),
+        };
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 /// Hacker News story item
 pub struct StoryItem {
     pub id: u64,
@@ -98,9 +211,44 @@ pub struct StoryItem {
     pub url: Option<String>,
     pub score: Option<i32>,
     pub title: Option<String>,
+    /// Total comment count
+    pub descendants: Option<u32>,
+    /// Creation time (Unix seconds in the API)
+    #[serde(
+        default,
+        with = "chrono::serde::ts_seconds_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub time: Option<DateTime<Utc>>,
+    /// Item type, set by the API for every item
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub item_type: Option<ItemType>,
+    /// Item a comment replies to, or the poll of a poll option
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<u64>,
+    /// HTML text of comments, text posts and jobs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// IDs of the direct replies, in ranked order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kids: Vec<u64>,
+    /// Lists the story appears in, only set for feeds aggregated from several story types
+    #[serde(skip)]
+    pub rankings: Vec<StoryRanking>,
 }
 
 impl StoryItem {
+    /// Get the URL of the story's discussion page on Hacker News
+    pub fn discussion_url(&self) -> String {
+        return format!("{}/item?id={}", Y_COMBINATOR_BASE_URL, self.id);
+    }
+
+    /// Get the site of the story's URL as Hacker News shows it (e.g. `example.com`,
+    /// `github.com/rust-lang`), or `None` for text posts
+    pub fn domain(&self) -> Option<String> {
+        return site_domain(self.url.as_deref()?);
+    }
+
     /// Get plural or singular `points` label for the story's score
     fn get_points_text(&self) -> String {
         let suffix: &str = if self.score.unwrap_or(0) == 1 {
@@ -111,29 +259,46 @@ impl StoryItem {
         return format!("point{suffix}");
     }
 
-    /// Formats Hacker News story item for display within a list
-    pub fn format_for_list(&self, index: usize) -> String {
-        return format!(
-            "{}. {} ({}) [{} {} by {}]",
-            index,
-            self.title.as_deref().unwrap_or("No Title"),
-            self.url.as_deref().unwrap_or("No URL"),
-            self.score.unwrap_or(0),
-            self.get_points_text(),
-            self.by.as_deref().unwrap_or("Unknown")
-        );
+    /// Get the story's list rankings as text (e.g. " {Top #3, Show #1}"), empty when not set
+    pub fn get_rankings_text(&self) -> String {
+        if self.rankings.is_empty() {
+            return String::new();
+        }
+
+        let rankings: Vec<String> = self
+            .rankings
+            .iter()
+            .map(|ranking| format!("{} #{}", ranking.story_type.full_name(), ranking.rank))
+            .collect();
+
+        return format!(" {{{}}}", rankings.join(", "));
+    }
+
+    /// Get the author and age of the story (e.g. "by alice 3 hours ago")
+    pub fn get_byline_text(&self, time: &TimeFormatter) -> String {
+        let age: String = self
+            .time
+            .map(|created| format!(" {}", time.format(created)))
+            .unwrap_or_default();
+
+        return format!("by {}{}", self.by.as_deref().unwrap_or("Unknown"), age);
     }
 
     /// Formats Hacker News story item for the detailed view of the item
-    pub fn format_for_details_header(&self) -> String {
+    pub fn format_for_details_header(&self, time: &TimeFormatter) -> String {
+        if self.item_type == Some(ItemType::Comment) {
+            return format!(
+                "Comment {}. What would you like to do?",
+                self.get_byline_text(time)
+            );
+        }
+
         return format!(
-            "{}{} [{} {} by {}]. What would you like to do?{}",
-            ORANGE_COLOR,
+            "{} [{} {} {}]. What would you like to do?",
             self.title.as_deref().unwrap_or("No Title"),
             self.score.unwrap_or(0),
             self.get_points_text(),
-            self.by.as_deref().unwrap_or("Unknown"),
-            Color::Reset.value()
+            self.get_byline_text(time)
         );
     }
 }
@@ -141,6 +306,14 @@ impl StoryItem {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Duration;
+
+    use crate::utils::time::tests::{test_formatter, test_now};
+    use crate::utils::time::{DisplayTimezone, TimeFormatter, TimeMode};
+
+    fn relative() -> TimeFormatter {
+        return test_formatter(TimeMode::Relative, DisplayTimezone::Local);
+    }
 
     #[test]
     fn test_hacker_news_story_type_full_name() {
@@ -161,6 +334,16 @@ mod tests {
         assert_eq!(HackerNewsStoryType::J.parameter_name(), "jobstories");
     }
 
+    #[test]
+    fn test_hacker_news_story_type_site_path() {
+        assert_eq!(HackerNewsStoryType::N.site_path(), "/newest");
+        assert_eq!(HackerNewsStoryType::T.site_path(), "/news");
+        assert_eq!(HackerNewsStoryType::B.site_path(), "/best");
+        assert_eq!(HackerNewsStoryType::A.site_path(), "/ask");
+        assert_eq!(HackerNewsStoryType::S.site_path(), "/show");
+        assert_eq!(HackerNewsStoryType::J.site_path(), "/jobs");
+    }
+
     #[test]
     fn test_hacker_news_story_type_max_stories_limit() {
         assert_eq!(HackerNewsStoryType::N.max_stories_limit(), 500);
@@ -186,10 +369,71 @@ mod tests {
             "Open URL: https://google.com"
         );
 
+        // ReadArticle
+        assert_eq!(
+            format!(
+                "{}",
+                StoryAction::ReadArticle("https://google.com".to_string())
+            ),
+            "Read article in terminal"
+        );
+
+        // ReadComments
+        assert_eq!(
+            format!("{}", StoryAction::ReadComments),
+            "Read comments in terminal"
+        );
+
         // ViewAuthor
         assert!(
             format!("{}", StoryAction::ViewAuthor("jake".to_string())).contains("user?id=jake")
         );
+
+        // ViewDomain
+        assert_eq!(
+            format!(
+                "{}",
+                StoryAction::ViewDomain("github.com/rust-lang".to_string())
+            ),
+            "More stories from github.com/rust-lang"
+        );
+
+        // Bookmark
+        assert_eq!(format!("{}", StoryAction::Bookmark), "Bookmark story");
+
+        // Mutes
+        assert_eq!(format!("{}", StoryAction::HideStory(1)), "Hide story");
+        assert_eq!(
+            format!("{}", StoryAction::MuteDomain("example.com".to_string())),
+            "Mute stories from example.com"
+        );
+        assert_eq!(
+            format!("{}", StoryAction::MuteAuthor("pg".to_string())),
+            "Mute stories by pg"
+        );
+
+        // Logged-in actions
+        assert_eq!(format!("{}", StoryAction::Upvote(1)), "Upvote");
+        assert_eq!(format!("{}", StoryAction::Favorite(1)), "Add to favorites");
+        assert_eq!(format!("{}", StoryAction::Reply(1)), "Reply");
+    }
+
+    #[test]
+    fn test_batch_action_display() {
+        assert_eq!(format!("{}", BatchAction::OpenUrls(3)), "Open 3 URLs");
+        assert_eq!(
+            format!("{}", BatchAction::OpenDiscussions(4)),
+            "Open 4 discussions on Hacker News"
+        );
+        assert_eq!(
+            format!("{}", BatchAction::Bookmark(2)),
+            "Bookmark 2 stories"
+        );
+        assert_eq!(
+            format!("{}", BatchAction::ExportMarkdown),
+            "Export selection to Markdown"
+        );
+        assert_eq!(format!("{}", BatchAction::Back), "<- Back");
     }
 
     #[test]
@@ -200,6 +444,7 @@ mod tests {
             url: None,
             score: Some(1),
             title: None,
+            ..Default::default()
         };
 
         // Singular
@@ -216,20 +461,88 @@ mod tests {
     }
 
     #[test]
-    fn test_format_for_list() {
+    fn test_format_for_details_header() {
         let item: StoryItem = StoryItem {
             id: 123,
             by: Some("johndoe".to_string()),
-            url: Some("https://google.com".to_string()),
-            score: Some(456),
+            score: Some(1),
+            title: Some("Title test".to_string()),
+            time: Some(test_now() - Duration::minutes(5)),
+            ..Default::default()
+        };
+
+        assert!(
+            item.format_for_details_header(&relative()).contains(
+                "Title test [1 point by johndoe 5 minutes ago]. What would you like to do?"
+            )
+        );
+
+        let absolute: TimeFormatter = test_formatter(TimeMode::Absolute, "UTC".parse().unwrap());
+        assert!(
+            item.format_for_details_header(&absolute)
+                .contains("[1 point by johndoe 2026-10-19 11:55]")
+        );
+
+        let comment: StoryItem = StoryItem {
+            item_type: Some(ItemType::Comment),
+            title: None,
+            ..item
+        };
+        assert_eq!(
+            comment.format_for_details_header(&relative()),
+            "Comment by johndoe 5 minutes ago. What would you like to do?"
+        );
+    }
+
+    #[test]
+    fn test_story_item_time_deserialization() {
+        let item: StoryItem =
+            serde_json::from_str(r#"{"id": 8863, "time": 1175714200, "type": "story"}"#).unwrap();
+        assert_eq!(item.time.map(|time| time.timestamp()), Some(1175714200));
+
+        // Serialized back in the API's Unix seconds format
+        assert!(
+            serde_json::to_string(&item)
+                .unwrap()
+                .contains(r#""time":1175714200"#)
+        );
+
+        let item: StoryItem = serde_json::from_str(r#"{"id": 8863}"#).unwrap();
+        assert_eq!(item.time, None);
+    }
+
+    #[test]
+    fn test_get_rankings_text() {
+        let item: StoryItem = StoryItem {
+            id: 123,
             title: Some("Title test".to_string()),
+            rankings: vec![
+                StoryRanking {
+                    story_type: HackerNewsStoryType::T,
+                    rank: 3,
+                },
+                StoryRanking {
+                    story_type: HackerNewsStoryType::S,
+                    rank: 1,
+                },
+            ],
+            ..Default::default()
         };
 
-        let formatted = item.format_for_list(1);
+        assert_eq!(item.get_rankings_text(), " {Top #3, Show #1}");
+        assert_eq!(StoryItem::default().get_rankings_text(), "");
+    }
 
-        assert!(formatted.starts_with("1. Title test"));
-        assert!(formatted.contains("(https://google.com)"));
-        assert!(formatted.contains("[456 points by johndoe]"));
+    #[test]
+    fn test_hacker_news_story_type_join_full_names() {
+        assert_eq!(
+            HackerNewsStoryType::join_full_names(&[
+                HackerNewsStoryType::T,
+                HackerNewsStoryType::S,
+                HackerNewsStoryType::A
+            ]),
+            "Top, Show, Ask"
+        );
     }
 
     #[test]
@@ -240,12 +553,12 @@ mod tests {
             url: None,
             score: None,
             title: None,
+            ..Default::default()
         };
 
-        let formatted: String = item.format_for_list(1);
+        let formatted: String = item.format_for_details_header(&relative());
 
-        assert!(formatted.contains("No Title"));
-        assert!(formatted.contains("Unknown"));
-        assert!(formatted.contains("0 points"));
+        // The age is left out when the time is unknown
+        assert!(formatted.contains("No Title [0 points by Unknown]."));
     }
 }
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// Hacker News user profile
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct HackerNewsUser {
+    /// Username
+    pub id: String,
+    /// Account creation time, in Unix seconds
+    pub created: i64,
+    pub karma: i32,
+    /// Profile description (HTML)
+    pub about: Option<String>,
+    /// IDs of the user's stories, polls and comments, newest first
+    #[serde(default)]
+    pub submitted: Vec<u64>,
+}
@@ -0,0 +1,451 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::api::HackerNewsGateway;
+use crate::models::domain::{normalize_site, site_matches};
+use crate::models::story::{HackerNewsStoryType, StoryItem};
+use crate::models::user::HackerNewsUser;
+use crate::types::HnResult;
+use crate::utils::helpers::contains_phrase;
+use crate::utils::json_store::JsonFile;
+use crate::utils::paths::data_dir;
+
+/// File name of the mute rules within the data directory
+const MUTES_FILE_NAME: &str = "mutes.json";
+
+/// Kinds of mute rules (author, domain, keyword, id)
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum MuteKind {
+    Author,
+    Domain,
+    Keyword,
+    Id,
+}
+
+impl fmt::Display for MuteKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Self::Author => write!(f, "author"),
+            Self::Domain => write!(f, "domain"),
+            Self::Keyword => write!(f, "keyword"),
+            Self::Id => write!(f, "id"),
+        };
+    }
+}
+
+/// Stories left out of every story list
+///
+/// A story is muted when any rule matches it.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct MuteRules {
+    /// Lowercase usernames of story authors
+    pub authors: BTreeSet<String>,
+    /// Sites as the list shows them (e.g. `example.com`), including their sub-sites
+    /// (`github.com` also mutes `github.com/rust-lang`)
+    pub domains: BTreeSet<String>,
+    /// Lowercase whole words or phrases to look for in titles
+    pub keywords: BTreeSet<String>,
+    pub ids: BTreeSet<u64>,
+}
+
+impl MuteRules {
+    pub fn is_empty(&self) -> bool {
+        return self.authors.is_empty()
+            && self.domains.is_empty()
+            && self.keywords.is_empty()
+            && self.ids.is_empty();
+    }
+
+    /// Whether any rule matches a story
+    pub fn matches(&self, story: &StoryItem) -> bool {
+        if self.ids.contains(&story.id) {
+            return true;
+        }
+
+        if let Some(by) = &story.by
+            && self.authors.contains(&by.to_lowercase())
+        {
+            return true;
+        }
+
+        if let Some(domain) = story.domain()
+            && self.domains.iter().any(|site| site_matches(&domain, site))
+        {
+            return true;
+        }
+
+        let title: String = story.title.as_deref().unwrap_or("").to_lowercase();
+        return self
+            .keywords
+            .iter()
+            .any(|keyword| contains_phrase(&title, keyword));
+    }
+
+    /// Leave out the muted stories
+    pub fn apply(&self, stories: Vec<StoryItem>) -> Vec<StoryItem> {
+        return stories
+            .into_iter()
+            .filter(|story| !self.matches(story))
+            .collect();
+    }
+
+    /// Add a rule, returning whether it is new
+    pub fn add(&mut self, kind: MuteKind, value: &str) -> HnResult<bool> {
+        let value: String = normalize_rule(kind, value)?;
+
+        return Ok(match kind {
+            MuteKind::Author => self.authors.insert(value),
+            MuteKind::Domain => self.domains.insert(value),
+            MuteKind::Keyword => self.keywords.insert(value),
+            MuteKind::Id => self.ids.insert(value.parse()?),
+        });
+    }
+
+    /// Remove a rule, returning whether it existed
+    pub fn remove(&mut self, kind: MuteKind, value: &str) -> HnResult<bool> {
+        let value: String = normalize_rule(kind, value)?;
+
+        return Ok(match kind {
+            MuteKind::Author => self.authors.remove(&value),
+            MuteKind::Domain => self.domains.remove(&value),
+            MuteKind::Keyword => self.keywords.remove(&value),
+            MuteKind::Id => self.ids.remove(&value.parse()?),
+        });
+    }
+
+    /// Describe every rule as `<kind> <value>`, the arguments that add it
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::new();
+        for (kind, values) in [
+            (MuteKind::Author, &self.authors),
+            (MuteKind::Domain, &self.domains),
+            (MuteKind::Keyword, &self.keywords),
+        ] {
+            lines.extend(values.iter().map(|value| format!("{kind} {value}")));
+        }
+        lines.extend(self.ids.iter().map(|id| format!("{} {id}", MuteKind::Id)));
+
+        return lines;
+    }
+}
+
+/// Validate a rule and bring it into the form it is compared in
+fn normalize_rule(kind: MuteKind, value: &str) -> HnResult<String> {
+    let value: &str = value.trim();
+    if value.is_empty() {
+        return Err(format!("Empty {kind} to mute").into());
+    }
+
+    return Ok(match kind {
+        MuteKind::Author | MuteKind::Keyword => value.to_lowercase(),
+        MuteKind::Domain => {
+            normalize_site(value).ok_or_else(|| format!("Invalid site: {value}"))?
+        }
+        MuteKind::Id => value
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid story ID: {value}"))?
+            .to_string(),
+    });
+}
+
+/// The saved mute rules, in `mutes.json`
+#[derive(Clone)]
+pub struct MuteStore {
+    file: JsonFile<MuteRules>,
+}
+
+impl MuteStore {
+    pub fn new(path: PathBuf) -> Self {
+        return Self {
+            file: JsonFile::new(path, "mute rules"),
+        };
+    }
+
+    /// Open the rules in the data directory
+    pub fn open_default() -> HnResult<Self> {
+        return Ok(Self::new(data_dir()?.join(MUTES_FILE_NAME)));
+    }
+
+    pub fn load(&self) -> HnResult<MuteRules> {
+        return Ok(self.file.load()?.unwrap_or_default());
+    }
+
+    pub fn save(&self, rules: &MuteRules) -> HnResult<()> {
+        return self.file.save(rules);
+    }
+
+    /// Add a rule and save it, returning whether it is new
+    pub fn add(&self, kind: MuteKind, value: &str) -> HnResult<bool> {
+        let mut rules: MuteRules = self.load()?;
+        let added: bool = rules.add(kind, value)?;
+        if added {
+            self.save(&rules)?;
+        }
+
+        return Ok(added);
+    }
+
+    /// Remove a rule and save the rest, returning whether it existed
+    pub fn remove(&self, kind: MuteKind, value: &str) -> HnResult<bool> {
+        let mut rules: MuteRules = self.load()?;
+        let removed: bool = rules.remove(kind, value)?;
+        if removed {
+            self.save(&rules)?;
+        }
+
+        return Ok(removed);
+    }
+}
+
+/// Gateway that leaves muted stories out of every story list fetched through another gateway
+///
+/// Rules are loaded for each list, so long-running commands pick up new ones. Single
+/// items, comments, past submissions of a link and the stories kept locally (bookmarks,
+/// the archive) are not muted, as they are asked for by ID or link.
+#[derive(Clone)]
+pub struct MutingGateway<G: HackerNewsGateway> {
+    gateway: G,
+    mutes: MuteStore,
+}
+
+impl<G: HackerNewsGateway> MutingGateway<G> {
+    pub fn new(gateway: G, mutes: MuteStore) -> Self {
+        return Self { gateway, mutes };
+    }
+
+    /// Leave out the muted stories, or keep them all when the rules cannot be read
+    fn unmuted(&self, stories: Vec<StoryItem>) -> HnResult<Vec<StoryItem>> {
+        return match self.mutes.load() {
+            Ok(rules) => Ok(rules.apply(stories)),
+            Err(error) => {
+                log::warn!("Showing muted stories: {error}");
+                Ok(stories)
+            }
+        };
+    }
+}
+
+#[async_trait]
+impl<G: HackerNewsGateway> HackerNewsGateway for MutingGateway<G> {
+    async fn fetch_stories(
+        &self,
+        story_type: &HackerNewsStoryType,
+        limit: u16,
+    ) -> HnResult<Vec<StoryItem>> {
+        return self.unmuted(self.gateway.fetch_stories(story_type, limit).await?);
+    }
+
+    async fn fetch_feed(
+        &self,
+        story_types: &[HackerNewsStoryType],
+        limit: u16,
+    ) -> HnResult<Vec<StoryItem>> {
+        return self.unmuted(self.gateway.fetch_feed(story_types, limit).await?);
+    }
+
+    async fn fetch_item(&self, id: u64) -> HnResult<Option<StoryItem>> {
+        return self.gateway.fetch_item(id).await;
+    }
+
+    async fn fetch_max_item_id(&self) -> HnResult<u64> {
+        return self.gateway.fetch_max_item_id().await;
+    }
+
+    async fn fetch_items(&self, ids: &[u64], concurrency: usize) -> HnResult<Vec<StoryItem>> {
+        return self.gateway.fetch_items(ids, concurrency).await;
+    }
+
+    async fn fetch_user(&self, id: &str) -> HnResult<Option<HackerNewsUser>> {
+        return self.gateway.fetch_user(id).await;
+    }
+
+    async fn fetch_domain_stories(&self, site: &str, limit: u16) -> HnResult<Vec<StoryItem>> {
+        return self.unmuted(self.gateway.fetch_domain_stories(site, limit).await?);
+    }
+
+    async fn fetch_url_submissions(&self, url: &str) -> HnResult<Vec<StoryItem>> {
+        return self.gateway.fetch_url_submissions(url).await;
+    }
+
+    async fn fetch_article(&self, url: &str) -> HnResult<String> {
+        return self.gateway.fetch_article(url).await;
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::api::MockHackerNewsGateway;
+    use std::fs;
+
+    /// A muting gateway over `gateway` with `rules` saved in `directory`
+    pub(crate) fn muting_gateway(
+        gateway: MockHackerNewsGateway,
+        directory: &std::path::Path,
+        rules: &[(MuteKind, &str)],
+    ) -> MutingGateway<MockHackerNewsGateway> {
+        let store: MuteStore = MuteStore::new(directory.join(MUTES_FILE_NAME));
+        for (kind, value) in rules {
+            store.add(*kind, value).unwrap();
+        }
+
+        return MutingGateway::new(gateway, store);
+    }
+
+    fn story(id: u64, by: &str, url: &str, title: &str) -> StoryItem {
+        return StoryItem {
+            id,
+            by: Some(by.to_string()),
+            url: Some(url.to_string()),
+            title: Some(title.to_string()),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_mute_rules_apply() {
+        let mut rules: MuteRules = MuteRules::default();
+        rules.add(MuteKind::Author, "Spammer").unwrap();
+        rules
+            .add(MuteKind::Domain, "https://www.github.com")
+            .unwrap();
+        rules.add(MuteKind::Keyword, "Crypto").unwrap();
+        rules.add(MuteKind::Id, " 7 ").unwrap();
+
+        let stories: Vec<StoryItem> = vec![
+            story(1, "spammer", "https://example.com", "Hello"),
+            story(2, "alice", "https://github.com/rust-lang/rust", "Rust"),
+            story(3, "bob", "https://example.com/a", "The crypto winter"),
+            story(4, "carol", "https://example.com/b", "Cryptography basics"),
+            story(7, "dave", "https://example.com/c", "Muted by ID"),
+        ];
+
+        let ids: Vec<u64> = rules.apply(stories).iter().map(|story| story.id).collect();
+
+        // Keywords match whole words, so "Cryptography" is kept
+        assert_eq!(ids, vec![4]);
+    }
+
+    #[test]
+    fn test_mute_rules_add_and_remove() {
+        let mut rules: MuteRules = MuteRules::default();
+        assert!(rules.is_empty());
+
+        assert!(rules.add(MuteKind::Domain, "Example.com/").unwrap());
+        assert!(!rules.add(MuteKind::Domain, "example.com").unwrap());
+        assert!(rules.add(MuteKind::Id, "42").unwrap());
+        assert!(rules.add(MuteKind::Author, "PG").unwrap());
+
+        assert_eq!(
+            rules.describe(),
+            vec!["author pg", "domain example.com", "id 42"]
+        );
+
+        assert!(rules.remove(MuteKind::Author, "pg").unwrap());
+        assert!(!rules.remove(MuteKind::Keyword, "missing").unwrap());
+        assert_eq!(rules.describe(), vec!["domain example.com", "id 42"]);
+
+        assert!(rules.add(MuteKind::Id, "abc").is_err());
+        assert!(rules.add(MuteKind::Keyword, "  ").is_err());
+    }
+
+    #[test]
+    fn test_mute_store_round_trip() {
+        let directory = tempfile::tempdir().unwrap();
+        let store: MuteStore = MuteStore::new(directory.path().join("mutes.json"));
+
+        assert!(store.load().unwrap().is_empty());
+
+        assert!(store.add(MuteKind::Keyword, "launch hn").unwrap());
+        assert!(!store.add(MuteKind::Keyword, "Launch HN").unwrap());
+
+        let rules: MuteRules = store.load().unwrap();
+        assert_eq!(rules.describe(), vec!["keyword launch hn"]);
+
+        assert!(store.remove(MuteKind::Keyword, "LAUNCH HN").unwrap());
+        assert!(store.load().unwrap().is_empty());
+
+        fs::write(directory.path().join("mutes.json"), "not json").unwrap();
+        assert!(
+            store
+                .load()
+                .unwrap_err()
+                .to_string()
+                .contains("Corrupt mute rules")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_muting_gateway_leaves_out_muted_stories() {
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway.expect_fetch_feed().returning(|_, _| {
+            Ok(vec![
+                story(1, "alice", "https://example.com", "Kept"),
+                story(2, "spammer", "https://example.com", "Muted"),
+            ])
+        });
+        mock_gateway
+            .expect_fetch_item()
+            .returning(|id| Ok(Some(story(id, "spammer", "https://example.com", "Item"))));
+        mock_gateway.expect_fetch_url_submissions().returning(|_| {
+            Ok(vec![story(
+                3,
+                "spammer",
+                "https://example.com/post",
+                "Post",
+            )])
+        });
+
+        let directory = tempfile::tempdir().unwrap();
+        let gateway: MutingGateway<MockHackerNewsGateway> = muting_gateway(
+            mock_gateway,
+            directory.path(),
+            &[(MuteKind::Author, "spammer")],
+        );
+
+        let stories: Vec<StoryItem> = gateway
+            .fetch_feed(&[HackerNewsStoryType::N], 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            stories.iter().map(|story| story.id).collect::<Vec<u64>>(),
+            vec![1]
+        );
+
+        // Items asked for by ID or link are shown anyway
+        assert!(gateway.fetch_item(2).await.unwrap().is_some());
+        assert_eq!(
+            gateway
+                .fetch_url_submissions("example.com/post")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_muting_gateway_keeps_stories_with_corrupt_rules() {
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_stories()
+            .returning(|_, _| Ok(vec![story(1, "spammer", "https://example.com", "Kept")]));
+
+        let directory = tempfile::tempdir().unwrap();
+        let gateway: MutingGateway<MockHackerNewsGateway> =
+            muting_gateway(mock_gateway, directory.path(), &[]);
+        fs::write(directory.path().join(MUTES_FILE_NAME), "not json").unwrap();
+
+        let stories: Vec<StoryItem> = gateway
+            .fetch_stories(&HackerNewsStoryType::T, 1)
+            .await
+            .unwrap();
+        assert_eq!(stories.len(), 1);
+    }
+}
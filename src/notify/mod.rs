@@ -0,0 +1,304 @@
+pub mod notifiers;
+pub mod rules;
+pub mod state;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::api::HackerNewsGateway;
+use crate::models::story::{HackerNewsStoryType, StoryItem};
+use crate::notify::notifiers::NotifierConfig;
+use crate::notify::rules::NotifyRule;
+use crate::notify::state::NotifyState;
+use crate::print_error;
+use crate::types::HnResult;
+
+/// Notify mode settings from the `[notify]` section of the config file
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifyConfig {
+    /// Story lists to poll
+    pub story_types: Vec<HackerNewsStoryType>,
+    /// The number of stories to check in each list
+    pub limit: u16,
+    /// Seconds between polls
+    pub interval: u64,
+    pub rules: Vec<NotifyRule>,
+    pub notifiers: Vec<NotifierConfig>,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        return Self {
+            story_types: vec![HackerNewsStoryType::N],
+            limit: 100,
+            interval: 300,
+            rules: Vec::new(),
+            notifiers: Vec::new(),
+        };
+    }
+}
+
+/// A story that matched a notify rule (also the webhook JSON payload)
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub id: u64,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub by: Option<String>,
+    pub score: Option<i32>,
+    pub discussion_url: String,
+    /// Label of the rule that matched
+    pub rule: String,
+}
+
+impl Notification {
+    pub fn new(story: &StoryItem, rule: &NotifyRule) -> Self {
+        return Self {
+            id: story.id,
+            title: story.title.clone(),
+            url: story.url.clone(),
+            by: story.by.clone(),
+            score: story.score,
+            discussion_url: story.discussion_url(),
+            rule: rule.label(),
+        };
+    }
+}
+
+/// A backend that delivers notifications (stdout, webhook, command, ...)
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Deliver a notification
+    async fn notify(&self, notification: &Notification) -> HnResult<()>;
+}
+
+/// Poll the story lists once and notify about stories matching a rule
+///
+/// Each story is only ever notified about once, even across restarts, as
+/// notified IDs are recorded in `state`. Delivery errors are reported but do not
+/// stop the other notifiers, and a story is only recorded once at least one notifier
+/// delivered it. Returns the number of stories notified about.
+pub async fn poll_once<G>(
+    gateway: &G,
+    config: &NotifyConfig,
+    notifiers: &[Box<dyn Notifier>],
+    state: &mut NotifyState,
+) -> HnResult<usize>
+where
+    G: HackerNewsGateway,
+{
+    let stories: Vec<StoryItem> = gateway
+        .fetch_feed(&config.story_types, config.limit)
+        .await?;
+
+    let mut notified: usize = 0;
+    for story in &stories {
+        if state.contains(story.id) {
+            continue;
+        }
+
+        let Some(rule) = config.rules.iter().find(|rule| rule.matches(story)) else {
+            continue;
+        };
+
+        let notification: Notification = Notification::new(story, rule);
+        let mut delivered: bool = false;
+        for notifier in notifiers {
+            match notifier.notify(&notification).await {
+                Ok(()) => delivered = true,
+                Err(error) => {
+                    print_error!("Failed to send notification for {}: {error}", story.id);
+                }
+            }
+        }
+
+        // Stories no notifier could deliver are tried again on the next poll
+        if delivered {
+            state.insert(story.id)?;
+            notified += 1;
+        }
+    }
+
+    return Ok(notified);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::MockHackerNewsGateway;
+    use crate::mutes::MuteKind;
+    use crate::mutes::tests::muting_gateway;
+
+    fn stories() -> Vec<StoryItem> {
+        return vec![
+            StoryItem {
+                id: 1,
+                title: Some("Launching our Rust SDK".to_string()),
+                ..Default::default()
+            },
+            StoryItem {
+                id: 2,
+                title: Some("Something else entirely".to_string()),
+                ..Default::default()
+            },
+        ];
+    }
+
+    #[tokio::test]
+    async fn test_poll_once_notifies_each_match_once() {
+        let directory = tempfile::tempdir().unwrap();
+        let state_path = directory.path().join("notify_state.json");
+
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_feed()
+            .times(2)
+            .returning(|_, _| Ok(stories()));
+
+        let mut mock_notifier = MockNotifier::new();
+        mock_notifier
+            .expect_notify()
+            .withf(|notification| {
+                notification.id == 1
+                    && notification.rule == "keyword: rust"
+                    && notification.discussion_url.ends_with("item?id=1")
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+        let notifiers: Vec<Box<dyn Notifier>> = vec![Box::new(mock_notifier)];
+
+        let config: NotifyConfig = NotifyConfig {
+            rules: vec![NotifyRule {
+                keywords: vec!["rust".to_string()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mut state: NotifyState = NotifyState::load(state_path.clone()).unwrap();
+        let first: usize = poll_once(&mock_gateway, &config, &notifiers, &mut state)
+            .await
+            .unwrap();
+        assert_eq!(first, 1);
+
+        // A restart must not re-alert for the same story
+        let mut state: NotifyState = NotifyState::load(state_path).unwrap();
+        let second: usize = poll_once(&mock_gateway, &config, &notifiers, &mut state)
+            .await
+            .unwrap();
+        assert_eq!(second, 0);
+    }
+
+    #[tokio::test]
+    async fn test_poll_once_continues_after_notifier_error() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_feed()
+            .returning(|_, _| Ok(stories()));
+
+        let mut failing_notifier = MockNotifier::new();
+        failing_notifier
+            .expect_notify()
+            .times(1)
+            .returning(|_| Err("webhook unavailable".into()));
+        let mut working_notifier = MockNotifier::new();
+        working_notifier
+            .expect_notify()
+            .times(1)
+            .returning(|_| Ok(()));
+        let notifiers: Vec<Box<dyn Notifier>> =
+            vec![Box::new(failing_notifier), Box::new(working_notifier)];
+
+        let config: NotifyConfig = NotifyConfig {
+            rules: vec![NotifyRule {
+                keywords: vec!["something".to_string()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mut state: NotifyState =
+            NotifyState::load(directory.path().join("notify_state.json")).unwrap();
+        let notified: usize = poll_once(&mock_gateway, &config, &notifiers, &mut state)
+            .await
+            .unwrap();
+
+        assert_eq!(notified, 1);
+        assert!(state.contains(2));
+    }
+
+    #[tokio::test]
+    async fn test_poll_once_retries_when_every_notifier_fails() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_feed()
+            .returning(|_, _| Ok(stories()));
+
+        let mut failing_notifier = MockNotifier::new();
+        failing_notifier
+            .expect_notify()
+            .times(2)
+            .returning(|_| Err("webhook unavailable".into()));
+        let notifiers: Vec<Box<dyn Notifier>> = vec![Box::new(failing_notifier)];
+
+        let config: NotifyConfig = NotifyConfig {
+            rules: vec![NotifyRule {
+                keywords: vec!["rust".to_string()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mut state: NotifyState =
+            NotifyState::load(directory.path().join("notify_state.json")).unwrap();
+        for _ in 0..2 {
+            let notified: usize = poll_once(&mock_gateway, &config, &notifiers, &mut state)
+                .await
+                .unwrap();
+            assert_eq!(notified, 0);
+            assert!(!state.contains(1));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_once_skips_muted_stories() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let mut mock_gateway = MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_feed()
+            .returning(|_, _| Ok(stories()));
+        let gateway = muting_gateway(
+            mock_gateway,
+            directory.path(),
+            &[(MuteKind::Keyword, "sdk")],
+        );
+
+        let mut mock_notifier = MockNotifier::new();
+        mock_notifier.expect_notify().times(0);
+        let notifiers: Vec<Box<dyn Notifier>> = vec![Box::new(mock_notifier)];
+
+        let config: NotifyConfig = NotifyConfig {
+            rules: vec![NotifyRule {
+                keywords: vec!["rust".to_string()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mut state: NotifyState =
+            NotifyState::load(directory.path().join("notify_state.json")).unwrap();
+        let notified: usize = poll_once(&gateway, &config, &notifiers, &mut state)
+            .await
+            .unwrap();
+
+        assert_eq!(notified, 0);
+        assert!(!state.contains(1));
+    }
+}
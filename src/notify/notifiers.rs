@@ -0,0 +1,237 @@
+use std::process::ExitStatus;
+
+use async_trait::async_trait;
+use reqwest::Client;
+use reqwest::header::USER_AGENT;
+use serde::Deserialize;
+use tokio::process::Command;
+
+use crate::notify::{Notification, Notifier};
+use crate::print_info;
+use crate::types::HnResult;
+use crate::utils::constants::HTTP_USER_AGENT;
+
+/// Notifier backend settings from a `[[notify.notifiers]]` config entry
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum NotifierConfig {
+    /// Print notifications to stdout
+    Stdout,
+
+    /// POST notifications as JSON to a URL
+    Webhook { url: String },
+
+    /// Run a command for each notification, with `{id}`, `{title}`, `{url}`,
+    /// `{by}`, `{discussion_url}` and `{rule}` placeholders
+    Command { command: String },
+}
+
+impl NotifierConfig {
+    /// Create the notifier backend for this config
+    pub fn build(&self) -> Box<dyn Notifier> {
+        return match self {
+            NotifierConfig::Stdout => Box::new(StdoutNotifier),
+            NotifierConfig::Webhook { url } => Box::new(WebhookNotifier {
+                client: Client::new(),
+                url: url.clone(),
+            }),
+            NotifierConfig::Command { command } => Box::new(CommandNotifier {
+                template: command.clone(),
+            }),
+        };
+    }
+}
+
+/// Prints notifications to stdout
+pub struct StdoutNotifier;
+
+#[async_trait]
+impl Notifier for StdoutNotifier {
+    async fn notify(&self, notification: &Notification) -> HnResult<()> {
+        print_info!(
+            "[{}] {} ({}) {}",
+            notification.rule,
+            notification.title.as_deref().unwrap_or("No Title"),
+            notification.url.as_deref().unwrap_or("No URL"),
+            notification.discussion_url
+        );
+
+        return Ok(());
+    }
+}
+
+/// Sends notifications to a generic HTTP webhook as a JSON payload
+pub struct WebhookNotifier {
+    client: Client,
+    url: String,
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, notification: &Notification) -> HnResult<()> {
+        self.client
+            .post(&self.url)
+            .header(USER_AGENT, HTTP_USER_AGENT)
+            .json(notification)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        return Ok(());
+    }
+}
+
+/// Runs a shell-style command template for each notification
+pub struct CommandNotifier {
+    template: String,
+}
+
+#[async_trait]
+impl Notifier for CommandNotifier {
+    async fn notify(&self, notification: &Notification) -> HnResult<()> {
+        let arguments: Vec<String> = command_arguments(&self.template, notification)?;
+        let status: ExitStatus = Command::new(&arguments[0])
+            .args(&arguments[1..])
+            .status()
+            .await
+            .map_err(|error| format!("Failed to run '{}': {error}", arguments[0]))?;
+
+        if !status.success() {
+            return Err(format!("'{}' exited with {status}", arguments[0]).into());
+        }
+
+        return Ok(());
+    }
+}
+
+/// Split a command template into arguments and fill in the notification placeholders
+///
+/// Placeholders are substituted after splitting, so values are never interpreted
+/// by a shell.
+fn command_arguments(template: &str, notification: &Notification) -> HnResult<Vec<String>> {
+    let arguments: Vec<String> = shlex::split(template)
+        .filter(|arguments| !arguments.is_empty())
+        .ok_or_else(|| format!("Invalid notify command: {template}"))?;
+
+    let replacements: [(&str, String); 6] = [
+        ("{id}", notification.id.to_string()),
+        ("{title}", notification.title.clone().unwrap_or_default()),
+        ("{url}", notification.url.clone().unwrap_or_default()),
+        ("{by}", notification.by.clone().unwrap_or_default()),
+        ("{discussion_url}", notification.discussion_url.clone()),
+        ("{rule}", notification.rule.clone()),
+    ];
+
+    let substituted: Vec<String> = arguments
+        .iter()
+        .map(|argument| replace_placeholders(argument, &replacements))
+        .collect();
+
+    return Ok(substituted);
+}
+
+/// Replace placeholders in a single pass, so substituted values are left untouched
+fn replace_placeholders(text: &str, replacements: &[(&str, String)]) -> String {
+    let mut result: String = String::new();
+    let mut rest: &str = text;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        match replacements
+            .iter()
+            .find(|(placeholder, _)| rest.starts_with(placeholder))
+        {
+            Some((placeholder, value)) => {
+                result.push_str(value);
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                result.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    result.push_str(rest);
+
+    return result;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification() -> Notification {
+        return Notification {
+            id: 8863,
+            title: Some("My YC app: Dropbox; {url}".to_string()),
+            url: Some("http://www.getdropbox.com/u/2/screencast.html".to_string()),
+            by: Some("dhouston".to_string()),
+            score: Some(111),
+            discussion_url: "https://news.ycombinator.com/item?id=8863".to_string(),
+            rule: "dropbox".to_string(),
+        };
+    }
+
+    #[test]
+    fn test_command_arguments() {
+        let arguments: Vec<String> =
+            command_arguments("notify-send 'HN: {title}' \"{rule} {id}\"", &notification())
+                .unwrap();
+
+        // Values containing placeholders are not substituted again
+        assert_eq!(
+            arguments,
+            vec![
+                "notify-send".to_string(),
+                "HN: My YC app: Dropbox; {url}".to_string(),
+                "dropbox 8863".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_command_arguments_invalid() {
+        assert!(command_arguments("", &notification()).is_err());
+        assert!(command_arguments("notify-send 'unterminated", &notification()).is_err());
+    }
+
+    #[test]
+    fn test_notifier_config_from_toml() {
+        #[derive(Deserialize)]
+        struct Notifiers {
+            notifiers: Vec<NotifierConfig>,
+        }
+
+        let parsed: Notifiers = toml::from_str(
+            r#"
+            [[notifiers]]
+            type = "stdout"
+
+            [[notifiers]]
+            type = "webhook"
+            url = "https://hooks.example.com/hn"
+
+            [[notifiers]]
+            type = "command"
+            command = "notify-send {title}"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            parsed.notifiers,
+            vec![
+                NotifierConfig::Stdout,
+                NotifierConfig::Webhook {
+                    url: "https://hooks.example.com/hn".to_string()
+                },
+                NotifierConfig::Command {
+                    command: "notify-send {title}".to_string()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::models::story::StoryItem;
+use crate::utils::helpers::contains_phrase;
+
+/// A rule that stories are matched against in notify mode
+///
+/// Each list matches if any of its entries match, and a rule matches when every
+/// non-empty list matches (e.g. a keyword *and* a domain). A rule with no
+/// criteria never matches.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifyRule {
+    /// Optional name shown in notifications
+    pub name: Option<String>,
+    /// Case-insensitive whole words or phrases to look for in the title
+    pub keywords: Vec<String>,
+    /// Domains the story URL must belong to (subdomains included)
+    pub domains: Vec<String>,
+    /// Usernames of story authors
+    pub authors: Vec<String>,
+}
+
+impl NotifyRule {
+    /// Whether a story matches this rule
+    pub fn matches(&self, story: &StoryItem) -> bool {
+        if self.keywords.is_empty() && self.domains.is_empty() && self.authors.is_empty() {
+            return false;
+        }
+
+        let title: String = story.title.as_deref().unwrap_or("").to_lowercase();
+        let keyword_match: bool = self.keywords.is_empty()
+            || self
+                .keywords
+                .iter()
+                .any(|keyword| contains_phrase(&title, &keyword.to_lowercase()));
+
+        let host: Option<String> = story.url.as_deref().and_then(url_host);
+        let domain_match: bool = self.domains.is_empty()
+            || host.as_deref().is_some_and(|host| {
+                self.domains
+                    .iter()
+                    .any(|domain| host_matches_domain(host, domain))
+            });
+
+        let author_match: bool = self.authors.is_empty()
+            || story.by.as_deref().is_some_and(|by| {
+                self.authors
+                    .iter()
+                    .any(|author| author.eq_ignore_ascii_case(by))
+            });
+
+        return keyword_match && domain_match && author_match;
+    }
+
+    /// Label for the rule in notifications (its name, or a summary of its criteria)
+    pub fn label(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+
+        let mut parts: Vec<String> = Vec::new();
+        if !self.keywords.is_empty() {
+            parts.push(format!("keyword: {}", self.keywords.join("|")));
+        }
+        if !self.domains.is_empty() {
+            parts.push(format!("domain: {}", self.domains.join("|")));
+        }
+        if !self.authors.is_empty() {
+            parts.push(format!("author: {}", self.authors.join("|")));
+        }
+
+        return parts.join(", ");
+    }
+}
+
+/// Lowercase host of a URL without a leading `www.`
+fn url_host(url: &str) -> Option<String> {
+    let host: String = Url::parse(url).ok()?.host_str()?.to_lowercase();
+    return Some(host.strip_prefix("www.").unwrap_or(&host).to_string());
+}
+
+/// Whether a host is the domain or one of its subdomains
+fn host_matches_domain(host: &str, domain: &str) -> bool {
+    let domain: String = domain.trim().to_lowercase();
+    let domain: &str = domain.strip_prefix("www.").unwrap_or(&domain);
+    return host == domain || host.ends_with(&format!(".{domain}"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn story(title: &str, url: Option<&str>, by: &str) -> StoryItem {
+        return StoryItem {
+            id: 1,
+            title: Some(title.to_string()),
+            url: url.map(str::to_string),
+            by: Some(by.to_string()),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_keyword_matches_whole_words() {
+        let rule: NotifyRule = NotifyRule {
+            keywords: vec!["Rust".to_string(), "hacker news".to_string()],
+            ..Default::default()
+        };
+
+        assert!(rule.matches(&story("Why we rewrote it in Rust", None, "a")));
+        assert!(rule.matches(&story("Rust: a retrospective", None, "a")));
+        assert!(rule.matches(&story("Show HN: Hacker News in the terminal", None, "a")));
+        assert!(!rule.matches(&story("In code we trust", None, "a")));
+        assert!(!rule.matches(&story("Rustic furniture", None, "a")));
+    }
+
+    #[test]
+    fn test_domain_matches_subdomains() {
+        let rule: NotifyRule = NotifyRule {
+            domains: vec!["example.com".to_string()],
+            ..Default::default()
+        };
+
+        assert!(rule.matches(&story("A", Some("https://example.com/a"), "a")));
+        assert!(rule.matches(&story("A", Some("https://www.example.com/a"), "a")));
+        assert!(rule.matches(&story("A", Some("https://blog.example.com/a"), "a")));
+        assert!(!rule.matches(&story("A", Some("https://notexample.com/a"), "a")));
+        assert!(!rule.matches(&story("A", None, "a")));
+    }
+
+    #[test]
+    fn test_author_and_combined_criteria() {
+        let rule: NotifyRule = NotifyRule {
+            keywords: vec!["release".to_string()],
+            authors: vec!["PG".to_string()],
+            ..Default::default()
+        };
+
+        assert!(rule.matches(&story("New release", None, "pg")));
+        assert!(!rule.matches(&story("New release", None, "dang")));
+        assert!(!rule.matches(&story("Essay", None, "pg")));
+    }
+
+    #[test]
+    fn test_empty_rule_never_matches() {
+        assert!(!NotifyRule::default().matches(&story("Anything", None, "a")));
+    }
+
+    #[test]
+    fn test_label() {
+        let rule: NotifyRule = NotifyRule {
+            keywords: vec!["rust".to_string(), "go".to_string()],
+            domains: vec!["github.com".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(rule.label(), "keyword: rust|go, domain: github.com");
+
+        let named: NotifyRule = NotifyRule {
+            name: Some("Our product".to_string()),
+            ..rule
+        };
+        assert_eq!(named.label(), "Our product");
+    }
+}
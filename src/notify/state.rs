@@ -0,0 +1,95 @@
+use std::collections::{HashSet, VecDeque};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::HnResult;
+use crate::utils::json_store::JsonFile;
+use crate::utils::paths::data_dir;
+
+/// File name of the notify state within the data directory
+const STATE_FILE_NAME: &str = "notify_state.json";
+/// Number of notified item IDs remembered before the oldest are forgotten
+const MAX_TRACKED_ITEMS: usize = 10_000;
+
+/// Persisted record of the items that have already been notified about
+pub struct NotifyState {
+    file: JsonFile<StateFile>,
+    notified: VecDeque<u64>,
+    lookup: HashSet<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StateFile {
+    notified: Vec<u64>,
+}
+
+impl NotifyState {
+    /// Load the state from a file, starting empty when it does not exist
+    pub fn load(path: PathBuf) -> HnResult<Self> {
+        let file: JsonFile<StateFile> = JsonFile::new(path, "notify state");
+        let notified: VecDeque<u64> = file
+            .load()?
+            .map_or_else(VecDeque::new, |state| VecDeque::from(state.notified));
+        let lookup: HashSet<u64> = notified.iter().copied().collect();
+
+        return Ok(Self {
+            file,
+            notified,
+            lookup,
+        });
+    }
+
+    /// Load the state from the local data directory
+    pub fn load_default() -> HnResult<Self> {
+        return Self::load(data_dir()?.join(STATE_FILE_NAME));
+    }
+
+    /// Whether an item has already been notified about
+    pub fn contains(&self, id: u64) -> bool {
+        return self.lookup.contains(&id);
+    }
+
+    /// Record an item as notified and persist the state
+    pub fn insert(&mut self, id: u64) -> HnResult<()> {
+        if !self.lookup.insert(id) {
+            return Ok(());
+        }
+
+        self.notified.push_back(id);
+        while self.notified.len() > MAX_TRACKED_ITEMS {
+            if let Some(oldest) = self.notified.pop_front() {
+                self.lookup.remove(&oldest);
+            }
+        }
+
+        return self.save();
+    }
+
+    fn save(&self) -> HnResult<()> {
+        return self.file.save(&StateFile {
+            notified: self.notified.iter().copied().collect(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notify_state_persists() {
+        let directory = tempfile::tempdir().unwrap();
+        let path: PathBuf = directory.path().join("notify_state.json");
+
+        let mut state: NotifyState = NotifyState::load(path.clone()).unwrap();
+        assert!(!state.contains(42));
+        state.insert(42).unwrap();
+        assert!(state.contains(42));
+
+        // A restart sees previously notified items
+        let reloaded: NotifyState = NotifyState::load(path).unwrap();
+        assert!(reloaded.contains(42));
+        assert!(!reloaded.contains(43));
+    }
+}
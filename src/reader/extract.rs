@@ -0,0 +1,515 @@
+use std::collections::HashMap;
+
+use ego_tree::{NodeId, NodeRef};
+use reqwest::Url;
+use scraper::{ElementRef, Html, Node, Selector};
+
+use crate::reader::{Article, Block};
+use crate::types::HnResult;
+
+/// Elements that never contain readable article content
+const SKIPPED_TAGS: [&str; 12] = [
+    "script", "style", "noscript", "nav", "footer", "aside", "form", "iframe", "svg", "button",
+    "template", "select",
+];
+
+/// Elements that start a new block of content when converting to text
+const BLOCK_TAGS: [&str; 24] = [
+    "address",
+    "article",
+    "blockquote",
+    "div",
+    "dl",
+    "figure",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "tr",
+    "td",
+    "ul",
+];
+
+/// Class/id fragments that suggest an element holds the main content
+const POSITIVE_HINTS: [&str; 10] = [
+    "article", "body", "content", "entry", "main", "page", "post", "text", "blog", "story",
+];
+
+/// Class/id fragments that suggest an element is page chrome rather than content
+const NEGATIVE_HINTS: [&str; 16] = [
+    "comment", "footer", "footnote", "masthead", "menu", "meta", "nav", "related", "share",
+    "sidebar", "social", "sponsor", "promo", "banner", "advert", "widget",
+];
+
+/// Minimum paragraph length (in characters) for it to contribute to a candidate's score
+const MIN_PARAGRAPH_LENGTH: usize = 25;
+
+/// Extract the main readable content from an HTML document
+///
+/// Uses a readability-style heuristic: paragraphs award points to their parent and
+/// grandparent elements, the scores are penalized by link density, and the best scoring
+/// element (plus any related siblings) is converted to text blocks. Relative links are
+/// resolved against `base_url`.
+pub fn extract_article(html: &str, base_url: &str) -> HnResult<Article> {
+    let document: Html = Html::parse_document(html);
+    let base: Option<Url> = Url::parse(base_url).ok();
+
+    let mut extractor = Extractor {
+        base,
+        links: Vec::new(),
+    };
+
+    let roots: Vec<ElementRef> = find_content_roots(&document);
+    let mut blocks: Vec<Block> = Vec::new();
+    for root in roots {
+        extractor.collect_blocks(root, &mut blocks);
+    }
+
+    let title: Option<String> = find_title(&document);
+
+    // The title is rendered separately, so drop a leading heading that repeats it
+    if let (Some(title), Some(Block::Heading(_, heading))) = (&title, blocks.first())
+        && heading.eq_ignore_ascii_case(title)
+    {
+        blocks.remove(0);
+    }
+
+    if blocks.is_empty() {
+        return Err("No readable content found on the page".into());
+    }
+
+    return Ok(Article {
+        title,
+        blocks,
+        links: extractor.links,
+    });
+}
+
+/// Find the document title, preferring the Open Graph title
+fn find_title(document: &Html) -> Option<String> {
+    let og_title = Selector::parse(r#"meta[property="og:title"]"#).unwrap();
+    let title = Selector::parse("title").unwrap();
+    let h1 = Selector::parse("h1").unwrap();
+
+    let candidates = document
+        .select(&og_title)
+        .filter_map(|element| element.attr("content").map(str::to_string))
+        .chain(document.select(&title).map(|element| text_of(element)))
+        .chain(document.select(&h1).map(|element| text_of(element)));
+
+    for candidate in candidates {
+        let candidate: String = collapse_whitespace(&candidate);
+        if !candidate.is_empty() {
+            return Some(candidate);
+        }
+    }
+
+    return None;
+}
+
+/// Find the element(s) most likely to contain the article body
+fn find_content_roots(document: &Html) -> Vec<ElementRef<'_>> {
+    let paragraphs = Selector::parse("p, pre, td").unwrap();
+    let mut scores: HashMap<NodeId, f64> = HashMap::new();
+
+    for paragraph in document.select(&paragraphs) {
+        if is_unlikely(paragraph) {
+            continue;
+        }
+
+        let text: String = collapse_whitespace(&text_of(paragraph));
+        let length: usize = text.chars().count();
+        if length < MIN_PARAGRAPH_LENGTH {
+            continue;
+        }
+
+        let score: f64 = 1.0 + text.matches(',').count() as f64 + (length as f64 / 100.0).min(3.0);
+
+        let parent: Option<ElementRef> = paragraph.parent().and_then(ElementRef::wrap);
+        if let Some(parent) = parent {
+            *scores
+                .entry(parent.id())
+                .or_insert_with(|| initial_score(parent)) += score;
+
+            if let Some(grandparent) = parent.parent().and_then(ElementRef::wrap) {
+                *scores
+                    .entry(grandparent.id())
+                    .or_insert_with(|| initial_score(grandparent)) += score / 2.0;
+            }
+        }
+    }
+
+    // Penalize candidates that are mostly links (navigation, link lists, ...)
+    let best: Option<(ElementRef, f64)> = scores
+        .iter()
+        .filter_map(|(id, score)| {
+            let element: ElementRef = document.tree.get(*id).and_then(ElementRef::wrap)?;
+            return Some((element, score * (1.0 - link_density(element))));
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    let Some((top, top_score)) = best else {
+        let fallback = Selector::parse("article, main, body").unwrap();
+        return document.select(&fallback).take(1).collect();
+    };
+
+    // Include siblings that look like they belong to the same article
+    let Some(parent) = top.parent().and_then(ElementRef::wrap) else {
+        return vec![top];
+    };
+
+    let threshold: f64 = (top_score * 0.2).max(10.0);
+    let roots: Vec<ElementRef> = parent
+        .child_elements()
+        .filter(|sibling| {
+            if sibling.id() == top.id() {
+                return true;
+            }
+
+            if let Some(score) = scores.get(&sibling.id())
+                && score * (1.0 - link_density(*sibling)) >= threshold
+            {
+                return true;
+            }
+
+            if sibling.value().name() == "p" {
+                let length: usize = collapse_whitespace(&text_of(*sibling)).chars().count();
+                return length > 80 && link_density(*sibling) < 0.25;
+            }
+
+            return false;
+        })
+        .collect();
+
+    return roots;
+}
+
+/// Starting score for a candidate based on its tag and class/id hints
+fn initial_score(element: ElementRef) -> f64 {
+    let tag_score: f64 = match element.value().name() {
+        "article" => 10.0,
+        "div" | "main" | "section" => 5.0,
+        "pre" | "td" | "blockquote" => 3.0,
+        "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+        _ => 0.0,
+    };
+
+    return tag_score + class_weight(element);
+}
+
+/// Weight an element by whether its class/id look like content or chrome
+fn class_weight(element: ElementRef) -> f64 {
+    let mut weight: f64 = 0.0;
+    let hints: String = format!(
+        "{} {}",
+        element.attr("class").unwrap_or(""),
+        element.attr("id").unwrap_or("")
+    )
+    .to_lowercase();
+
+    if NEGATIVE_HINTS.iter().any(|hint| hints.contains(hint)) {
+        weight -= 25.0;
+    }
+
+    if POSITIVE_HINTS.iter().any(|hint| hints.contains(hint)) {
+        weight += 25.0;
+    }
+
+    return weight;
+}
+
+/// Whether an element sits inside page chrome that should never be treated as content
+fn is_unlikely(element: ElementRef) -> bool {
+    return element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .chain(std::iter::once(element))
+        .any(|ancestor| {
+            SKIPPED_TAGS.contains(&ancestor.value().name()) || class_weight(ancestor) < 0.0
+        });
+}
+
+/// Ratio of link text to all text within an element
+fn link_density(element: ElementRef) -> f64 {
+    let links = Selector::parse("a").unwrap();
+    let text_length: usize = text_of(element).trim().chars().count();
+    if text_length == 0 {
+        return 0.0;
+    }
+
+    let link_length: usize = element
+        .select(&links)
+        .map(|link| text_of(link).trim().chars().count())
+        .sum();
+
+    return link_length as f64 / text_length as f64;
+}
+
+/// All text within an element
+fn text_of(element: ElementRef) -> String {
+    return element.text().collect::<String>();
+}
+
+/// Collapse runs of whitespace into single spaces and trim the ends
+fn collapse_whitespace(text: &str) -> String {
+    return text.split_whitespace().collect::<Vec<&str>>().join(" ");
+}
+
+/// Converts the chosen content elements into text blocks
+struct Extractor {
+    base: Option<Url>,
+    links: Vec<String>,
+}
+
+impl Extractor {
+    /// Convert an element and its children into blocks
+    fn collect_blocks(&mut self, element: ElementRef, blocks: &mut Vec<Block>) {
+        let name: &str = element.value().name();
+        if SKIPPED_TAGS.contains(&name) {
+            return;
+        }
+
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level: u8 = name[1..].parse().unwrap_or(1);
+                push_text(blocks, self.inline_text(element), |text| {
+                    Block::Heading(level, text)
+                });
+            }
+            "p" => push_text(blocks, self.inline_text(element), Block::Paragraph),
+            "pre" => {
+                let code: String = text_of(element).trim_end().to_string();
+                if !code.trim().is_empty() {
+                    blocks.push(Block::Code(code));
+                }
+            }
+            "blockquote" => push_text(blocks, self.inline_text(element), Block::Quote),
+            "li" => push_text(blocks, self.inline_text(element), Block::ListItem),
+            _ => {
+                // Page chrome nested in the content (share bars, comment sections, ...)
+                if matches!(name, "div" | "section" | "header") && class_weight(element) < 0.0 {
+                    return;
+                }
+
+                self.collect_children(element, blocks);
+            }
+        }
+    }
+
+    /// Convert a container's children, grouping loose inline content into paragraphs
+    fn collect_children(&mut self, element: ElementRef, blocks: &mut Vec<Block>) {
+        let mut inline: String = String::new();
+
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => inline.push_str(text),
+                Node::Element(child_element) => {
+                    let Some(child_ref) = ElementRef::wrap(child) else {
+                        continue;
+                    };
+
+                    if BLOCK_TAGS.contains(&child_element.name()) {
+                        push_text(blocks, collapse_whitespace(&inline), Block::Paragraph);
+                        inline.clear();
+                        self.collect_blocks(child_ref, blocks);
+                    } else {
+                        self.append_inline(child, &mut inline);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        push_text(blocks, collapse_whitespace(&inline), Block::Paragraph);
+    }
+
+    /// Text content of an element with links replaced by numbered references
+    fn inline_text(&mut self, element: ElementRef) -> String {
+        let mut text: String = String::new();
+        for child in element.children() {
+            self.append_inline(child, &mut text);
+        }
+
+        return collapse_whitespace(&text);
+    }
+
+    /// Append a node's inline text to `out`, numbering any links
+    fn append_inline(&mut self, node: NodeRef<Node>, out: &mut String) {
+        match node.value() {
+            Node::Text(text) => out.push_str(text),
+            Node::Element(element) => {
+                let name: &str = element.name();
+                if SKIPPED_TAGS.contains(&name) {
+                    return;
+                }
+
+                if name == "br" {
+                    out.push(' ');
+                    return;
+                }
+
+                for child in node.children() {
+                    self.append_inline(child, out);
+                }
+
+                if name == "a"
+                    && let Some(href) = element.attr("href")
+                    && let Some(index) = self.link_index(href)
+                {
+                    out.push_str(&format!("[{}]", index));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Get the 1-based reference number for a link, registering it if new
+    fn link_index(&mut self, href: &str) -> Option<usize> {
+        let href: &str = href.trim();
+        if href.is_empty() || href.starts_with('#') || href.starts_with("javascript:") {
+            return None;
+        }
+
+        let absolute: String = match &self.base {
+            Some(base) => base.join(href).ok()?.to_string(),
+            None => Url::parse(href).ok()?.to_string(),
+        };
+
+        if let Some(position) = self.links.iter().position(|link| *link == absolute) {
+            return Some(position + 1);
+        }
+
+        self.links.push(absolute);
+        return Some(self.links.len());
+    }
+}
+
+/// Push a text block unless the text is empty
+fn push_text(blocks: &mut Vec<Block>, text: String, block: impl FnOnce(String) -> Block) {
+    if !text.is_empty() {
+        blocks.push(block(text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOG_POST: &str = include_str!("../../tests/fixtures/reader/blog_post.html");
+    const DIV_LAYOUT: &str = include_str!("../../tests/fixtures/reader/div_layout.html");
+    const NO_CONTENT: &str = include_str!("../../tests/fixtures/reader/no_content.html");
+
+    fn all_text(article: &Article) -> String {
+        return article
+            .blocks
+            .iter()
+            .map(|block| match block {
+                Block::Heading(_, text)
+                | Block::Paragraph(text)
+                | Block::ListItem(text)
+                | Block::Quote(text)
+                | Block::Code(text) => text.clone(),
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+    }
+
+    #[test]
+    fn test_extract_article_blog_post() {
+        let article: Article =
+            extract_article(BLOG_POST, "https://blog.example.com/posts/rust-async").unwrap();
+
+        assert_eq!(
+            article.title.as_deref(),
+            Some("Understanding async Rust from the ground up")
+        );
+
+        let text: String = all_text(&article);
+        assert!(text.contains("Futures in Rust are lazy"));
+        assert!(text.contains("fn main()"));
+
+        // Page chrome is left out
+        assert!(!text.contains("Subscribe to our newsletter"));
+        assert!(!text.contains("Share on"));
+        assert!(!text.contains("Great post!"));
+        assert!(!text.contains("Home"));
+
+        // The leading heading repeats the title and is dropped
+        assert!(!matches!(
+            article.blocks.first(),
+            Some(Block::Heading(1, _))
+        ));
+        assert!(
+            article
+                .blocks
+                .contains(&Block::Heading(2, "Polling and wakers".to_string()))
+        );
+        assert!(
+            article
+                .blocks
+                .iter()
+                .any(|block| matches!(block, Block::Code(_)))
+        );
+        assert!(
+            article.blocks.iter().any(
+                |block| matches!(block, Block::ListItem(text) if text.starts_with("Executors"))
+            )
+        );
+    }
+
+    #[test]
+    fn test_extract_article_resolves_and_numbers_links() {
+        let article: Article =
+            extract_article(BLOG_POST, "https://blog.example.com/posts/rust-async").unwrap();
+
+        assert_eq!(
+            article.links,
+            vec![
+                "https://doc.rust-lang.org/std/future/trait.Future.html".to_string(),
+                "https://blog.example.com/posts/pinning".to_string(),
+            ]
+        );
+
+        let text: String = all_text(&article);
+        assert!(text.contains("the Future trait[1]"));
+        assert!(text.contains("previous post on pinning[2]"));
+        // Repeated links reuse the same reference number
+        assert!(text.contains("Future[1] docs"));
+    }
+
+    #[test]
+    fn test_extract_article_div_layout() {
+        let article: Article =
+            extract_article(DIV_LAYOUT, "https://news.example.org/2026/10/story").unwrap();
+
+        assert_eq!(
+            article.title.as_deref(),
+            Some("City council approves bike lanes")
+        );
+
+        let text: String = all_text(&article);
+        assert!(text.contains("The city council voted 7-2"));
+        assert!(text.contains("Construction is expected to begin"));
+        assert!(!text.contains("Most read"));
+        assert!(!text.contains("Copyright"));
+    }
+
+    #[test]
+    fn test_extract_article_no_content() {
+        let result: HnResult<Article> = extract_article(NO_CONTENT, "https://example.com");
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,21 @@
+pub mod extract;
+pub mod render;
+
+/// A block of readable article content
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Heading(u8, String),
+    Paragraph(String),
+    ListItem(String),
+    Quote(String),
+    Code(String),
+}
+
+/// Readable article content extracted from a story's linked web page
+#[derive(Debug, Clone, PartialEq)]
+pub struct Article {
+    pub title: Option<String>,
+    pub blocks: Vec<Block>,
+    /// Absolute link URLs, referenced from block text as `[n]` (1-based)
+    pub links: Vec<String>,
+}
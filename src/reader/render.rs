@@ -0,0 +1,138 @@
+use console::Style;
+use textwrap::Options;
+
+use crate::reader::{Article, Block};
+
+/// Narrowest width articles are wrapped to, regardless of terminal size
+const MIN_WIDTH: usize = 20;
+/// Widest width articles are wrapped to, to keep lines readable on wide terminals
+const MAX_WIDTH: usize = 100;
+
+/// Render an article as wrapped terminal text with styled headings and a links section
+pub fn render_article(article: &Article, width: usize) -> String {
+    let width: usize = width.clamp(MIN_WIDTH, MAX_WIDTH);
+    let title_style: Style = Style::new().bold().underlined();
+    let heading_style: Style = Style::new().bold();
+    let dim_style: Style = Style::new().dim();
+
+    let mut lines: Vec<String> = Vec::new();
+
+    if let Some(title) = &article.title {
+        for line in wrap(title, width, "", "") {
+            lines.push(title_style.apply_to(line).to_string());
+        }
+        lines.push(String::new());
+    }
+
+    for block in &article.blocks {
+        match block {
+            Block::Heading(level, text) => {
+                let marker: String = "#".repeat(usize::from(*level).clamp(1, 6));
+                let prefix: String = format!("{} ", marker);
+                let indent: String = " ".repeat(prefix.len());
+                for line in wrap(text, width, &prefix, &indent) {
+                    lines.push(heading_style.apply_to(line).to_string());
+                }
+            }
+            Block::Paragraph(text) => lines.extend(wrap(text, width, "", "")),
+            Block::ListItem(text) => lines.extend(wrap(text, width, "  • ", "    ")),
+            Block::Quote(text) => {
+                for line in wrap(text, width, "  │ ", "  │ ") {
+                    lines.push(dim_style.apply_to(line).to_string());
+                }
+            }
+            Block::Code(code) => {
+                // Code is kept verbatim; long lines are left for the pager to handle
+                for line in code.lines() {
+                    lines.push(dim_style.apply_to(format!("    {}", line)).to_string());
+                }
+            }
+        }
+        lines.push(String::new());
+    }
+
+    if !article.links.is_empty() {
+        lines.push(heading_style.apply_to("Links").to_string());
+        for (index, link) in article.links.iter().enumerate() {
+            lines.push(format!("[{}] {}", index + 1, link));
+        }
+    }
+
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    return lines.join("\n");
+}
+
+/// Wrap text to the given width with separate first-line and continuation indents
+fn wrap(text: &str, width: usize, initial_indent: &str, subsequent_indent: &str) -> Vec<String> {
+    let options: Options = Options::new(width)
+        .initial_indent(initial_indent)
+        .subsequent_indent(subsequent_indent);
+
+    return textwrap::wrap(text, options)
+        .into_iter()
+        .map(|line| line.into_owned())
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::measure_text_width;
+
+    /// Width of the widest line in rendered output, ignoring escape codes
+    fn max_line_width(rendered: &str) -> usize {
+        return rendered.lines().map(measure_text_width).max().unwrap_or(0);
+    }
+
+    fn sample_article() -> Article {
+        return Article {
+            title: Some("A fairly long article title that needs wrapping".to_string()),
+            blocks: vec![
+                Block::Paragraph(
+                    "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod \
+                     tempor incididunt ut labore et dolore magna aliqua[1]."
+                        .to_string(),
+                ),
+                Block::Heading(2, "Section heading".to_string()),
+                Block::ListItem("First item in a list that is long enough to wrap".to_string()),
+                Block::Quote("A quotation that also happens to wrap across lines".to_string()),
+                Block::Code("fn main() {}".to_string()),
+            ],
+            links: vec!["https://example.com/lorem".to_string()],
+        };
+    }
+
+    #[test]
+    fn test_render_article_wraps_to_width() {
+        let rendered: String = render_article(&sample_article(), 30);
+
+        // Code and link lines are verbatim, everything else must fit the width
+        assert!(max_line_width(&rendered) <= 30);
+    }
+
+    #[test]
+    fn test_render_article_layout() {
+        let rendered: String =
+            console::strip_ansi_codes(&render_article(&sample_article(), 80)).into_owned();
+
+        assert!(rendered.starts_with("A fairly long article title"));
+        assert!(rendered.contains("## Section heading"));
+        assert!(rendered.contains("  • First item"));
+        assert!(rendered.contains("  │ A quotation"));
+        assert!(rendered.contains("    fn main() {}"));
+        assert!(rendered.ends_with("Links\n[1] https://example.com/lorem"));
+    }
+
+    #[test]
+    fn test_render_article_clamps_width() {
+        let mut article: Article = sample_article();
+        article.links.clear();
+
+        let rendered: String = render_article(&article, 5);
+
+        assert!(max_line_width(&rendered) <= MIN_WIDTH);
+    }
+}
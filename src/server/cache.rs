@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex as AsyncMutex;
+
+use crate::types::HnResult;
+
+/// Number of keys kept before expired entries are pruned
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// A cached value and when it was fetched
+type Slot<V> = Arc<AsyncMutex<Option<(Instant, V)>>>;
+
+/// Time-based response cache that coalesces concurrent requests for the same key
+///
+/// Each key has its own slot lock, so while one request fetches a value, other
+/// requests for that key wait for it instead of hitting the upstream API again.
+/// Failed fetches are not cached.
+pub struct ResponseCache<K, V> {
+    ttl: Duration,
+    slots: Mutex<HashMap<K, Slot<V>>>,
+}
+
+impl<K, V> ResponseCache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    pub fn new(ttl: Duration) -> Self {
+        return Self {
+            ttl,
+            slots: Mutex::new(HashMap::new()),
+        };
+    }
+
+    /// Get the cached value for a key, or fetch and cache it when missing or expired
+    pub async fn get_or_fetch<F, Fut>(&self, key: K, fetch: F) -> HnResult<V>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = HnResult<V>>,
+    {
+        let slot: Slot<V> = self.slot(key);
+        let mut entry = slot.lock().await;
+
+        if let Some((fetched_at, value)) = entry.as_ref()
+            && fetched_at.elapsed() < self.ttl
+        {
+            return Ok(value.clone());
+        }
+
+        let value: V = fetch().await?;
+        *entry = Some((Instant::now(), value.clone()));
+
+        return Ok(value);
+    }
+
+    /// Get or create the slot for a key
+    fn slot(&self, key: K) -> Slot<V> {
+        let mut slots = self.slots.lock().unwrap_or_else(|error| error.into_inner());
+
+        if !slots.contains_key(&key) && slots.len() >= PRUNE_THRESHOLD {
+            // Keep slots that are fresh or being fetched right now
+            slots.retain(|_, slot| match slot.try_lock() {
+                Ok(entry) => entry
+                    .as_ref()
+                    .is_some_and(|(fetched_at, _)| fetched_at.elapsed() < self.ttl),
+                Err(_) => true,
+            });
+        }
+
+        return slots.entry(key).or_default().clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use futures::future::join_all;
+
+    #[tokio::test]
+    async fn test_get_or_fetch_coalesces_concurrent_requests() {
+        let cache: ResponseCache<u64, String> = ResponseCache::new(Duration::from_secs(60));
+        let fetches: AtomicUsize = AtomicUsize::new(0);
+
+        let results: Vec<HnResult<String>> = join_all((0..10).map(|_| {
+            cache.get_or_fetch(1, || async {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                return Ok("story".to_string());
+            })
+        }))
+        .await;
+
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert!(
+            results
+                .iter()
+                .all(|result| result.as_deref().ok() == Some("story"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_or_fetch_expires_and_skips_errors() {
+        let cache: ResponseCache<u64, u32> = ResponseCache::new(Duration::ZERO);
+
+        let failed: HnResult<u32> = cache
+            .get_or_fetch(1, || async { Err("upstream down".into()) })
+            .await;
+        assert!(failed.is_err());
+
+        // Nothing was cached for the failure, and a zero TTL never serves stale values
+        assert_eq!(cache.get_or_fetch(1, || async { Ok(1) }).await.unwrap(), 1);
+        assert_eq!(cache.get_or_fetch(1, || async { Ok(2) }).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_or_fetch_keys_are_independent() {
+        let cache: ResponseCache<&str, u32> = ResponseCache::new(Duration::from_secs(60));
+
+        assert_eq!(
+            cache.get_or_fetch("a", || async { Ok(1) }).await.unwrap(),
+            1
+        );
+        assert_eq!(
+            cache.get_or_fetch("b", || async { Ok(2) }).await.unwrap(),
+            2
+        );
+        assert_eq!(
+            cache.get_or_fetch("a", || async { Ok(3) }).await.unwrap(),
+            1
+        );
+    }
+}
@@ -0,0 +1,530 @@
+pub mod cache;
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use http_body_util::Full;
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{self, HeaderValue};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode, Uri};
+use hyper_util::rt::TokioIo;
+use serde::Serialize;
+use serde_json::json;
+use tokio::net::TcpListener;
+
+use crate::api::HackerNewsGateway;
+use crate::models::story::{HackerNewsStoryType, StoryItem};
+use crate::models::user::HackerNewsUser;
+use crate::print_error;
+use crate::server::cache::ResponseCache;
+use crate::types::HnResult;
+
+/// Number of stories returned by `/stories/{type}` without a `limit` parameter
+const DEFAULT_STORIES_LIMIT: u16 = 30;
+
+/// Settings for the HTTP API server
+#[derive(Debug, Clone)]
+pub struct ServerOptions {
+    pub bind: SocketAddr,
+    /// Origins allowed to make cross-origin requests, or `*` for any origin
+    pub cors_origins: Vec<String>,
+    pub cache_ttl: Duration,
+}
+
+/// State shared by every connection
+struct ServerState<G> {
+    gateway: G,
+    options: ServerOptions,
+    stories: ResponseCache<(HackerNewsStoryType, u16), Vec<StoryItem>>,
+    items: ResponseCache<u64, Option<StoryItem>>,
+    users: ResponseCache<String, Option<HackerNewsUser>>,
+}
+
+/// JSON API over HTTP exposing story lists, items and users from a gateway
+pub struct ApiServer<G> {
+    listener: TcpListener,
+    state: Arc<ServerState<G>>,
+}
+
+impl<G> ApiServer<G>
+where
+    G: HackerNewsGateway + 'static,
+{
+    /// Bind the server to the configured address
+    pub async fn bind(gateway: G, options: ServerOptions) -> HnResult<Self> {
+        let listener: TcpListener = TcpListener::bind(options.bind)
+            .await
+            .map_err(|error| format!("Failed to bind {}: {error}", options.bind))?;
+
+        let state: ServerState<G> = ServerState {
+            gateway,
+            stories: ResponseCache::new(options.cache_ttl),
+            items: ResponseCache::new(options.cache_ttl),
+            users: ResponseCache::new(options.cache_ttl),
+            options,
+        };
+
+        return Ok(Self {
+            listener,
+            state: Arc::new(state),
+        });
+    }
+
+    /// Get the address the server is listening on
+    pub fn local_addr(&self) -> HnResult<SocketAddr> {
+        return Ok(self.listener.local_addr()?);
+    }
+
+    /// Accept and serve connections until `shutdown` completes
+    pub async fn run(self, shutdown: impl Future<Output = ()>) -> HnResult<()> {
+        tokio::pin!(shutdown);
+
+        loop {
+            let (stream, _) = tokio::select! {
+                accepted = self.listener.accept() => accepted?,
+                _ = &mut shutdown => return Ok(()),
+            };
+
+            let state: Arc<ServerState<G>> = Arc::clone(&self.state);
+            tokio::spawn(async move {
+                let service = service_fn(move |request: Request<Incoming>| {
+                    let state: Arc<ServerState<G>> = Arc::clone(&state);
+                    async move { Ok::<_, Infallible>(state.handle(&request).await) }
+                });
+
+                if let Err(error) = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    print_error!("Connection error: {error}");
+                }
+            });
+        }
+    }
+}
+
+impl<G> ServerState<G>
+where
+    G: HackerNewsGateway,
+{
+    /// Respond to a request, adding CORS headers for allowed origins
+    async fn handle<B>(&self, request: &Request<B>) -> Response<Full<Bytes>> {
+        let mut response: Response<Full<Bytes>> = match *request.method() {
+            Method::GET => self.route(request.uri()).await,
+            Method::OPTIONS => preflight_response(),
+            _ => {
+                let mut response =
+                    error_response(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed");
+                response
+                    .headers_mut()
+                    .insert(header::ALLOW, HeaderValue::from_static("GET, OPTIONS"));
+                response
+            }
+        };
+
+        let origin: Option<&str> = request
+            .headers()
+            .get(header::ORIGIN)
+            .and_then(|value| value.to_str().ok());
+        if let Some(allowed) = origin.and_then(|origin| self.allowed_origin(origin))
+            && let Ok(value) = HeaderValue::from_str(allowed)
+        {
+            let headers = response.headers_mut();
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, value);
+            headers.insert(header::VARY, HeaderValue::from_static("Origin"));
+        }
+
+        return response;
+    }
+
+    /// Get the `Access-Control-Allow-Origin` value for a request origin, if allowed
+    fn allowed_origin<'a>(&'a self, origin: &'a str) -> Option<&'a str> {
+        return self
+            .options
+            .cors_origins
+            .iter()
+            .find(|allowed| *allowed == "*" || allowed.eq_ignore_ascii_case(origin))
+            .map(|allowed| if allowed == "*" { "*" } else { origin });
+    }
+
+    /// Route a GET request to its endpoint
+    async fn route(&self, uri: &Uri) -> Response<Full<Bytes>> {
+        let segments: Vec<&str> = uri.path().trim_matches('/').split('/').collect();
+
+        return match segments.as_slice() {
+            ["stories", story_type] => self.stories(story_type, uri.query()).await,
+            ["item", id] => self.item(id).await,
+            ["user", id] if !id.is_empty() => self.user(id).await,
+            _ => error_response(StatusCode::NOT_FOUND, "Not found"),
+        };
+    }
+
+    /// `GET /stories/{type}?limit=N`
+    async fn stories(&self, name: &str, query: Option<&str>) -> Response<Full<Bytes>> {
+        let Ok(story_type) = HackerNewsStoryType::from_str(name, true) else {
+            return error_response(
+                StatusCode::NOT_FOUND,
+                &format!("Unknown story type '{name}', expected top, new, best, ask, show or jobs"),
+            );
+        };
+
+        let limit: u16 = match query_parameter(query, "limit").map(str::parse::<u16>) {
+            None => DEFAULT_STORIES_LIMIT.min(story_type.max_stories_limit()),
+            Some(Ok(limit)) if (1..=story_type.max_stories_limit()).contains(&limit) => limit,
+            Some(_) => {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    &format!(
+                        "limit must be between 1 and {} for '{}' stories",
+                        story_type.max_stories_limit(),
+                        story_type.full_name()
+                    ),
+                );
+            }
+        };
+
+        let result: HnResult<Vec<StoryItem>> = self
+            .stories
+            .get_or_fetch((story_type, limit), || {
+                self.gateway.fetch_stories(&story_type, limit)
+            })
+            .await;
+
+        return self.json_result(result);
+    }
+
+    /// `GET /item/{id}`
+    async fn item(&self, id: &str) -> Response<Full<Bytes>> {
+        let Ok(id) = id.parse::<u64>() else {
+            return error_response(StatusCode::BAD_REQUEST, "Item ID must be a number");
+        };
+
+        let result: HnResult<Option<StoryItem>> = self
+            .items
+            .get_or_fetch(id, || self.gateway.fetch_item(id))
+            .await;
+
+        return match result {
+            Ok(None) => error_response(StatusCode::NOT_FOUND, &format!("Item {id} not found")),
+            result => self.json_result(result),
+        };
+    }
+
+    /// `GET /user/{id}`
+    async fn user(&self, id: &str) -> Response<Full<Bytes>> {
+        let result: HnResult<Option<HackerNewsUser>> = self
+            .users
+            .get_or_fetch(id.to_string(), || self.gateway.fetch_user(id))
+            .await;
+
+        return match result {
+            Ok(None) => error_response(StatusCode::NOT_FOUND, &format!("User '{id}' not found")),
+            result => self.json_result(result),
+        };
+    }
+
+    /// Serialize a gateway result, reporting upstream failures as `502 Bad Gateway`
+    fn json_result<T: Serialize>(&self, result: HnResult<T>) -> Response<Full<Bytes>> {
+        return match result {
+            Ok(value) => {
+                let mut response: Response<Full<Bytes>> = json_response(StatusCode::OK, &value);
+                let cache_control: String =
+                    format!("public, max-age={}", self.options.cache_ttl.as_secs());
+                if let Ok(value) = HeaderValue::from_str(&cache_control) {
+                    response.headers_mut().insert(header::CACHE_CONTROL, value);
+                }
+                response
+            }
+            Err(error) => error_response(
+                StatusCode::BAD_GATEWAY,
+                &format!("Hacker News API request failed: {error}"),
+            ),
+        };
+    }
+}
+
+/// Get the value of a query string parameter
+fn query_parameter<'a>(query: Option<&'a str>, name: &str) -> Option<&'a str> {
+    return query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value);
+}
+
+/// Build a JSON response
+fn json_response<T: Serialize>(status: StatusCode, value: &T) -> Response<Full<Bytes>> {
+    let body: Vec<u8> = serde_json::to_vec(value).unwrap_or_default();
+    let mut response: Response<Full<Bytes>> = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+
+    return response;
+}
+
+/// Build a JSON error response, e.g. `{"error": "Not found"}`
+fn error_response(status: StatusCode, message: &str) -> Response<Full<Bytes>> {
+    return json_response(status, &json!({ "error": message }));
+}
+
+/// Answer a CORS preflight request
+fn preflight_response() -> Response<Full<Bytes>> {
+    let mut response: Response<Full<Bytes>> = Response::new(Full::new(Bytes::new()));
+    *response.status_mut() = StatusCode::NO_CONTENT;
+
+    let headers = response.headers_mut();
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_METHODS,
+        HeaderValue::from_static("GET, OPTIONS"),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_HEADERS,
+        HeaderValue::from_static("Content-Type"),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_MAX_AGE,
+        HeaderValue::from_static("86400"),
+    );
+
+    return response;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tokio::sync::oneshot;
+
+    use crate::client::HackerNewsClient;
+    use crate::utils::test_server;
+
+    /// Start a mock Hacker News API that serves canned JSON by path and counts requests,
+    /// returning its base URL
+    async fn spawn_upstream(
+        routes: HashMap<&'static str, &'static str>,
+        hits: Arc<AtomicUsize>,
+    ) -> String {
+        return test_server::spawn(move |request| {
+            hits.fetch_add(1, Ordering::SeqCst);
+            let body: &str = routes.get(request.uri().path()).copied().unwrap_or("null");
+            return Response::new(Full::new(Bytes::from(body)));
+        })
+        .await;
+    }
+
+    /// Start the API server against a mock upstream, returning its base URL
+    async fn spawn_server(
+        hits: Arc<AtomicUsize>,
+        cors_origins: Vec<String>,
+    ) -> (String, oneshot::Sender<()>) {
+        let upstream: String = spawn_upstream(
+            HashMap::from([
+                ("/v0/topstories.json", "[1, 2]"),
+                (
+                    "/v0/item/1.json",
+                    r#"{"id": 1, "by": "alice", "title": "First", "score": 10}"#,
+                ),
+                (
+                    "/v0/item/2.json",
+                    r#"{"id": 2, "by": "bob", "title": "Second", "score": 5}"#,
+                ),
+                (
+                    "/v0/user/alice.json",
+                    r#"{"id": "alice", "created": 1600000000, "karma": 42}"#,
+                ),
+            ]),
+            hits,
+        )
+        .await;
+
+        let gateway: HackerNewsClient = HackerNewsClient::with_base_url(&format!("{upstream}/v0"));
+        let server: ApiServer<HackerNewsClient> = ApiServer::bind(
+            gateway,
+            ServerOptions {
+                bind: "127.0.0.1:0".parse().unwrap(),
+                cors_origins,
+                cache_ttl: Duration::from_secs(60),
+            },
+        )
+        .await
+        .unwrap();
+
+        let address: SocketAddr = server.local_addr().unwrap();
+        let (stop, stopped) = oneshot::channel::<()>();
+        tokio::spawn(server.run(async {
+            let _ = stopped.await;
+        }));
+
+        return (format!("http://{address}"), stop);
+    }
+
+    #[tokio::test]
+    async fn test_server_end_to_end() {
+        let hits: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+        let (base_url, _stop) = spawn_server(Arc::clone(&hits), Vec::new()).await;
+        let client: reqwest::Client = reqwest::Client::new();
+
+        let response = client
+            .get(format!("{base_url}/stories/top?limit=2"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE.as_str()],
+            "application/json"
+        );
+        let stories: Vec<StoryItem> = response.json().await.unwrap();
+        let titles: Vec<Option<String>> = stories.into_iter().map(|story| story.title).collect();
+        assert_eq!(
+            titles,
+            vec![Some("First".to_string()), Some("Second".to_string())]
+        );
+
+        // The ID list plus two items
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+
+        let user: HackerNewsUser = client
+            .get(format!("{base_url}/user/alice"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(user.karma, 42);
+
+        let missing = client
+            .get(format!("{base_url}/item/99"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), 404);
+
+        let invalid = client
+            .get(format!("{base_url}/item/abc"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(invalid.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_server_caches_and_coalesces_requests() {
+        let hits: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+        let (base_url, _stop) = spawn_server(Arc::clone(&hits), Vec::new()).await;
+        let client: reqwest::Client = reqwest::Client::new();
+
+        let statuses: Vec<u16> = futures::future::join_all((0..5).map(|_| {
+            let request = client.get(format!("{base_url}/item/1")).send();
+            async move { request.await.unwrap().status().as_u16() }
+        }))
+        .await;
+        assert_eq!(statuses, vec![200; 5]);
+
+        client
+            .get(format!("{base_url}/item/1"))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_server_cors() {
+        let hits: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+        let (base_url, _stop) =
+            spawn_server(hits, vec!["https://dashboard.example.com".to_string()]).await;
+        let client: reqwest::Client = reqwest::Client::new();
+
+        let allowed = client
+            .get(format!("{base_url}/item/1"))
+            .header("Origin", "https://dashboard.example.com")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            allowed.headers()["access-control-allow-origin"],
+            "https://dashboard.example.com"
+        );
+
+        let denied = client
+            .get(format!("{base_url}/item/1"))
+            .header("Origin", "https://evil.example.com")
+            .send()
+            .await
+            .unwrap();
+        assert!(!denied.headers().contains_key("access-control-allow-origin"));
+
+        let preflight = client
+            .request(Method::OPTIONS, format!("{base_url}/item/1"))
+            .header("Origin", "https://dashboard.example.com")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(preflight.status(), 204);
+        assert_eq!(
+            preflight.headers()["access-control-allow-methods"],
+            "GET, OPTIONS"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_route_errors() {
+        let mut mock_gateway = crate::api::MockHackerNewsGateway::new();
+        mock_gateway
+            .expect_fetch_stories()
+            .returning(|_, _| Err("connection reset".into()));
+
+        let server: ApiServer<_> = ApiServer::bind(
+            mock_gateway,
+            ServerOptions {
+                bind: "127.0.0.1:0".parse().unwrap(),
+                cors_origins: vec!["*".to_string()],
+                cache_ttl: Duration::from_secs(60),
+            },
+        )
+        .await
+        .unwrap();
+
+        let cases: [(&str, StatusCode); 5] = [
+            ("/stories/top", StatusCode::BAD_GATEWAY),
+            ("/stories/ask?limit=500", StatusCode::BAD_REQUEST),
+            ("/stories/latest", StatusCode::NOT_FOUND),
+            ("/user/", StatusCode::NOT_FOUND),
+            ("/", StatusCode::NOT_FOUND),
+        ];
+        for (path, status) in cases {
+            let request: Request<()> = Request::get(path)
+                .header(header::ORIGIN, "https://any.example.com")
+                .body(())
+                .unwrap();
+            let response: Response<Full<Bytes>> = server.state.handle(&request).await;
+
+            assert_eq!(response.status(), status, "{path}");
+            assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+        }
+
+        let request: Request<()> = Request::post("/item/1").body(()).unwrap();
+        let response: Response<Full<Bytes>> = server.state.handle(&request).await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[test]
+    fn test_query_parameter() {
+        assert_eq!(query_parameter(Some("a=1&limit=20"), "limit"), Some("20"));
+        assert_eq!(query_parameter(Some("limits=20"), "limit"), None);
+        assert_eq!(query_parameter(None, "limit"), None);
+    }
+}
@@ -0,0 +1,177 @@
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+
+use crate::models::story::StoryItem;
+use crate::ui::layout::StoryRow;
+
+/// A story matching a filter query
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoryMatch {
+    /// Index of the story in the unfiltered list
+    pub index: usize,
+    /// Matched character positions in the title
+    pub title: Vec<usize>,
+    /// Matched character positions in the domain
+    pub domain: Vec<usize>,
+}
+
+impl StoryMatch {
+    /// Positions of the matched characters that are visible in a laid out row
+    pub fn row_highlights(&self, row: &StoryRow) -> Vec<usize> {
+        let title = self
+            .title
+            .iter()
+            .map(|position| row.title.start + position)
+            .filter(|position| row.title.contains(position));
+        let domain = self
+            .domain
+            .iter()
+            .map(|position| row.domain.start + position)
+            .filter(|position| row.domain.contains(position));
+
+        return title.chain(domain).collect();
+    }
+}
+
+/// Fuzzy-match stories against a query over their title, domain and author
+///
+/// Matches are sorted best first, keeping list order for equal scores. An empty
+/// query matches every story in list order.
+pub fn filter_stories(stories: &[StoryItem], query: &str) -> Vec<StoryMatch> {
+    let query: &str = query.trim();
+    if query.is_empty() {
+        return (0..stories.len())
+            .map(|index| StoryMatch {
+                index,
+                title: Vec::new(),
+                domain: Vec::new(),
+            })
+            .collect();
+    }
+
+    let matcher: SkimMatcherV2 = SkimMatcherV2::default().smart_case();
+    let mut matches: Vec<(i64, StoryMatch)> = stories
+        .iter()
+        .enumerate()
+        .filter_map(|(index, story)| {
+            let title: &str = story.title.as_deref().unwrap_or("No Title");
+            let domain: String = story.domain().unwrap_or_default();
+            let author: &str = story.by.as_deref().unwrap_or_default();
+
+            // Fields are searched together so a query can span them, e.g. "rust github"
+            let haystack: String = format!("{title} {domain} {author}");
+            let (score, positions) = matcher.fuzzy_indices(&haystack, query)?;
+
+            let title_chars: usize = title.chars().count();
+            let domain_start: usize = title_chars + 1;
+            let domain_end: usize = domain_start + domain.chars().count();
+
+            return Some((
+                score,
+                StoryMatch {
+                    index,
+                    title: positions
+                        .iter()
+                        .copied()
+                        .filter(|position| *position < title_chars)
+                        .collect(),
+                    domain: positions
+                        .iter()
+                        .filter(|position| (domain_start..domain_end).contains(position))
+                        .map(|position| position - domain_start)
+                        .collect(),
+                },
+            ));
+        })
+        .collect();
+
+    // Stable sort keeps list order for equal scores
+    matches.sort_by_key(|(score, _)| -score);
+
+    return matches
+        .into_iter()
+        .map(|(_, story_match)| story_match)
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stories() -> Vec<StoryItem> {
+        return vec![
+            StoryItem {
+                id: 1,
+                title: Some("Writing a database in Go".to_string()),
+                url: Some("https://example.com/db".to_string()),
+                by: Some("alice".to_string()),
+                ..Default::default()
+            },
+            StoryItem {
+                id: 2,
+                title: Some("Rust 2026 roadmap".to_string()),
+                url: Some("https://blog.rust-lang.org/roadmap".to_string()),
+                by: Some("bob".to_string()),
+                ..Default::default()
+            },
+            StoryItem {
+                id: 3,
+                title: Some("Ask HN: Favorite terminal tools?".to_string()),
+                by: Some("rustacean".to_string()),
+                ..Default::default()
+            },
+        ];
+    }
+
+    fn indexes(matches: &[StoryMatch]) -> Vec<usize> {
+        return matches
+            .iter()
+            .map(|story_match| story_match.index)
+            .collect();
+    }
+
+    #[test]
+    fn test_filter_stories_empty_query_keeps_order() {
+        assert_eq!(indexes(&filter_stories(&stories(), "  ")), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_filter_stories_matches_title_domain_and_author() {
+        let stories: Vec<StoryItem> = stories();
+
+        // Title and domain matches rank above the author-only match
+        assert_eq!(indexes(&filter_stories(&stories, "rust")), vec![1, 2]);
+        assert_eq!(indexes(&filter_stories(&stories, "example")), vec![0]);
+        assert_eq!(indexes(&filter_stories(&stories, "alice")), vec![0]);
+        assert!(filter_stories(&stories, "haskell").is_empty());
+
+        let matches: Vec<StoryMatch> = filter_stories(&stories, "dtbs");
+        assert_eq!(matches[0].title, vec![10, 12, 14, 16]);
+    }
+
+    #[test]
+    fn test_filter_stories_domain_positions() {
+        let matches: Vec<StoryMatch> = filter_stories(&stories(), "rust-lang");
+
+        assert_eq!(indexes(&matches), vec![1]);
+        assert_eq!(matches[0].domain, (0..9).collect::<Vec<usize>>());
+        assert!(matches[0].title.is_empty());
+    }
+
+    #[test]
+    fn test_row_highlights_skip_hidden_characters() {
+        let story_match: StoryMatch = StoryMatch {
+            index: 0,
+            title: vec![0, 5, 9],
+            domain: vec![1],
+        };
+        let row: StoryRow = StoryRow {
+            text: "1.  Writing a… (example.com)".to_string(),
+            title: 4..13,
+            domain: 16..27,
+        };
+
+        // The last title match was cut off by the ellipsis
+        assert_eq!(story_match.row_highlights(&row), vec![4, 9, 17]);
+    }
+}
@@ -0,0 +1,395 @@
+use std::ops::Range;
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::models::story::StoryItem;
+use crate::utils::time::TimeFormatter;
+
+/// Space between columns
+const COLUMN_GAP: &str = "  ";
+/// Narrowest title column before optional columns are dropped
+const MIN_TITLE_WIDTH: usize = 20;
+/// Narrowest title text kept when the domain and rankings still fit beside it
+const MIN_TRUNCATED_TITLE_WIDTH: usize = 10;
+/// Marks truncated text
+const ELLIPSIS: char = '…';
+
+/// Columns that are dropped, in order, when the terminal is too narrow
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum OptionalColumn {
+    Age,
+    Comments,
+    Score,
+}
+
+/// Cell values of one story row before alignment
+struct RowCells {
+    rank: String,
+    score: String,
+    comments: String,
+    age: String,
+    title: String,
+    domain: Option<String>,
+    /// Domain and list rankings shown after the title, e.g. " (example.com) {Top #3}"
+    title_suffix: String,
+}
+
+impl RowCells {
+    fn new(index: usize, story: &StoryItem, time: &TimeFormatter) -> Self {
+        let domain: Option<String> = story.domain();
+        let domain_text: String = domain
+            .as_ref()
+            .map(|domain| format!(" ({domain})"))
+            .unwrap_or_default();
+
+        return Self {
+            rank: format!("{}.", index + 1),
+            score: format!("{} pts", story.score.unwrap_or(0)),
+            comments: format!("{} cmts", story.descendants.unwrap_or(0)),
+            age: story
+                .time
+                .map(|created| time.format_short(created))
+                .unwrap_or_default(),
+            title: story
+                .title
+                .clone()
+                .unwrap_or_else(|| "No Title".to_string()),
+            title_suffix: format!("{domain_text}{}", story.get_rankings_text()),
+            domain,
+        };
+    }
+
+    fn optional(&self, column: OptionalColumn) -> &str {
+        return match column {
+            OptionalColumn::Age => &self.age,
+            OptionalColumn::Comments => &self.comments,
+            OptionalColumn::Score => &self.score,
+        };
+    }
+}
+
+/// A laid out story row
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoryRow {
+    pub text: String,
+    /// Characters of `text` showing the title, leaving out any ellipsis
+    pub title: Range<usize>,
+    /// Characters of `text` showing the domain (without parentheses)
+    pub domain: Range<usize>,
+}
+
+/// Lay out stories as aligned rows that fit within `width` terminal columns
+///
+/// Rows have right-aligned rank, score, comment count and age columns, followed by
+/// the title, domain and list rankings. Titles are truncated with an ellipsis, and
+/// the age, comments and score columns are dropped in that order when the title
+/// would otherwise get narrower than 20 columns.
+pub fn layout_story_rows(
+    stories: &[StoryItem],
+    width: usize,
+    time: &TimeFormatter,
+) -> Vec<StoryRow> {
+    let rows: Vec<RowCells> = stories
+        .iter()
+        .enumerate()
+        .map(|(index, story)| RowCells::new(index, story, time))
+        .collect();
+
+    let column_width = |cell: &dyn Fn(&RowCells) -> &str| -> usize {
+        return rows.iter().map(|row| cell(row).width()).max().unwrap_or(0);
+    };
+    let rank_width: usize = column_width(&|row| &row.rank);
+
+    let mut columns: Vec<(OptionalColumn, usize)> = [
+        OptionalColumn::Score,
+        OptionalColumn::Comments,
+        OptionalColumn::Age,
+    ]
+    .into_iter()
+    .map(|column| (column, column_width(&|row| row.optional(column))))
+    .filter(|(_, column_width)| *column_width > 0)
+    .collect();
+
+    let fixed_width = |columns: &[(OptionalColumn, usize)]| -> usize {
+        return rank_width
+            + columns
+                .iter()
+                .map(|(_, width)| COLUMN_GAP.len() + width)
+                .sum::<usize>()
+            + COLUMN_GAP.len();
+    };
+
+    for column in [
+        OptionalColumn::Age,
+        OptionalColumn::Comments,
+        OptionalColumn::Score,
+    ] {
+        if width.saturating_sub(fixed_width(&columns)) >= MIN_TITLE_WIDTH {
+            break;
+        }
+        columns.retain(|(optional, _)| *optional != column);
+    }
+
+    let title_width: usize = width.saturating_sub(fixed_width(&columns));
+
+    return rows
+        .iter()
+        .map(|row| {
+            let mut line: String = pad_start(&row.rank, rank_width);
+            for (column, column_width) in &columns {
+                line.push_str(COLUMN_GAP);
+                line.push_str(&pad_start(row.optional(*column), *column_width));
+            }
+            line.push_str(COLUMN_GAP);
+
+            let title_start: usize = line.chars().count();
+            let fitted: FittedTitle = fit_title(&row.title, &row.title_suffix, title_width);
+            line.push_str(&fitted.text);
+
+            // Very narrow terminals cannot even fit the rank column
+            let text: String = truncate_to_width(&line, width);
+            let mut visible: usize = text.chars().count();
+            if fitted.truncated || text != line {
+                visible = visible.saturating_sub(1);
+            }
+
+            let domain_start: usize = title_start + fitted.suffix_start + " (".len();
+            let domain_chars: usize = row
+                .domain
+                .as_deref()
+                .map_or(0, |domain| domain.chars().count());
+            let clip = |range: Range<usize>| -> Range<usize> {
+                return range.start.min(visible)..range.end.min(visible);
+            };
+
+            return StoryRow {
+                title: clip(title_start..title_start + fitted.title_chars),
+                domain: clip(domain_start..domain_start + domain_chars),
+                text,
+            };
+        })
+        .collect();
+}
+
+/// A title and its suffix fitted into the title column
+struct FittedTitle {
+    text: String,
+    /// Number of title characters kept, not counting an ellipsis
+    title_chars: usize,
+    /// Character offset of the suffix within `text`
+    suffix_start: usize,
+    /// Whether `text` ends with an ellipsis
+    truncated: bool,
+}
+
+/// Fit a title and its suffix into `width` columns, truncating the title first
+fn fit_title(title: &str, suffix: &str, width: usize) -> FittedTitle {
+    let title_chars: usize = title.chars().count();
+
+    if title.width() + suffix.width() <= width {
+        return FittedTitle {
+            text: format!("{title}{suffix}"),
+            title_chars,
+            suffix_start: title_chars,
+            truncated: false,
+        };
+    }
+
+    let title_width: usize = width.saturating_sub(suffix.width());
+    if title_width >= MIN_TRUNCATED_TITLE_WIDTH {
+        let truncated_title: String = truncate_to_width(title, title_width);
+        let kept: usize = truncated_title.chars().count();
+        return FittedTitle {
+            text: format!("{truncated_title}{suffix}"),
+            title_chars: kept - 1,
+            suffix_start: kept,
+            truncated: false,
+        };
+    }
+
+    return FittedTitle {
+        text: truncate_to_width(&format!("{title}{suffix}"), width),
+        title_chars,
+        suffix_start: title_chars,
+        truncated: true,
+    };
+}
+
+/// Truncate text to at most `width` terminal columns, ending with an ellipsis when cut
+pub fn truncate_to_width(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+
+    if width == 0 {
+        return String::new();
+    }
+
+    let mut truncated: String = String::new();
+    let mut used: usize = 0;
+    for character in text.chars() {
+        let character_width: usize = character.width().unwrap_or(0);
+        // Leave room for the ellipsis
+        if used + character_width > width - 1 {
+            break;
+        }
+        truncated.push(character);
+        used += character_width;
+    }
+
+    truncated.push(ELLIPSIS);
+
+    return truncated;
+}
+
+/// Right-align text within `width` terminal columns
+fn pad_start(text: &str, width: usize) -> String {
+    let padding: usize = width.saturating_sub(text.width());
+    return format!("{}{text}", " ".repeat(padding));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    use crate::models::story::{HackerNewsStoryType, StoryRanking};
+    use crate::utils::time::tests::{test_formatter, test_now};
+    use crate::utils::time::{DisplayTimezone, TimeMode};
+
+    fn stories() -> Vec<StoryItem> {
+        return vec![
+            StoryItem {
+                id: 1,
+                title: Some("Show HN: A tiny Rust web framework".to_string()),
+                url: Some("https://github.com/someone/framework".to_string()),
+                score: Some(1234),
+                descendants: Some(456),
+                time: Some(test_now() - Duration::hours(3)),
+                ..Default::default()
+            },
+            StoryItem {
+                id: 2,
+                title: Some("日本語のタイトルのテストです".to_string()),
+                url: Some("https://www.example.jp/post".to_string()),
+                score: Some(7),
+                descendants: Some(0),
+                time: Some(test_now() - Duration::minutes(12)),
+                ..Default::default()
+            },
+        ];
+    }
+
+    fn layout_rows(width: usize) -> Vec<StoryRow> {
+        let time = test_formatter(TimeMode::Relative, DisplayTimezone::Local);
+        return layout_story_rows(&stories(), width, &time);
+    }
+
+    fn layout(width: usize) -> Vec<String> {
+        return layout_rows(width).into_iter().map(|row| row.text).collect();
+    }
+
+    /// The characters of a row within a range
+    fn slice(row: &StoryRow, range: &Range<usize>) -> String {
+        return row
+            .text
+            .chars()
+            .skip(range.start)
+            .take(range.len())
+            .collect();
+    }
+
+    #[test]
+    fn test_layout_story_rows_aligns_columns() {
+        let rows: Vec<String> = layout(100);
+
+        assert_eq!(
+            rows,
+            vec![
+                "1.  1234 pts  456 cmts   3h  Show HN: A tiny Rust web framework (github.com/someone)",
+                "2.     7 pts    0 cmts  12m  日本語のタイトルのテストです (example.jp)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_layout_story_rows_truncates_titles() {
+        let rows: Vec<String> = layout(60);
+
+        assert!(rows.iter().all(|row| row.width() <= 60), "{rows:#?}");
+        assert_eq!(
+            rows[0],
+            "1.  1234 pts  456 cmts   3h  Show HN: … (github.com/someone)"
+        );
+        // Wide characters take two columns each and are never split
+        assert_eq!(
+            rows[1],
+            "2.     7 pts    0 cmts  12m  日本語のタイトル… (example.jp)"
+        );
+    }
+
+    #[test]
+    fn test_layout_story_rows_drops_columns_when_narrow() {
+        let rows: Vec<String> = layout(40);
+
+        assert!(rows.iter().all(|row| row.width() <= 40), "{rows:#?}");
+        assert!(rows[0].starts_with("1.  1234 pts  Show HN"), "{}", rows[0]);
+
+        let rows: Vec<String> = layout(24);
+        assert!(rows[0].starts_with("1.  Show HN"), "{}", rows[0]);
+        assert!(rows.iter().all(|row| row.width() <= 24), "{rows:#?}");
+
+        assert!(layout(1).iter().all(|row| row == "…"));
+    }
+
+    #[test]
+    fn test_layout_story_rows_shows_rankings() {
+        let time = test_formatter(TimeMode::Relative, DisplayTimezone::Local);
+        let story: StoryItem = StoryItem {
+            id: 1,
+            title: Some("Title".to_string()),
+            rankings: vec![StoryRanking {
+                story_type: HackerNewsStoryType::S,
+                rank: 2,
+            }],
+            ..Default::default()
+        };
+
+        let rows: Vec<String> = layout_story_rows(&[story], 80, &time)
+            .into_iter()
+            .map(|row| row.text)
+            .collect();
+
+        // Text posts without a time have no domain or age
+        assert_eq!(rows, vec!["1.  0 pts  0 cmts  Title {Show #2}"]);
+    }
+
+    #[test]
+    fn test_layout_story_rows_title_and_domain_ranges() {
+        let rows: Vec<StoryRow> = layout_rows(100);
+        assert_eq!(
+            slice(&rows[0], &rows[0].title),
+            "Show HN: A tiny Rust web framework"
+        );
+        assert_eq!(slice(&rows[0], &rows[0].domain), "github.com/someone");
+        assert_eq!(slice(&rows[1], &rows[1].domain), "example.jp");
+
+        // Ranges leave out truncated text and the ellipsis
+        let rows: Vec<StoryRow> = layout_rows(60);
+        assert_eq!(slice(&rows[0], &rows[0].title), "Show HN: ");
+        assert_eq!(slice(&rows[0], &rows[0].domain), "github.com/someone");
+
+        let rows: Vec<StoryRow> = layout_rows(20);
+        assert_eq!(rows[0].text, "1.  Show HN: A tiny…");
+        assert_eq!(slice(&rows[0], &rows[0].title), "Show HN: A tiny");
+        assert!(rows[0].domain.is_empty());
+    }
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("hello", 5), "hello");
+        assert_eq!(truncate_to_width("hello world", 6), "hello…");
+        assert_eq!(truncate_to_width("日本語", 4), "日…");
+        assert_eq!(truncate_to_width("hello", 0), "");
+    }
+}
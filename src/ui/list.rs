@@ -0,0 +1,508 @@
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::Print,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+use crate::{
+    types::HnResult,
+    ui::{
+        layout::truncate_to_width,
+        theme::{self, Theme, ThemeRole},
+    },
+};
+
+/// Marker in front of the selected row
+const SELECTED_MARKER: &str = "❯ ";
+/// Space in front of the other rows, as wide as the marker
+const UNSELECTED_MARKER: &str = "  ";
+/// Marker in front of marked rows
+const MARKED_MARKER: &str = "✓ ";
+/// Space in front of unmarked rows, as wide as the mark
+const UNMARKED_MARKER: &str = "  ";
+/// Key help shown below the list
+const FOOTER: &str =
+    "↑/↓ move · PgUp/PgDn page · Space mark · a mark all · / filter · Enter select · Esc quit";
+/// Key help shown below the list while typing a filter
+const FILTER_FOOTER: &str =
+    "Type to filter · ↑/↓ move · Tab mark · Enter select · Esc clear filter";
+/// Lines used by the prompt and the footer
+const CHROME_HEIGHT: usize = 2;
+
+/// A row of a list
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListRow {
+    /// Index of the item the row shows, returned when it is selected
+    pub index: usize,
+    pub text: String,
+    /// Character positions to highlight, e.g. filter matches
+    pub highlights: Vec<usize>,
+}
+
+/// Selected item, marked items and filter query, kept between visits to a list
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ListCursor {
+    /// Index of the selected item
+    pub index: usize,
+    pub query: String,
+    /// Indexes of the marked items
+    pub marked: BTreeSet<usize>,
+}
+
+impl ListCursor {
+    /// Mark an item, or unmark it when it is already marked
+    pub fn toggle_mark(&mut self, index: usize) {
+        if !self.marked.remove(&index) {
+            self.marked.insert(index);
+        }
+    }
+
+    /// Mark all of the items, or unmark them when they are all marked already
+    pub fn toggle_marks(&mut self, indexes: &[usize]) {
+        if indexes.iter().all(|index| self.marked.contains(index)) {
+            for index in indexes {
+                self.marked.remove(index);
+            }
+        } else {
+            self.marked.extend(indexes);
+        }
+    }
+
+    /// What pressing Enter chooses: the marked items if there are any, otherwise the
+    /// selected item
+    fn choice(&self) -> ListChoice {
+        if self.marked.is_empty() {
+            return ListChoice::One(self.index);
+        }
+
+        return ListChoice::Marked(self.marked.iter().copied().collect());
+    }
+}
+
+/// Items chosen from a list
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListChoice {
+    /// The selected item's index
+    One(usize),
+    /// Indexes of the marked items, in list order
+    Marked(Vec<usize>),
+}
+
+/// Selection and scroll position of a list
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ListState {
+    len: usize,
+    selected: usize,
+    offset: usize,
+}
+
+impl ListState {
+    pub fn new(len: usize, selected: usize) -> Self {
+        return Self {
+            len,
+            selected: selected.min(len.saturating_sub(1)),
+            offset: 0,
+        };
+    }
+
+    pub fn selected(&self) -> usize {
+        return self.selected;
+    }
+
+    /// Move up one row, wrapping around to the bottom
+    pub fn previous(&mut self) {
+        self.selected = if self.selected == 0 {
+            self.len.saturating_sub(1)
+        } else {
+            self.selected - 1
+        };
+    }
+
+    /// Move down one row, wrapping around to the top
+    pub fn next(&mut self) {
+        self.selected = if self.selected + 1 >= self.len {
+            0
+        } else {
+            self.selected + 1
+        };
+    }
+
+    /// Move up a page without wrapping
+    pub fn page_up(&mut self, height: usize) {
+        self.selected = self.selected.saturating_sub(height.max(1));
+    }
+
+    /// Move down a page without wrapping
+    pub fn page_down(&mut self, height: usize) {
+        self.selected = (self.selected + height.max(1)).min(self.len.saturating_sub(1));
+    }
+
+    pub fn first(&mut self) {
+        self.selected = 0;
+    }
+
+    pub fn last(&mut self) {
+        self.selected = self.len.saturating_sub(1);
+    }
+
+    /// Scroll so the selected row is visible in a window of `height` rows
+    ///
+    /// Returns the range of visible rows.
+    pub fn scroll_into_view(&mut self, height: usize) -> std::ops::Range<usize> {
+        let height: usize = height.max(1);
+
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+
+        // Fill the window again when it grows after a resize
+        self.offset = self.offset.min(self.len.saturating_sub(height));
+
+        return self.offset..(self.offset + height).min(self.len);
+    }
+}
+
+/// Restores the terminal when the list closes, even on errors
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> HnResult<Self> {
+        terminal::enable_raw_mode()?;
+        // Construct the guard first so raw mode is undone if the screen switch fails
+        let guard: TerminalGuard = TerminalGuard;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+
+        return Ok(guard);
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Let the user pick an item from a full-screen list
+///
+/// `rows` lays out the rows matching a filter query for a given width. It is called
+/// again whenever the query changes or the terminal is resized, so the list reflows
+/// to the new size. Pressing `/` starts typing a filter query, and Space or Tab marks
+/// rows for a batch action.
+///
+/// `on_select` is called with the index of the selected item whenever the selection
+/// moves, e.g. to start loading its details.
+///
+/// Returns the chosen items, or `None` when the user quits with Esc, `q` or Ctrl+C.
+/// The cursor starts on `cursor` and is updated with the final selection, marks and
+/// query.
+pub fn select<F, S>(
+    prompt: &str,
+    cursor: &mut ListCursor,
+    rows: F,
+    mut on_select: S,
+) -> HnResult<Option<ListChoice>>
+where
+    F: Fn(usize, &str) -> Vec<ListRow>,
+    S: FnMut(usize),
+{
+    let _guard: TerminalGuard = TerminalGuard::enter()?;
+    let mut stdout = io::stdout();
+
+    let (mut width, mut height) = terminal_size()?;
+    let mut filtering: bool = false;
+    let mut lines: Vec<ListRow> = rows(row_width(width), &cursor.query);
+    let mut state: ListState = ListState::new(lines.len(), position_of(&lines, cursor.index));
+    let mut notified: Option<usize> = None;
+
+    loop {
+        let list_height: usize = height.saturating_sub(CHROME_HEIGHT);
+        let mut header: String = prompt.to_string();
+        if filtering || !cursor.query.is_empty() {
+            header.push_str(&format!("  /{} ({} matches)", cursor.query, lines.len()));
+        }
+        if !cursor.marked.is_empty() {
+            header.push_str(&format!("  [{} marked]", cursor.marked.len()));
+        }
+        let footer: &str = if filtering { FILTER_FOOTER } else { FOOTER };
+        draw(
+            &mut stdout,
+            (&header, footer),
+            &lines,
+            &cursor.marked,
+            &mut state,
+            width,
+            list_height,
+        )?;
+
+        if let Some(line) = lines.get(state.selected()) {
+            cursor.index = line.index;
+            if notified != Some(line.index) {
+                notified = Some(line.index);
+                on_select(line.index);
+            }
+        }
+
+        let previous_query: String = cursor.query.clone();
+
+        match event::read()? {
+            Event::Resize(columns, rows_count) => {
+                (width, height) = (usize::from(columns), usize::from(rows_count));
+                lines = rows(row_width(width), &cursor.query);
+            }
+            Event::Key(key) if key.kind != KeyEventKind::Release => match key {
+                KeyEvent {
+                    code: KeyCode::Char('c'),
+                    modifiers: KeyModifiers::CONTROL,
+                    ..
+                } => return Ok(None),
+                KeyEvent {
+                    code: KeyCode::Char('u'),
+                    modifiers: KeyModifiers::CONTROL,
+                    ..
+                } if filtering => cursor.query.clear(),
+                KeyEvent { code, .. } => match code {
+                    KeyCode::Enter if !lines.is_empty() => return Ok(Some(cursor.choice())),
+                    KeyCode::Tab if !lines.is_empty() => {
+                        cursor.toggle_mark(cursor.index);
+                        state.next();
+                    }
+                    KeyCode::Up => state.previous(),
+                    KeyCode::Down => state.next(),
+                    KeyCode::PageUp => state.page_up(list_height),
+                    KeyCode::PageDown => state.page_down(list_height),
+                    KeyCode::Home => state.first(),
+                    KeyCode::End => state.last(),
+                    KeyCode::Esc if filtering => {
+                        filtering = false;
+                        cursor.query.clear();
+                    }
+                    KeyCode::Backspace if filtering && cursor.query.is_empty() => {
+                        filtering = false;
+                    }
+                    KeyCode::Backspace if filtering => {
+                        cursor.query.pop();
+                    }
+                    KeyCode::Char(character) if filtering => cursor.query.push(character),
+                    KeyCode::Char('/') => filtering = true,
+                    KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+                    KeyCode::Char(' ') if !lines.is_empty() => {
+                        cursor.toggle_mark(cursor.index);
+                        state.next();
+                    }
+                    KeyCode::Char('a') => {
+                        let indexes: Vec<usize> = lines.iter().map(|line| line.index).collect();
+                        cursor.toggle_marks(&indexes);
+                    }
+                    KeyCode::Char('k') => state.previous(),
+                    KeyCode::Char('j') => state.next(),
+                    KeyCode::Char('h') => state.page_up(list_height),
+                    KeyCode::Char('l') => state.page_down(list_height),
+                    KeyCode::Char('g') => state.first(),
+                    KeyCode::Char('G') => state.last(),
+                    _ => {}
+                },
+            },
+            _ => {}
+        }
+
+        // Keep the selected item when it still matches, otherwise start at the best match
+        if cursor.query != previous_query {
+            lines = rows(row_width(width), &cursor.query);
+            state = ListState::new(lines.len(), position_of(&lines, cursor.index));
+            if cursor.query.len() > previous_query.len() {
+                state.first();
+            }
+        }
+    }
+}
+
+/// Position of an item's row, or the first row when it is not shown
+fn position_of(lines: &[ListRow], index: usize) -> usize {
+    return lines
+        .iter()
+        .position(|line| line.index == index)
+        .unwrap_or(0);
+}
+
+/// Current terminal width and height
+fn terminal_size() -> HnResult<(usize, usize)> {
+    let (columns, rows) = terminal::size()?;
+    return Ok((usize::from(columns), usize::from(rows)));
+}
+
+/// Width left for row text after the selection marker and the mark
+fn row_width(width: usize) -> usize {
+    return width.saturating_sub(UNSELECTED_MARKER.len() + UNMARKED_MARKER.len());
+}
+
+/// Redraw the header, the visible rows and the footer
+fn draw(
+    stdout: &mut io::Stdout,
+    (header, footer): (&str, &str),
+    lines: &[ListRow],
+    marked: &BTreeSet<usize>,
+    state: &mut ListState,
+    width: usize,
+    list_height: usize,
+) -> HnResult<()> {
+    let theme: &Theme = theme::current();
+
+    queue!(stdout, MoveTo(0, 0), Clear(ClearType::All))?;
+    queue!(
+        stdout,
+        Print(theme.paint(ThemeRole::Prompt, truncate_to_width(header, width)))
+    )?;
+
+    for (line_number, index) in state.scroll_into_view(list_height).enumerate() {
+        let line: &ListRow = &lines[index];
+        let mark: String = if marked.contains(&line.index) {
+            theme.paint(ThemeRole::Accent, MARKED_MARKER).to_string()
+        } else {
+            UNMARKED_MARKER.to_string()
+        };
+        let row: String = if index == state.selected() {
+            format!(
+                "{}{mark}{}",
+                theme.paint(ThemeRole::Selected, SELECTED_MARKER),
+                paint_row(theme, line, Some(ThemeRole::Selected))
+            )
+        } else {
+            format!("{UNSELECTED_MARKER}{mark}{}", paint_row(theme, line, None))
+        };
+
+        queue!(stdout, MoveTo(0, screen_row(line_number + 1)), Print(row))?;
+    }
+
+    queue!(
+        stdout,
+        MoveTo(0, screen_row(list_height + 1)),
+        Print(theme.paint(ThemeRole::Muted, truncate_to_width(footer, width)))
+    )?;
+
+    stdout.flush()?;
+
+    return Ok(());
+}
+
+/// Style a row, highlighting its matched characters
+fn paint_row(theme: &Theme, line: &ListRow, role: Option<ThemeRole>) -> String {
+    let mut painted: String = String::new();
+    let mut run: String = String::new();
+    let mut run_highlighted: bool = false;
+
+    let mut flush = |run: &mut String, highlighted: bool| {
+        if run.is_empty() {
+            return;
+        }
+        let run_role: Option<ThemeRole> = if highlighted {
+            Some(ThemeRole::Match)
+        } else {
+            role
+        };
+        match run_role {
+            Some(run_role) => painted.push_str(&theme.paint(run_role, run.as_str()).to_string()),
+            None => painted.push_str(run),
+        }
+        run.clear();
+    };
+
+    for (position, character) in line.text.chars().enumerate() {
+        let highlighted: bool = line.highlights.contains(&position);
+        if highlighted != run_highlighted {
+            flush(&mut run, run_highlighted);
+            run_highlighted = highlighted;
+        }
+        run.push(character);
+    }
+    flush(&mut run, run_highlighted);
+
+    return painted;
+}
+
+/// Convert a line number to a terminal row
+fn screen_row(line: usize) -> u16 {
+    return u16::try_from(line).unwrap_or(u16::MAX);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_state_wraps_single_steps() {
+        let mut state: ListState = ListState::new(3, 0);
+
+        state.previous();
+        assert_eq!(state.selected(), 2);
+
+        state.next();
+        assert_eq!(state.selected(), 0);
+    }
+
+    #[test]
+    fn test_list_state_pages_without_wrapping() {
+        let mut state: ListState = ListState::new(25, 3);
+
+        state.page_up(10);
+        assert_eq!(state.selected(), 0);
+
+        state.page_down(10);
+        state.page_down(10);
+        state.page_down(10);
+        assert_eq!(state.selected(), 24);
+
+        state.first();
+        assert_eq!(state.selected(), 0);
+
+        // Out of range defaults are clamped
+        assert_eq!(ListState::new(5, 9).selected(), 4);
+    }
+
+    #[test]
+    fn test_list_state_scrolls_selection_into_view() {
+        let mut state: ListState = ListState::new(30, 0);
+        assert_eq!(state.scroll_into_view(10), 0..10);
+
+        state.page_down(12);
+        assert_eq!(state.scroll_into_view(10), 3..13);
+
+        state.first();
+        assert_eq!(state.scroll_into_view(10), 0..10);
+
+        // A taller window after a resize shows as many rows as possible
+        state.last();
+        assert_eq!(state.scroll_into_view(10), 20..30);
+        assert_eq!(state.scroll_into_view(40), 0..30);
+    }
+
+    #[test]
+    fn test_list_cursor_marks() {
+        let mut cursor: ListCursor = ListCursor {
+            index: 4,
+            ..Default::default()
+        };
+        assert_eq!(cursor.choice(), ListChoice::One(4));
+
+        cursor.toggle_mark(7);
+        cursor.toggle_mark(2);
+        cursor.toggle_mark(5);
+        cursor.toggle_mark(7);
+        assert_eq!(cursor.choice(), ListChoice::Marked(vec![2, 5]));
+
+        // Marking all visible rows keeps the marks outside of them
+        cursor.toggle_marks(&[5, 8]);
+        assert_eq!(cursor.choice(), ListChoice::Marked(vec![2, 5, 8]));
+
+        // Unmarks them once they are all marked
+        cursor.toggle_marks(&[5, 8]);
+        assert_eq!(cursor.choice(), ListChoice::Marked(vec![2]));
+    }
+}
@@ -1,9 +1,18 @@
+pub mod fuzzy;
+pub mod layout;
+pub mod list;
+pub mod opener;
+pub mod pager;
+pub mod prefetch;
+pub mod print;
 pub mod terminal;
+pub mod theme;
+pub mod thread;
 
 use async_trait::async_trait;
 
 use crate::{
-    models::story::{HackerNewsStoryType, StoryItem},
+    models::{item::ItemThread, story::StoryItem},
     types::HnResult,
 };
 
@@ -12,9 +21,10 @@ use crate::{
 #[async_trait]
 pub trait UserInterface {
     /// Renders list of Hacker News stories and handles detail view
-    async fn render_stories_list(
-        &self,
-        stories: Vec<StoryItem>,
-        story_type: &HackerNewsStoryType,
-    ) -> HnResult<()>;
+    ///
+    /// `list_name` describes the list in prompts, e.g. "Top, Show" or "example.com".
+    async fn render_stories_list(&self, stories: Vec<StoryItem>, list_name: &str) -> HnResult<()>;
+
+    /// Shows an item's story and the comments down to it, then handles the story's detail view
+    async fn render_thread(&self, thread: ItemThread) -> HnResult<()>;
 }
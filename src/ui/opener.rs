@@ -0,0 +1,169 @@
+use std::io::{self, Write};
+use std::process::{Command, ExitStatus};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use clap::ValueEnum;
+
+use crate::print_success;
+use crate::types::HnResult;
+
+/// Placeholder replaced with the link URL in a custom browser command
+const URL_PLACEHOLDER: &str = "{url}";
+
+/// How links are opened (browser, print, clipboard, hyperlink)
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum OpenMode {
+    /// Open in the system browser, or with the `--browser` command when given
+    #[default]
+    Browser,
+
+    /// Print the URL
+    Print,
+
+    /// Copy the URL to the clipboard (OSC 52, works over SSH in most terminals)
+    Clipboard,
+
+    /// Print the URL as a clickable terminal hyperlink (OSC 8)
+    Hyperlink,
+}
+
+/// Opens story, discussion and author links the way the user configured
+#[derive(Clone, Debug, Default)]
+pub struct LinkOpener {
+    mode: OpenMode,
+    command: Option<String>,
+}
+
+impl LinkOpener {
+    pub fn new(mode: OpenMode, command: Option<String>) -> Self {
+        return Self { mode, command };
+    }
+
+    /// Open a link according to the configured mode
+    pub fn open(&self, url: &str) -> HnResult<()> {
+        match self.mode {
+            OpenMode::Browser => match &self.command {
+                Some(template) => run_command(template, url)?,
+                None => webbrowser::open(url).map_err(|error| {
+                    format!(
+                        "{error}. Use '--open print' or '--browser <COMMAND>' on headless systems"
+                    )
+                })?,
+            },
+            OpenMode::Print => println!("{url}"),
+            OpenMode::Clipboard => {
+                print!("{}", osc52_copy_sequence(url));
+                io::stdout().flush()?;
+                print_success!("Copied to clipboard: {url}");
+            }
+            OpenMode::Hyperlink => println!("{}", osc8_hyperlink(url, url)),
+        }
+
+        return Ok(());
+    }
+}
+
+/// Run a custom browser command template for a URL and wait for it to exit
+fn run_command(template: &str, url: &str) -> HnResult<()> {
+    let arguments: Vec<String> = command_arguments(template, url)?;
+    let status: ExitStatus = Command::new(&arguments[0])
+        .args(&arguments[1..])
+        .status()
+        .map_err(|error| format!("Failed to run '{}': {error}", arguments[0]))?;
+
+    if !status.success() {
+        return Err(format!("'{}' exited with {status}", arguments[0]).into());
+    }
+
+    return Ok(());
+}
+
+/// Split a browser command template into arguments, substituting the URL
+///
+/// The URL replaces every `{url}` placeholder, or is appended as the last
+/// argument when the template has none.
+fn command_arguments(template: &str, url: &str) -> HnResult<Vec<String>> {
+    let mut arguments: Vec<String> =
+        shlex::split(template).ok_or_else(|| format!("Invalid browser command: {template}"))?;
+
+    if arguments.is_empty() {
+        return Err("Browser command is empty".into());
+    }
+
+    if arguments
+        .iter()
+        .any(|argument| argument.contains(URL_PLACEHOLDER))
+    {
+        for argument in arguments.iter_mut() {
+            *argument = argument.replace(URL_PLACEHOLDER, url);
+        }
+    } else {
+        arguments.push(url.to_string());
+    }
+
+    return Ok(arguments);
+}
+
+/// Terminal escape sequence that copies text to the system clipboard
+fn osc52_copy_sequence(text: &str) -> String {
+    return format!("\x1b]52;c;{}\x07", STANDARD.encode(text));
+}
+
+/// Terminal escape sequence that renders `text` as a hyperlink to `url`
+pub fn osc8_hyperlink(url: &str, text: &str) -> String {
+    return format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://example.com/a?b=c&d=e";
+
+    #[test]
+    fn test_command_arguments_with_placeholder() {
+        let arguments: Vec<String> = command_arguments("firefox --new-tab {url}", URL).unwrap();
+
+        assert_eq!(arguments, vec!["firefox", "--new-tab", URL]);
+    }
+
+    #[test]
+    fn test_command_arguments_without_placeholder() {
+        let arguments: Vec<String> = command_arguments("w3m", URL).unwrap();
+
+        assert_eq!(arguments, vec!["w3m", URL]);
+    }
+
+    #[test]
+    fn test_command_arguments_quoted() {
+        let arguments: Vec<String> =
+            command_arguments(r#""/opt/My Browser/browser" --url={url}"#, URL).unwrap();
+
+        assert_eq!(
+            arguments,
+            vec![
+                "/opt/My Browser/browser".to_string(),
+                format!("--url={URL}")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_command_arguments_invalid() {
+        assert!(command_arguments("", URL).is_err());
+        assert!(command_arguments("firefox \"unterminated", URL).is_err());
+    }
+
+    #[test]
+    fn test_osc52_copy_sequence() {
+        assert_eq!(osc52_copy_sequence("hello"), "\x1b]52;c;aGVsbG8=\x07");
+    }
+
+    #[test]
+    fn test_osc8_hyperlink() {
+        assert_eq!(
+            osc8_hyperlink(URL, "link"),
+            format!("\x1b]8;;{URL}\x1b\\link\x1b]8;;\x1b\\")
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use crate::types::HnResult;
+
+/// Pager used when `PAGER` is not set
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Show content in the user's pager (`PAGER`, falling back to `less -R`)
+///
+/// When stdout is not a terminal, or no pager can be started, the content is
+/// printed directly instead.
+pub fn page(content: &str) -> HnResult<()> {
+    if !io::stdout().is_terminal() {
+        println!("{content}");
+        return Ok(());
+    }
+
+    let pager: String = env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+
+    let mut parts = pager.split_whitespace();
+    let program: &str = parts.next().unwrap_or("less");
+
+    let mut child = match Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(_) => {
+            println!("{content}");
+            return Ok(());
+        }
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager may exit before reading everything (e.g. user quits early)
+        match stdin.write_all(content.as_bytes()) {
+            Err(error) if error.kind() != io::ErrorKind::BrokenPipe => return Err(error.into()),
+            _ => {}
+        }
+    }
+
+    child.wait()?;
+
+    return Ok(());
+}
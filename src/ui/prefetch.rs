@@ -0,0 +1,436 @@
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+use serde::Deserialize;
+use tokio::task::JoinHandle;
+
+use crate::api::HackerNewsGateway;
+use crate::models::story::StoryItem;
+
+/// Top-level comments fetched for a story, prefetched or not
+pub const COMMENTS_LIMIT: usize = 30;
+/// Concurrent requests for a story's comments
+pub const COMMENTS_CONCURRENCY: usize = 5;
+
+/// Prefetch settings, from the `[prefetch]` config section
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PrefetchConfig {
+    /// Stories prefetched from the highlighted one down, 0 to turn prefetching off
+    pub stories: usize,
+    /// Most memory used by prefetched articles and comments, in megabytes
+    pub max_memory_mb: usize,
+}
+
+impl Default for PrefetchConfig {
+    fn default() -> Self {
+        return Self {
+            stories: 4,
+            max_memory_mb: 16,
+        };
+    }
+}
+
+/// A story detail that can be prefetched
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum DetailKey {
+    /// HTML of a linked article, by URL
+    Article(String),
+    /// Top-level comments, by story ID
+    Comments(u64),
+}
+
+#[derive(Debug, Clone)]
+enum Detail {
+    Article(String),
+    Comments(Vec<StoryItem>),
+}
+
+impl Detail {
+    /// Rough number of bytes the detail takes up in memory
+    fn size(&self) -> usize {
+        return match self {
+            Detail::Article(html) => html.len(),
+            Detail::Comments(comments) => comments
+                .iter()
+                .map(|comment| {
+                    return mem::size_of::<StoryItem>()
+                        + comment.text.as_ref().map_or(0, String::len)
+                        + comment.by.as_ref().map_or(0, String::len)
+                        + comment.kids.len() * mem::size_of::<u64>();
+                })
+                .sum(),
+        };
+    }
+}
+
+/// Prefetched details, evicting the least recently used once over a byte budget
+struct DetailCache {
+    budget: usize,
+    used: usize,
+    /// Details and when they were last used
+    entries: HashMap<DetailKey, (Detail, u64)>,
+    /// Counter ordering uses
+    clock: u64,
+}
+
+impl DetailCache {
+    fn new(budget: usize) -> Self {
+        return Self {
+            budget,
+            used: 0,
+            entries: HashMap::new(),
+            clock: 0,
+        };
+    }
+
+    fn get(&mut self, key: &DetailKey) -> Option<Detail> {
+        self.clock += 1;
+        let (detail, last_used) = self.entries.get_mut(key)?;
+        *last_used = self.clock;
+
+        return Some(detail.clone());
+    }
+
+    fn contains(&self, key: &DetailKey) -> bool {
+        return self.entries.contains_key(key);
+    }
+
+    /// Cache a detail, unless it alone is over the budget
+    fn insert(&mut self, key: DetailKey, detail: Detail) {
+        let size: usize = detail.size();
+        if size > self.budget {
+            return;
+        }
+        if let Some((old, _)) = self.entries.remove(&key) {
+            self.used -= old.size();
+        }
+
+        while self.used + size > self.budget {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            if let Some((evicted, _)) = self.entries.remove(&oldest) {
+                self.used -= evicted.size();
+            }
+        }
+
+        self.clock += 1;
+        self.used += size;
+        self.entries.insert(key, (detail, self.clock));
+    }
+}
+
+/// Cached details and the fetches in flight
+struct PrefetchState {
+    cache: DetailCache,
+    /// Fetch tasks by detail, with the number each was started as
+    tasks: HashMap<DetailKey, (u64, JoinHandle<()>)>,
+    /// Number given to the next task
+    next_task: u64,
+}
+
+/// Fetches the articles and comments of the highlighted and following stories in the
+/// background, so opening them does not wait on the network
+///
+/// Fetches for stories that leave the window when the highlight moves are cancelled,
+/// and fetched details are kept within a memory budget.
+pub struct Prefetcher<G: HackerNewsGateway> {
+    gateway: Arc<G>,
+    stories: usize,
+    state: Arc<Mutex<PrefetchState>>,
+}
+
+impl<G: HackerNewsGateway + 'static> Prefetcher<G> {
+    pub fn new(gateway: Arc<G>, config: PrefetchConfig) -> Self {
+        return Self {
+            gateway,
+            stories: config.stories,
+            state: Arc::new(Mutex::new(PrefetchState {
+                cache: DetailCache::new(config.max_memory_mb.saturating_mul(1024 * 1024)),
+                tasks: HashMap::new(),
+                next_task: 0,
+            })),
+        };
+    }
+
+    /// Prefetch the details of the story at `index` and the ones after it, cancelling
+    /// fetches for stories outside that window
+    pub fn prefetch_from(&self, stories: &[StoryItem], index: usize) {
+        let window: Vec<DetailKey> = stories
+            .iter()
+            .skip(index)
+            .take(self.stories)
+            .flat_map(|story| {
+                let article = story.url.clone().map(DetailKey::Article);
+                let comments = (!story.kids.is_empty()).then_some(DetailKey::Comments(story.id));
+                return article.into_iter().chain(comments);
+            })
+            .collect();
+        let wanted: HashSet<&DetailKey> = window.iter().collect();
+
+        let mut state = self.state.lock().unwrap();
+        state.tasks.retain(|key, (_, task)| {
+            let keep: bool = wanted.contains(key) && !task.is_finished();
+            if !keep {
+                task.abort();
+            }
+            return keep;
+        });
+
+        for key in window {
+            if state.cache.contains(&key) || state.tasks.contains_key(&key) {
+                continue;
+            }
+            let Some(story) = stories.iter().find(|story| match &key {
+                DetailKey::Article(url) => story.url.as_ref() == Some(url),
+                DetailKey::Comments(id) => story.id == *id,
+            }) else {
+                continue;
+            };
+
+            let number: u64 = state.next_task;
+            state.next_task += 1;
+            let task: JoinHandle<()> = tokio::spawn(fetch_detail(
+                Arc::clone(&self.gateway),
+                Arc::clone(&self.state),
+                key.clone(),
+                story.kids.iter().copied().take(COMMENTS_LIMIT).collect(),
+                number,
+            ));
+            state.tasks.insert(key, (number, task));
+        }
+    }
+
+    /// Prefetched HTML of an article
+    pub fn article(&self, url: &str) -> Option<String> {
+        return match self
+            .state
+            .lock()
+            .unwrap()
+            .cache
+            .get(&DetailKey::Article(url.to_string()))
+        {
+            Some(Detail::Article(html)) => Some(html),
+            _ => None,
+        };
+    }
+
+    /// Prefetched top-level comments of a story
+    pub fn comments(&self, id: u64) -> Option<Vec<StoryItem>> {
+        return match self
+            .state
+            .lock()
+            .unwrap()
+            .cache
+            .get(&DetailKey::Comments(id))
+        {
+            Some(Detail::Comments(comments)) => Some(comments),
+            _ => None,
+        };
+    }
+}
+
+impl<G: HackerNewsGateway> Drop for Prefetcher<G> {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        for (_, (_, task)) in state.tasks.drain() {
+            task.abort();
+        }
+    }
+}
+
+/// Fetch a detail and cache it, leaving nothing cached when the fetch fails
+async fn fetch_detail<G: HackerNewsGateway>(
+    gateway: Arc<G>,
+    state: Arc<Mutex<PrefetchState>>,
+    key: DetailKey,
+    comment_ids: Vec<u64>,
+    number: u64,
+) {
+    let result = match &key {
+        DetailKey::Article(url) => gateway.fetch_article(url).await.map(Detail::Article),
+        DetailKey::Comments(_) => gateway
+            .fetch_items(&comment_ids, COMMENTS_CONCURRENCY)
+            .await
+            .map(Detail::Comments),
+    };
+
+    let mut state = state.lock().unwrap();
+    match result {
+        Ok(detail) => state.cache.insert(key.clone(), detail),
+        // Opening the story fetches it again and reports the error then
+        Err(error) => log::debug!("Prefetching {key:?} failed: {error}"),
+    }
+    // A newer fetch of the same detail may have replaced this one
+    if state
+        .tasks
+        .get(&key)
+        .is_some_and(|(current, _)| *current == number)
+    {
+        state.tasks.remove(&key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::sync::oneshot;
+
+    use crate::api::MockHackerNewsGateway;
+
+    fn story(id: u64, url: &str, kids: Vec<u64>) -> StoryItem {
+        return StoryItem {
+            id,
+            url: Some(url.to_string()),
+            kids,
+            ..Default::default()
+        };
+    }
+
+    fn config(stories: usize) -> PrefetchConfig {
+        return PrefetchConfig {
+            stories,
+            ..Default::default()
+        };
+    }
+
+    /// Wait for the fetches in flight to finish
+    async fn finish_fetches<G: HackerNewsGateway>(prefetcher: &Prefetcher<G>) {
+        let tasks: Vec<JoinHandle<()>> = prefetcher
+            .state
+            .lock()
+            .unwrap()
+            .tasks
+            .drain()
+            .map(|(_, (_, task))| task)
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_window_from_highlighted_story() {
+        let mut gateway = MockHackerNewsGateway::new();
+        gateway
+            .expect_fetch_article()
+            .times(2)
+            .returning(|url| Ok(format!("<p>{url}</p>")));
+        gateway.expect_fetch_items().times(1).returning(|ids, _| {
+            return Ok(ids
+                .iter()
+                .map(|id| StoryItem {
+                    id: *id,
+                    text: Some(format!("Comment {id}")),
+                    ..Default::default()
+                })
+                .collect());
+        });
+        let prefetcher = Prefetcher::new(Arc::new(gateway), config(2));
+        let stories: Vec<StoryItem> = vec![
+            story(1, "https://example.com/1", vec![11]),
+            story(2, "https://example.com/2", vec![21, 22]),
+            story(3, "https://example.com/3", Vec::new()),
+            story(4, "https://example.com/4", vec![41]),
+        ];
+
+        prefetcher.prefetch_from(&stories, 1);
+        finish_fetches(&prefetcher).await;
+
+        assert_eq!(prefetcher.article("https://example.com/1"), None);
+        assert_eq!(
+            prefetcher.article("https://example.com/2").as_deref(),
+            Some("<p>https://example.com/2</p>")
+        );
+        assert!(prefetcher.article("https://example.com/3").is_some());
+        assert_eq!(prefetcher.article("https://example.com/4"), None);
+        let comments: Vec<u64> = prefetcher
+            .comments(2)
+            .unwrap()
+            .iter()
+            .map(|comment| comment.id)
+            .collect();
+        assert_eq!(comments, vec![21, 22]);
+        assert!(prefetcher.comments(3).is_none());
+
+        // Details already cached are not fetched again
+        prefetcher.prefetch_from(&stories, 1);
+        assert!(prefetcher.state.lock().unwrap().tasks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_cancels_when_selection_moves() {
+        let mut gateway = MockHackerNewsGateway::new();
+        gateway
+            .expect_fetch_article()
+            .withf(|url| url == "https://example.com/2")
+            .times(1)
+            .returning(|url| Ok(format!("<p>{url}</p>")));
+        let prefetcher = Prefetcher::new(Arc::new(gateway), config(1));
+        let stories: Vec<StoryItem> = vec![
+            story(1, "https://example.com/slow", Vec::new()),
+            story(2, "https://example.com/2", Vec::new()),
+        ];
+
+        // An article that never finishes loading is in flight for the highlighted story,
+        // dropping the sender once it is cancelled
+        let (cancelled_sender, cancelled) = oneshot::channel::<()>();
+        let slow_task: JoinHandle<()> = tokio::spawn(async move {
+            let _cancelled_sender = cancelled_sender;
+            std::future::pending::<()>().await;
+        });
+        let slow_key: DetailKey = DetailKey::Article("https://example.com/slow".to_string());
+        prefetcher
+            .state
+            .lock()
+            .unwrap()
+            .tasks
+            .insert(slow_key.clone(), (0, slow_task));
+
+        // Still wanted, so it is neither cancelled nor fetched again
+        prefetcher.prefetch_from(&stories, 0);
+        assert!(
+            prefetcher
+                .state
+                .lock()
+                .unwrap()
+                .tasks
+                .contains_key(&slow_key)
+        );
+
+        prefetcher.prefetch_from(&stories, 1);
+        finish_fetches(&prefetcher).await;
+
+        assert!(cancelled.await.is_err());
+        assert!(prefetcher.article("https://example.com/2").is_some());
+    }
+
+    #[test]
+    fn test_detail_cache_evicts_least_recently_used() {
+        let mut cache: DetailCache = DetailCache::new(10);
+        let article = |id: u64| DetailKey::Article(id.to_string());
+
+        cache.insert(article(1), Detail::Article("aaaa".to_string()));
+        cache.insert(article(2), Detail::Article("bbbb".to_string()));
+        assert!(cache.get(&article(1)).is_some());
+        cache.insert(article(3), Detail::Article("cccc".to_string()));
+
+        assert!(cache.contains(&article(1)));
+        assert!(!cache.contains(&article(2)));
+        assert!(cache.contains(&article(3)));
+        assert_eq!(cache.used, 8);
+
+        // A detail over the whole budget is never cached
+        cache.insert(article(4), Detail::Article("x".repeat(11)));
+        assert!(!cache.contains(&article(4)));
+        assert_eq!(cache.used, 8);
+    }
+}
@@ -0,0 +1,48 @@
+// Status message macros styled with the current theme, used instead of bare `println!`
+
+/// Print a success message
+#[macro_export]
+macro_rules! print_success {
+    ($($arg:tt)*) => {
+        println!(
+            "{}",
+            $crate::ui::theme::current().paint($crate::ui::theme::ThemeRole::Success, format!($($arg)*))
+        )
+    };
+}
+
+/// Print an info message
+#[macro_export]
+macro_rules! print_info {
+    ($($arg:tt)*) => {
+        println!(
+            "{}",
+            $crate::ui::theme::current().paint($crate::ui::theme::ThemeRole::Info, format!($($arg)*))
+        )
+    };
+}
+
+/// Print a warning message
+#[macro_export]
+macro_rules! print_warn {
+    ($($arg:tt)*) => {
+        println!(
+            "{}",
+            $crate::ui::theme::current().paint($crate::ui::theme::ThemeRole::Warning, format!($($arg)*))
+        )
+    };
+}
+
+/// Print an error message to stderr
+#[macro_export]
+macro_rules! print_error {
+    ($($arg:tt)*) => {
+        eprintln!(
+            "{}",
+            $crate::ui::theme::current()
+                .style($crate::ui::theme::ThemeRole::Error)
+                .for_stderr()
+                .apply_to(format!($($arg)*))
+        )
+    };
+}
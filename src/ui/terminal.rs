@@ -1,92 +1,719 @@
+use std::fs;
+use std::sync::{Arc, OnceLock};
+
 use async_trait::async_trait;
-use chroma_print::print_info;
-use dialoguer::{Select, theme::ColorfulTheme};
+use chrono::{DateTime, Utc};
+use console::Term;
+use dialoguer::{Confirm, Editor, Input, Select};
 
 use crate::{
-    models::story::{HackerNewsStoryType, StoryAction, StoryItem},
+    api::{AccountGateway, HackerNewsGateway},
+    bookmarks::BookmarkStore,
+    digest::{DigestContext, DigestFormat, render_digest},
+    models::{
+        item::ItemThread,
+        story::{BatchAction, StoryAction, StoryItem},
+    },
+    mutes::{MuteKind, MuteRules, MuteStore},
+    print_error, print_info, print_success, print_warn,
+    reader::{extract::extract_article, render::render_article},
     types::HnResult,
-    ui::UserInterface,
-    utils::constants::Y_COMBINATOR_BASE_URL,
+    ui::{
+        UserInterface,
+        fuzzy::filter_stories,
+        layout::{StoryRow, layout_story_rows},
+        list::{self, ListChoice, ListCursor, ListRow},
+        opener::LinkOpener,
+        pager,
+        prefetch::{COMMENTS_CONCURRENCY, COMMENTS_LIMIT, PrefetchConfig, Prefetcher},
+        theme::{self, ThemeRole},
+        thread::{format_comments, format_thread},
+    },
+    utils::{
+        constants::Y_COMBINATOR_BASE_URL,
+        helpers::{clear_loading, show_loading},
+        time::TimeFormatter,
+    },
 };
 
-pub struct TerminalUserInterface;
+/// Number of stories listed in a site's view
+const DOMAIN_STORIES_LIMIT: u16 = 30;
 
-#[async_trait]
-impl UserInterface for TerminalUserInterface {
-    async fn render_stories_list(
+/// Document formats marked stories can be exported to
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SelectionFormat {
+    Json,
+    Markdown,
+}
+
+impl SelectionFormat {
+    fn extension(self) -> &'static str {
+        return match self {
+            SelectionFormat::Json => "json",
+            SelectionFormat::Markdown => "md",
+        };
+    }
+
+    /// Render the stories as a JSON array or a Markdown digest
+    fn render(
+        self,
+        stories: &[StoryItem],
+        list_name: &str,
+        generated_at: DateTime<Utc>,
+    ) -> HnResult<String> {
+        return match self {
+            SelectionFormat::Json => Ok(serde_json::to_string_pretty(stories)?),
+            SelectionFormat::Markdown => {
+                let title: String = format!("Hacker News {list_name} stories");
+                let context: DigestContext =
+                    DigestContext::new(title, list_name, generated_at, stories);
+                render_digest(DigestFormat::Markdown, None, &context)
+            }
+        };
+    }
+}
+
+/// Loads the logged-in account, or `None` when logged out
+type AccountLoader = Box<dyn Fn() -> HnResult<Option<Box<dyn AccountGateway>>> + Send + Sync>;
+
+/// Interactive terminal user interface
+pub struct TerminalUserInterface<G: HackerNewsGateway> {
+    gateway: Arc<G>,
+    opener: LinkOpener,
+    time: TimeFormatter,
+    /// Logged-in account, which enables voting, favoriting, hiding, flagging and replying.
+    /// Loaded on first use, so commands that never show a menu don't read the session
+    account: OnceLock<Option<Box<dyn AccountGateway>>>,
+    account_loader: Option<AccountLoader>,
+    /// Rules for the stories left out of every list
+    mutes: Option<MuteStore>,
+    /// Loads the details of the highlighted stories in the background
+    prefetcher: Prefetcher<G>,
+}
+
+impl<G: HackerNewsGateway + 'static> TerminalUserInterface<G> {
+    pub fn new(gateway: G, opener: LinkOpener, time: TimeFormatter) -> Self {
+        let gateway: Arc<G> = Arc::new(gateway);
+        return Self {
+            prefetcher: Prefetcher::new(Arc::clone(&gateway), PrefetchConfig::default()),
+            gateway,
+            opener,
+            time,
+            account: OnceLock::new(),
+            account_loader: None,
+            mutes: None,
+        };
+    }
+
+    /// Prefetch story details with other settings than the defaults
+    pub fn with_prefetch(mut self, config: PrefetchConfig) -> Self {
+        self.prefetcher = Prefetcher::new(Arc::clone(&self.gateway), config);
+        return self;
+    }
+
+    /// Offer to mute stories, dropping them from the list once muted
+    pub fn with_mutes(mut self, mutes: MuteStore) -> Self {
+        self.mutes = Some(mutes);
+        return self;
+    }
+
+    /// Offer the logged-in actions of the account returned by `load`, if any, on stories
+    pub fn with_account<A, F>(mut self, load: F) -> Self
+    where
+        A: AccountGateway + 'static,
+        F: Fn() -> HnResult<Option<A>> + Send + Sync + 'static,
+    {
+        self.account_loader = Some(Box::new(move || {
+            return Ok(load()?.map(|account| Box::new(account) as Box<dyn AccountGateway>));
+        }));
+        return self;
+    }
+
+    /// The logged-in account, loading it on first use. An account that cannot be loaded
+    /// is reported once and the UI carries on without it
+    fn account(&self) -> Option<&dyn AccountGateway> {
+        let account = self.account.get_or_init(|| {
+            let load: &AccountLoader = self.account_loader.as_ref()?;
+            return load().unwrap_or_else(|error| {
+                print_warn!("Account actions disabled: {error}");
+                None
+            });
+        });
+        return account.as_deref();
+    }
+
+    /// Open a link, reporting failures without leaving the UI
+    fn open_link(&self, url: &str) {
+        if let Err(error) = self.opener.open(url) {
+            print_error!("Unable to open link: {error}");
+        }
+    }
+
+    /// Fetch a story's linked article, unless it was prefetched, and show it in the pager
+    async fn read_article(&self, url: &str) -> HnResult<()> {
+        let html: String = match self.prefetcher.article(url) {
+            Some(html) => html,
+            None => {
+                show_loading(Some("Fetching article...please wait"));
+                let result: HnResult<String> = self.gateway.fetch_article(url).await;
+                clear_loading();
+                result?
+            }
+        };
+
+        let article = extract_article(&html, url)?;
+        let (_, width) = Term::stdout().size();
+
+        return pager::page(&render_article(&article, usize::from(width)));
+    }
+
+    /// Fetch a story's top-level comments, unless they were prefetched, and show them
+    /// in the pager
+    async fn read_comments(&self, story: &StoryItem) -> HnResult<()> {
+        let comments: Vec<StoryItem> = match self.prefetcher.comments(story.id) {
+            Some(comments) => comments,
+            None => {
+                let ids: Vec<u64> = story.kids.iter().copied().take(COMMENTS_LIMIT).collect();
+                show_loading(Some("Fetching comments...please wait"));
+                let result: HnResult<Vec<StoryItem>> =
+                    self.gateway.fetch_items(&ids, COMMENTS_CONCURRENCY).await;
+                clear_loading();
+                result?
+            }
+        };
+        let (_, width) = Term::stdout().size();
+
+        return pager::page(&format_comments(
+            story,
+            &comments,
+            usize::from(width),
+            &self.time,
+            theme::current(),
+        ));
+    }
+
+    /// Search for recent stories from a site and list them
+    async fn view_domain(&self, domain: &str) -> HnResult<()> {
+        show_loading(Some(&format!(
+            "Searching stories from {domain}...please wait"
+        )));
+        let result: HnResult<Vec<StoryItem>> = self
+            .gateway
+            .fetch_domain_stories(domain, DOMAIN_STORIES_LIMIT)
+            .await;
+        clear_loading();
+
+        let stories: Vec<StoryItem> = result?;
+        if stories.is_empty() {
+            print_warn!("No stories found from {domain}");
+            return Ok(());
+        }
+
+        return self.render_stories_list(stories, domain).await;
+    }
+
+    /// Save stories to the bookmarks, reporting the outcome without leaving the UI
+    fn bookmark(&self, stories: &[StoryItem]) {
+        let result: HnResult<usize> =
+            BookmarkStore::open_default().and_then(|store| store.add(stories, Utc::now()));
+
+        match result {
+            Ok(0) => print_info!("Already bookmarked"),
+            Ok(1) => print_success!("Bookmarked 1 story"),
+            Ok(added) => print_success!("Bookmarked {added} stories"),
+            Err(error) => print_error!("Unable to bookmark: {error}"),
+        }
+    }
+
+    /// Ask for a file name and write the stories to it
+    fn export_selection(
         &self,
-        stories: Vec<StoryItem>,
-        story_type: &HackerNewsStoryType,
+        stories: &[StoryItem],
+        list_name: &str,
+        format: SelectionFormat,
     ) -> HnResult<()> {
-        // Format story items for list
-        let formatted_story_items: Vec<String> = stories
-            .iter()
-            .enumerate()
-            .map(|(index, story)| story.format_for_list(index + 1))
-            .collect();
+        let path: String = Input::with_theme(&theme::current().dialoguer())
+            .with_prompt("Export to file")
+            .default(format!("hn-stories.{}", format.extension()))
+            .interact_text()?;
 
-        // Story selection list loop
-        loop {
-            print_info!("Press Ctrl+C or Esc to exit.");
-            let selection = Select::with_theme(&ColorfulTheme::default())
-                .with_prompt(format!(
-                    "Select a '{}' story item to view more",
-                    story_type.full_name()
-                ))
-                .items(&formatted_story_items)
-                .default(0)
-                .interact_opt()?;
+        fs::write(&path, format.render(stories, list_name, Utc::now())?)?;
+        print_success!("Exported {} stories to {path}", stories.len());
 
-            let index: usize = match selection {
-                Some(i) => i,
-                None => break, // User pressed Ctrl+C or Esc...exit
-            };
+        return Ok(());
+    }
 
-            let selected_story: &StoryItem = &stories[index];
+    /// The saved mute rules, or none without a mute store or when they cannot be read
+    fn mute_rules(&self) -> MuteRules {
+        let Some(mutes) = &self.mutes else {
+            return MuteRules::default();
+        };
 
-            // Selected story/item action loop
-            loop {
-                let mut actions: Vec<StoryAction> = Vec::new();
+        return mutes.load().unwrap_or_else(|error| {
+            log::warn!("Showing muted stories: {error}");
+            MuteRules::default()
+        });
+    }
 
-                if let Some(url) = &selected_story.url {
-                    actions.push(StoryAction::OpenUrl(url.clone()));
-                }
+    /// Add a mute rule, reporting the outcome without leaving the UI
+    fn mute(&self, kind: MuteKind, value: &str) {
+        let Some(mutes) = &self.mutes else {
+            return;
+        };
 
-                actions.push(StoryAction::ViewStoryItem(selected_story.id));
+        match mutes.add(kind, value) {
+            Ok(true) if kind == MuteKind::Id => print_success!("Story hidden"),
+            Ok(true) => print_success!("Muted {kind} {value}"),
+            Ok(false) => print_info!("Already muted"),
+            Err(error) => print_error!("Unable to mute: {error}"),
+        }
+    }
+
+    /// Run a logged-in action, asking for confirmation or the reply text first
+    async fn account_action(&self, action: &StoryAction) -> HnResult<()> {
+        let account: &dyn AccountGateway = self.account().ok_or("Not logged in")?;
 
-                if let Some(by) = &selected_story.by {
-                    actions.push(StoryAction::ViewAuthor(by.clone()));
+        match action {
+            StoryAction::Upvote(id) => {
+                account.upvote(*id).await?;
+                print_success!("Upvoted");
+            }
+            StoryAction::Favorite(id) => {
+                account.favorite(*id).await?;
+                print_success!("Added to favorites");
+            }
+            StoryAction::Hide(id) => {
+                account.hide(*id).await?;
+                print_success!("Hidden on Hacker News");
+            }
+            StoryAction::Flag(id) => {
+                let confirmed: bool = Confirm::with_theme(&theme::current().dialoguer())
+                    .with_prompt("Flag this story for the moderators?")
+                    .default(false)
+                    .interact()?;
+                if confirmed {
+                    account.flag(*id).await?;
+                    print_success!("Flagged");
+                }
+            }
+            StoryAction::Reply(id) => {
+                // Quitting the editor without saving, or saving nothing, cancels the reply
+                let text: String = Editor::new()
+                    .extension(".txt")
+                    .edit("")?
+                    .unwrap_or_default();
+                if text.trim().is_empty() {
+                    print_info!("Reply cancelled");
+                } else {
+                    account.reply(*id, text.trim()).await?;
+                    print_success!("Reply posted");
                 }
+            }
+            _ => {}
+        }
 
-                actions.push(StoryAction::Back);
+        return Ok(());
+    }
 
-                // Show list of actions for selected story/item
-                let action_index = Select::with_theme(&ColorfulTheme::default())
-                    .with_prompt(selected_story.format_for_details_header())
-                    .items(&actions)
-                    .default(0)
-                    .interact()?;
+    /// Actions offered for a story
+    fn story_menu(&self, story: &StoryItem) -> Vec<StoryAction> {
+        let mut actions: Vec<StoryAction> = Vec::new();
+
+        if let Some(url) = &story.url {
+            actions.push(StoryAction::OpenUrl(url.clone()));
+            actions.push(StoryAction::ReadArticle(url.clone()));
+        }
+
+        if !story.kids.is_empty() {
+            actions.push(StoryAction::ReadComments);
+        }
+
+        actions.push(StoryAction::ViewStoryItem(story.id));
+
+        if let Some(by) = &story.by {
+            actions.push(StoryAction::ViewAuthor(by.clone()));
+        }
+
+        if let Some(domain) = story.domain() {
+            actions.push(StoryAction::ViewDomain(domain));
+        }
+
+        actions.push(StoryAction::Bookmark);
+
+        if self.mutes.is_some() {
+            actions.push(StoryAction::HideStory(story.id));
+            if let Some(domain) = story.domain() {
+                actions.push(StoryAction::MuteDomain(domain));
+            }
+            if let Some(by) = &story.by {
+                actions.push(StoryAction::MuteAuthor(by.clone()));
+            }
+        }
+
+        if self.account().is_some() {
+            actions.push(StoryAction::Upvote(story.id));
+            actions.push(StoryAction::Favorite(story.id));
+            actions.push(StoryAction::Hide(story.id));
+            actions.push(StoryAction::Flag(story.id));
+            actions.push(StoryAction::Reply(story.id));
+        }
+
+        actions.push(StoryAction::Back);
+
+        return actions;
+    }
 
-                // Handle action
-                match &actions[action_index] {
-                    StoryAction::OpenUrl(url) => {
-                        let _ = webbrowser::open(url);
+    /// Actions offered for a comment: its replies, page and author, and the account
+    /// actions Hacker News allows on comments
+    fn comment_menu(&self, comment: &StoryItem) -> Vec<StoryAction> {
+        let mut actions: Vec<StoryAction> = Vec::new();
+
+        if !comment.kids.is_empty() {
+            actions.push(StoryAction::ReadComments);
+        }
+
+        actions.push(StoryAction::ViewStoryItem(comment.id));
+
+        if let Some(by) = &comment.by {
+            actions.push(StoryAction::ViewAuthor(by.clone()));
+        }
+
+        if self.account().is_some() {
+            actions.push(StoryAction::Upvote(comment.id));
+            actions.push(StoryAction::Favorite(comment.id));
+            actions.push(StoryAction::Flag(comment.id));
+            actions.push(StoryAction::Reply(comment.id));
+        }
+
+        actions.push(StoryAction::Back);
+
+        return actions;
+    }
+
+    /// Show the actions for a single story until the user goes back
+    async fn story_actions(&self, story: &StoryItem) -> HnResult<()> {
+        return self.item_actions(story, Self::story_menu).await;
+    }
+
+    /// Show the actions from `menu` for a story or comment until the user goes back
+    async fn item_actions(
+        &self,
+        item: &StoryItem,
+        menu: fn(&Self, &StoryItem) -> Vec<StoryAction>,
+    ) -> HnResult<()> {
+        loop {
+            let actions: Vec<StoryAction> = menu(self, item);
+
+            // Show list of actions for selected story/item
+            let header = theme::current()
+                .style(ThemeRole::Accent)
+                .for_stderr()
+                .apply_to(item.format_for_details_header(&self.time));
+            let action_index = Select::with_theme(&theme::current().dialoguer())
+                .with_prompt(header.to_string())
+                .items(&actions)
+                .default(0)
+                .interact()?;
+
+            // Handle action
+            match &actions[action_index] {
+                StoryAction::OpenUrl(url) => self.open_link(url),
+                StoryAction::ReadArticle(url) => {
+                    if let Err(error) = self.read_article(url).await {
+                        print_error!("Unable to read article: {error}");
                     }
-                    StoryAction::ViewAuthor(user) => {
-                        let _ = webbrowser::open(&format!(
-                            "{}/user?id={}",
-                            Y_COMBINATOR_BASE_URL, user
-                        ));
+                }
+                StoryAction::ReadComments => {
+                    if let Err(error) = self.read_comments(item).await {
+                        print_error!("Unable to read comments: {error}");
+                    }
+                }
+                StoryAction::ViewAuthor(user) => {
+                    self.open_link(&format!("{}/user?id={}", Y_COMBINATOR_BASE_URL, user));
+                }
+                StoryAction::ViewStoryItem(id) => {
+                    self.open_link(&format!("{}/item?id={}", Y_COMBINATOR_BASE_URL, id));
+                }
+                StoryAction::ViewDomain(domain) => {
+                    if let Err(error) = self.view_domain(domain).await {
+                        print_error!("Unable to list stories from {domain}: {error}");
                     }
-                    StoryAction::ViewStoryItem(id) => {
-                        let _ =
-                            webbrowser::open(&format!("{}/item?id={}", Y_COMBINATOR_BASE_URL, id));
+                }
+                StoryAction::Bookmark => self.bookmark(std::slice::from_ref(item)),
+                // The story leaves the list, so go back to it
+                StoryAction::HideStory(id) => {
+                    self.mute(MuteKind::Id, &id.to_string());
+                    return Ok(());
+                }
+                StoryAction::MuteDomain(domain) => {
+                    self.mute(MuteKind::Domain, domain);
+                    return Ok(());
+                }
+                StoryAction::MuteAuthor(author) => {
+                    self.mute(MuteKind::Author, author);
+                    return Ok(());
+                }
+                StoryAction::Upvote(_)
+                | StoryAction::Favorite(_)
+                | StoryAction::Hide(_)
+                | StoryAction::Flag(_)
+                | StoryAction::Reply(_) => {
+                    if let Err(error) = self.account_action(&actions[action_index]).await {
+                        print_error!("{error}");
                     }
-                    StoryAction::Back => break,
                 }
+                StoryAction::Back => return Ok(()),
+            }
+        }
+    }
+
+    /// Show the actions for the marked stories until the user goes back
+    ///
+    /// Unmarking the stories also clears the marks in `cursor`.
+    fn batch_actions(
+        &self,
+        stories: &[StoryItem],
+        list_name: &str,
+        cursor: &mut ListCursor,
+    ) -> HnResult<()> {
+        let urls: Vec<&str> = stories
+            .iter()
+            .filter_map(|story| story.url.as_deref())
+            .collect();
+
+        loop {
+            let mut actions: Vec<BatchAction> = Vec::new();
+
+            // Text posts have no URL of their own
+            if !urls.is_empty() {
+                actions.push(BatchAction::OpenUrls(urls.len()));
+            }
+
+            actions.push(BatchAction::OpenDiscussions(stories.len()));
+            actions.push(BatchAction::Bookmark(stories.len()));
+            actions.push(BatchAction::ExportJson);
+            actions.push(BatchAction::ExportMarkdown);
+            actions.push(BatchAction::ClearMarks);
+            actions.push(BatchAction::Back);
+
+            let header = theme::current()
+                .style(ThemeRole::Accent)
+                .for_stderr()
+                .apply_to(format!("{} marked stories", stories.len()));
+            let action_index = Select::with_theme(&theme::current().dialoguer())
+                .with_prompt(header.to_string())
+                .items(&actions)
+                .default(0)
+                .interact()?;
+
+            match &actions[action_index] {
+                BatchAction::OpenUrls(_) => urls.iter().for_each(|url| self.open_link(url)),
+                BatchAction::OpenDiscussions(_) => stories
+                    .iter()
+                    .for_each(|story| self.open_link(&story.discussion_url())),
+                BatchAction::Bookmark(_) => self.bookmark(stories),
+                BatchAction::ExportJson => {
+                    if let Err(error) =
+                        self.export_selection(stories, list_name, SelectionFormat::Json)
+                    {
+                        print_error!("Unable to export stories: {error}");
+                    }
+                }
+                BatchAction::ExportMarkdown => {
+                    if let Err(error) =
+                        self.export_selection(stories, list_name, SelectionFormat::Markdown)
+                    {
+                        print_error!("Unable to export stories: {error}");
+                    }
+                }
+                BatchAction::ClearMarks => {
+                    cursor.marked.clear();
+                    return Ok(());
+                }
+                BatchAction::Back => return Ok(()),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<G: HackerNewsGateway + 'static> UserInterface for TerminalUserInterface<G> {
+    async fn render_stories_list(
+        &self,
+        mut stories: Vec<StoryItem>,
+        list_name: &str,
+    ) -> HnResult<()> {
+        let prompt: String = format!("Select a '{}' story item to view more", list_name);
+        let mut cursor: ListCursor = ListCursor::default();
+
+        // Story lists come without muted stories, lists kept locally (e.g. bookmarks) keep them
+        let muted_before: MuteRules = self.mute_rules();
+
+        // Story selection list loop
+        loop {
+            // Rows are laid out again when the filter changes or the terminal is resized.
+            // Filtered rows keep their rank and map back to the story's list index.
+            // The list blocks on terminal input, so let prefetches run on other threads
+            let choice: Option<ListChoice> = tokio::task::block_in_place(|| {
+                return list::select(
+                    &prompt,
+                    &mut cursor,
+                    |width, query| {
+                        let rows: Vec<StoryRow> = layout_story_rows(&stories, width, &self.time);
+                        return filter_stories(&stories, query)
+                            .into_iter()
+                            .map(|story_match| {
+                                let row: &StoryRow = &rows[story_match.index];
+                                return ListRow {
+                                    index: story_match.index,
+                                    text: row.text.clone(),
+                                    highlights: story_match.row_highlights(row),
+                                };
+                            })
+                            .collect();
+                    },
+                    |index| self.prefetcher.prefetch_from(&stories, index),
+                );
+            })?;
+
+            match choice {
+                Some(ListChoice::One(index)) => self.story_actions(&stories[index]).await?,
+                Some(ListChoice::Marked(indexes)) => {
+                    let marked: Vec<StoryItem> = indexes
+                        .iter()
+                        .map(|index| stories[*index].clone())
+                        .collect();
+                    self.batch_actions(&marked, list_name, &mut cursor)?;
+                }
+                None => break, // User pressed Ctrl+C or Esc...exit
+            }
+
+            // Drop stories muted in the menus, staying on the selected story if it is left
+            let selected_id: Option<u64> = stories.get(cursor.index).map(|story| story.id);
+            let count: usize = stories.len();
+            let rules: MuteRules = self.mute_rules();
+            stories.retain(|story| !rules.matches(story) || muted_before.matches(story));
+            if stories.len() != count {
+                cursor.marked.clear();
+                cursor.index = stories
+                    .iter()
+                    .position(|story| Some(story.id) == selected_id)
+                    .unwrap_or(cursor.index.min(stories.len().saturating_sub(1)));
+            }
+            if stories.is_empty() {
+                break;
             }
         }
 
         return Ok(());
     }
+
+    async fn render_thread(&self, thread: ItemThread) -> HnResult<()> {
+        let (_, width) = Term::stdout().size();
+        println!(
+            "{}\n",
+            format_thread(&thread, usize::from(width), &self.time, theme::current())
+        );
+
+        // A looked-up comment's actions act on the comment, not on its story
+        return match &thread.comment {
+            Some(comment) => self.item_actions(comment, Self::comment_menu).await,
+            None => self.story_actions(&thread.story).await,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_selection_format_render() {
+        let stories: Vec<StoryItem> = vec![StoryItem {
+            id: 7,
+            title: Some("Marked story".to_string()),
+            url: Some("https://example.com/post".to_string()),
+            ..Default::default()
+        }];
+        let generated_at: DateTime<Utc> = Utc.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap();
+
+        let json: String = SelectionFormat::Json
+            .render(&stories, "Top", generated_at)
+            .unwrap();
+        let parsed: Vec<StoryItem> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0].id, 7);
+
+        let markdown: String = SelectionFormat::Markdown
+            .render(&stories, "Top", generated_at)
+            .unwrap();
+        assert!(markdown.contains("Hacker News Top stories"));
+        assert!(markdown.contains("https://example.com/post"));
+    }
+
+    #[test]
+    fn test_account_loaded_once_on_first_use() {
+        use crate::api::{MockAccountGateway, MockHackerNewsGateway};
+        use crate::ui::opener::OpenMode;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let loads: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+        let counted: Arc<AtomicUsize> = Arc::clone(&loads);
+        let ui = TerminalUserInterface::new(
+            MockHackerNewsGateway::new(),
+            LinkOpener::new(OpenMode::Print, None),
+            TimeFormatter::default(),
+        )
+        .with_account(move || -> HnResult<Option<MockAccountGateway>> {
+            counted.fetch_add(1, Ordering::SeqCst);
+            return Err("Corrupt session".into());
+        });
+        assert_eq!(loads.load(Ordering::SeqCst), 0);
+
+        // A session that cannot be read leaves the UI without account actions
+        assert!(ui.account().is_none());
+        assert!(ui.account().is_none());
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_comment_menu_acts_on_the_comment() {
+        use crate::api::{MockAccountGateway, MockHackerNewsGateway};
+        use crate::ui::opener::OpenMode;
+
+        let ui = TerminalUserInterface::new(
+            MockHackerNewsGateway::new(),
+            LinkOpener::new(OpenMode::Print, None),
+            TimeFormatter::default(),
+        )
+        .with_account(|| Ok(Some(MockAccountGateway::new())));
+        let comment: StoryItem = StoryItem {
+            id: 8,
+            by: Some("commenter".to_string()),
+            kids: vec![9],
+            parent: Some(7),
+            ..Default::default()
+        };
+
+        let actions: Vec<StoryAction> = ui.comment_menu(&comment);
+        let ids: Vec<u64> = actions
+            .iter()
+            .filter_map(|action| match action {
+                StoryAction::Upvote(id)
+                | StoryAction::Favorite(id)
+                | StoryAction::Flag(id)
+                | StoryAction::Reply(id) => Some(*id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ids, vec![8, 8, 8, 8]);
+        // Hiding, bookmarking and muting only apply to stories
+        assert!(!actions.iter().any(|action| matches!(
+            action,
+            StoryAction::Hide(_)
+                | StoryAction::HideStory(_)
+                | StoryAction::Bookmark
+                | StoryAction::MuteAuthor(_)
+        )));
+    }
 }
@@ -0,0 +1,924 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, IsTerminal};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use console::{Color, Style, StyledObject};
+use dialoguer::theme::ColorfulTheme;
+use serde::Deserialize;
+
+use crate::types::HnResult;
+
+/// Theme used when none is configured
+pub const DEFAULT_THEME: &str = "dark";
+/// Names of the built-in themes
+pub const BUILTIN_THEMES: [&str; 4] = ["dark", "light", "high-contrast", "monochrome"];
+
+/// Theme shared by the whole UI, set once at startup
+static CURRENT_THEME: OnceLock<Theme> = OnceLock::new();
+
+/// How many colors the terminal can show
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorLevel {
+    /// No escape codes at all (e.g. piped output or `NO_COLOR`)
+    None,
+    /// The 16 standard ANSI colors
+    Ansi16,
+    /// The xterm 256-color palette
+    Ansi256,
+    /// 24-bit RGB colors
+    TrueColor,
+}
+
+impl ColorLevel {
+    /// Detect the color level of an output stream from the environment
+    ///
+    /// `NO_COLOR` disables colors and `CLICOLOR_FORCE` enables them even when the
+    /// output is not a terminal. Otherwise colors are used on terminals, unless
+    /// `CLICOLOR=0` or `TERM=dumb`. `COLORTERM` and `TERM` decide the palette size.
+    pub fn detect<F>(is_terminal: bool, var: F) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let is_set = |name: &str| -> bool {
+            return var(name).is_some_and(|value| !value.is_empty() && value != "0");
+        };
+
+        if var("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return Self::None;
+        }
+
+        let term: String = var("TERM").unwrap_or_default().to_lowercase();
+        if !is_set("CLICOLOR_FORCE")
+            && (!is_terminal || var("CLICOLOR").as_deref() == Some("0") || term == "dumb")
+        {
+            return Self::None;
+        }
+
+        let colorterm: String = var("COLORTERM").unwrap_or_default().to_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" || term.contains("direct") {
+            return Self::TrueColor;
+        }
+        if term.contains("256color") {
+            return Self::Ansi256;
+        }
+
+        return Self::Ansi16;
+    }
+
+    /// Detect the color level of stdout
+    pub fn stdout() -> Self {
+        return Self::detect(io::stdout().is_terminal(), |name| std::env::var(name).ok());
+    }
+
+    /// Detect the color level of stderr
+    pub fn stderr() -> Self {
+        return Self::detect(io::stderr().is_terminal(), |name| std::env::var(name).ok());
+    }
+}
+
+/// One of the eight base ANSI colors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BaseColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl BaseColor {
+    const ALL: [BaseColor; 8] = [
+        BaseColor::Black,
+        BaseColor::Red,
+        BaseColor::Green,
+        BaseColor::Yellow,
+        BaseColor::Blue,
+        BaseColor::Magenta,
+        BaseColor::Cyan,
+        BaseColor::White,
+    ];
+
+    fn name(self) -> &'static str {
+        return match self {
+            BaseColor::Black => "black",
+            BaseColor::Red => "red",
+            BaseColor::Green => "green",
+            BaseColor::Yellow => "yellow",
+            BaseColor::Blue => "blue",
+            BaseColor::Magenta => "magenta",
+            BaseColor::Cyan => "cyan",
+            BaseColor::White => "white",
+        };
+    }
+
+    fn index(self) -> u8 {
+        return Self::ALL
+            .iter()
+            .position(|color| *color == self)
+            .unwrap_or(0) as u8;
+    }
+
+    fn console(self) -> Color {
+        return match self {
+            BaseColor::Black => Color::Black,
+            BaseColor::Red => Color::Red,
+            BaseColor::Green => Color::Green,
+            BaseColor::Yellow => Color::Yellow,
+            BaseColor::Blue => Color::Blue,
+            BaseColor::Magenta => Color::Magenta,
+            BaseColor::Cyan => Color::Cyan,
+            BaseColor::White => Color::White,
+        };
+    }
+}
+
+/// A theme color: a named ANSI color, a 256-color palette index or an RGB color
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThemeColor {
+    Named { color: BaseColor, bright: bool },
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl ThemeColor {
+    const fn named(color: BaseColor) -> Self {
+        return Self::Named {
+            color,
+            bright: false,
+        };
+    }
+
+    const fn bright(color: BaseColor) -> Self {
+        return Self::Named {
+            color,
+            bright: true,
+        };
+    }
+
+    /// The closest color the terminal can show, or `None` without colors
+    ///
+    /// console writes bright colors as 256-color codes, so 16-color terminals
+    /// only get the eight base colors.
+    pub fn to_console(self, level: ColorLevel) -> Option<Color> {
+        return match (self, level) {
+            (_, ColorLevel::None) => None,
+            (Self::Rgb(red, green, blue), ColorLevel::TrueColor) => {
+                Some(Color::TrueColor(red, green, blue))
+            }
+            (Self::Rgb(red, green, blue), ColorLevel::Ansi256) => {
+                Some(Color::Color256(rgb_to_256(red, green, blue)))
+            }
+            (Self::Rgb(red, green, blue), ColorLevel::Ansi16) => {
+                Some(nearest_base_color(red, green, blue).console())
+            }
+            (Self::Indexed(index), ColorLevel::Ansi16) => {
+                let (red, green, blue) = palette_rgb(index);
+                Some(nearest_base_color(red, green, blue).console())
+            }
+            (Self::Indexed(index), _) => Some(Color::Color256(index)),
+            (
+                Self::Named {
+                    color,
+                    bright: true,
+                },
+                ColorLevel::Ansi256 | ColorLevel::TrueColor,
+            ) => Some(Color::Color256(color.index() + 8)),
+            (Self::Named { color, .. }, _) => Some(color.console()),
+        };
+    }
+}
+
+impl FromStr for ThemeColor {
+    type Err = String;
+
+    /// Parse `#rrggbb`, `#rgb`, a palette index (0-255) or a name like `red` or `bright-red`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value: String = value.trim().to_lowercase();
+        let invalid = || -> String {
+            return format!(
+                "Invalid color '{value}', expected #rrggbb, a 0-255 palette index or a name like 'red' or 'bright-red'"
+            );
+        };
+
+        if let Some(hex) = value.strip_prefix('#') {
+            let digits: Vec<u8> = hex
+                .chars()
+                .map(|digit| digit.to_digit(16).map(|digit| digit as u8))
+                .collect::<Option<Vec<u8>>>()
+                .ok_or_else(invalid)?;
+
+            return match digits.as_slice() {
+                [red, green, blue] => Ok(Self::Rgb(red * 17, green * 17, blue * 17)),
+                [r1, r2, g1, g2, b1, b2] => Ok(Self::Rgb(r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2)),
+                _ => Err(invalid()),
+            };
+        }
+
+        if let Ok(index) = value.parse::<u8>() {
+            return Ok(Self::Indexed(index));
+        }
+
+        let (name, bright) = match value
+            .strip_prefix("bright-")
+            .or_else(|| value.strip_prefix("bright_"))
+        {
+            Some(name) => (name, true),
+            None => (value.as_str(), false),
+        };
+
+        return BaseColor::ALL
+            .into_iter()
+            .find(|color| color.name() == name)
+            .map(|color| Self::Named { color, bright })
+            .ok_or_else(invalid);
+    }
+}
+
+impl fmt::Display for ThemeColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Self::Named { color, bright } => {
+                write!(
+                    f,
+                    "{}{}",
+                    if *bright { "bright-" } else { "" },
+                    color.name()
+                )
+            }
+            Self::Indexed(index) => write!(f, "{index}"),
+            Self::Rgb(red, green, blue) => write!(f, "#{red:02x}{green:02x}{blue:02x}"),
+        };
+    }
+}
+
+impl<'de> Deserialize<'de> for ThemeColor {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value: String = String::deserialize(deserializer)?;
+        return value.parse().map_err(serde::de::Error::custom);
+    }
+}
+
+/// Color and text attributes of one theme role
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeStyle {
+    pub color: Option<ThemeColor>,
+    pub bold: bool,
+    pub dim: bool,
+    pub underline: bool,
+}
+
+impl ThemeStyle {
+    const fn plain() -> Self {
+        return Self {
+            color: None,
+            bold: false,
+            dim: false,
+            underline: false,
+        };
+    }
+
+    const fn color(color: ThemeColor) -> Self {
+        return Self {
+            color: Some(color),
+            ..Self::plain()
+        };
+    }
+
+    const fn bold(mut self) -> Self {
+        self.bold = true;
+        return self;
+    }
+
+    const fn dim(mut self) -> Self {
+        self.dim = true;
+        return self;
+    }
+
+    const fn underline(mut self) -> Self {
+        self.underline = true;
+        return self;
+    }
+
+    /// Build the console style for a terminal color level
+    pub fn to_console(self, level: ColorLevel) -> Style {
+        let mut console_style: Style = Style::new();
+
+        if level == ColorLevel::None {
+            return console_style;
+        }
+        if let Some(color) = self.color.and_then(|color| color.to_console(level)) {
+            console_style = console_style.fg(color);
+        }
+        if self.bold {
+            console_style = console_style.bold();
+        }
+        if self.dim {
+            console_style = console_style.dim();
+        }
+        if self.underline {
+            console_style = console_style.underlined();
+        }
+
+        return console_style;
+    }
+}
+
+/// A role's style in the config file: a color (e.g. `"#ff6600"`) or a style table
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum ThemeStyleConfig {
+    Color(ThemeColor),
+    Style(ThemeStyle),
+}
+
+impl ThemeStyleConfig {
+    fn style(self) -> ThemeStyle {
+        return match self {
+            Self::Color(color) => ThemeStyle::color(color),
+            Self::Style(style) => style,
+        };
+    }
+}
+
+/// Parts of the UI a theme styles
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeRole {
+    /// Highlighted text, e.g. the selected story's header
+    Accent,
+    /// Loading messages
+    Loading,
+    Info,
+    Success,
+    Warning,
+    Error,
+    /// The selected row of a list
+    Selected,
+    /// Characters matching a list filter
+    Match,
+    /// List and menu prompts
+    Prompt,
+    /// Hints and secondary text
+    Muted,
+}
+
+/// A user-defined theme from the config file, based on another theme
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// Theme the overrides apply to [default: dark]
+    pub extends: Option<String>,
+    #[serde(flatten)]
+    pub styles: HashMap<ThemeRole, ThemeStyleConfig>,
+}
+
+/// A set of styles for every theme role, resolved for a terminal color level
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    styles: HashMap<ThemeRole, ThemeStyle>,
+    level: ColorLevel,
+}
+
+impl Theme {
+    /// Get a built-in theme by name
+    pub fn builtin(name: &str) -> Option<Self> {
+        use BaseColor::*;
+        use ThemeRole::*;
+
+        let styles: Vec<(ThemeRole, ThemeStyle)> = match name {
+            "dark" => vec![
+                (Accent, ThemeStyle::color(ThemeColor::Rgb(255, 102, 0))),
+                (Loading, ThemeStyle::color(ThemeColor::named(Yellow))),
+                (Info, ThemeStyle::color(ThemeColor::named(Cyan))),
+                (Success, ThemeStyle::color(ThemeColor::named(Green))),
+                (Warning, ThemeStyle::color(ThemeColor::named(Yellow))),
+                (Error, ThemeStyle::color(ThemeColor::named(Red))),
+                (Selected, ThemeStyle::color(ThemeColor::named(Cyan)).bold()),
+                (Match, ThemeStyle::color(ThemeColor::named(Yellow)).bold()),
+                (Prompt, ThemeStyle::plain().bold()),
+                (Muted, ThemeStyle::plain().dim()),
+            ],
+            "light" => vec![
+                (
+                    Accent,
+                    ThemeStyle::color(ThemeColor::Rgb(194, 78, 0)).bold(),
+                ),
+                (Loading, ThemeStyle::color(ThemeColor::Rgb(138, 106, 0))),
+                (Info, ThemeStyle::color(ThemeColor::named(Blue))),
+                (Success, ThemeStyle::color(ThemeColor::Rgb(46, 125, 50))),
+                (Warning, ThemeStyle::color(ThemeColor::Rgb(160, 90, 0))),
+                (Error, ThemeStyle::color(ThemeColor::Rgb(198, 40, 40))),
+                (Selected, ThemeStyle::color(ThemeColor::named(Blue)).bold()),
+                (
+                    Match,
+                    ThemeStyle::color(ThemeColor::named(Magenta))
+                        .bold()
+                        .underline(),
+                ),
+                (Prompt, ThemeStyle::plain().bold()),
+                (Muted, ThemeStyle::color(ThemeColor::Indexed(244))),
+            ],
+            "high-contrast" => vec![
+                (Accent, ThemeStyle::color(ThemeColor::bright(Yellow)).bold()),
+                (Loading, ThemeStyle::color(ThemeColor::bright(Yellow))),
+                (Info, ThemeStyle::color(ThemeColor::bright(Cyan))),
+                (Success, ThemeStyle::color(ThemeColor::bright(Green))),
+                (
+                    Warning,
+                    ThemeStyle::color(ThemeColor::bright(Yellow)).bold(),
+                ),
+                (Error, ThemeStyle::color(ThemeColor::bright(Red)).bold()),
+                (
+                    Selected,
+                    ThemeStyle::color(ThemeColor::bright(White))
+                        .bold()
+                        .underline(),
+                ),
+                (
+                    Match,
+                    ThemeStyle::color(ThemeColor::bright(Yellow))
+                        .bold()
+                        .underline(),
+                ),
+                (Prompt, ThemeStyle::color(ThemeColor::bright(White)).bold()),
+                (Muted, ThemeStyle::color(ThemeColor::named(White))),
+            ],
+            "monochrome" => vec![
+                (Accent, ThemeStyle::plain().bold()),
+                (Warning, ThemeStyle::plain().bold()),
+                (Error, ThemeStyle::plain().bold()),
+                (Selected, ThemeStyle::plain().bold().underline()),
+                (Match, ThemeStyle::plain().underline()),
+                (Prompt, ThemeStyle::plain().bold()),
+                (Muted, ThemeStyle::plain().dim()),
+            ],
+            _ => return None,
+        };
+
+        return Some(Self {
+            styles: styles.into_iter().collect(),
+            level: ColorLevel::TrueColor,
+        });
+    }
+
+    /// Resolve a built-in or user-defined theme by name
+    ///
+    /// User-defined themes override the styles of the theme they extend, and may
+    /// replace a built-in theme of the same name.
+    pub fn resolve(name: &str, themes: &HashMap<String, ThemeConfig>) -> HnResult<Self> {
+        return Self::resolve_chain(name, themes, &mut Vec::new());
+    }
+
+    fn resolve_chain<'a>(
+        name: &'a str,
+        themes: &'a HashMap<String, ThemeConfig>,
+        chain: &mut Vec<&'a str>,
+    ) -> HnResult<Self> {
+        // A theme extending its own name builds on the built-in theme
+        let Some(config) = themes.get(name).filter(|_| !chain.contains(&name)) else {
+            return Self::builtin(name).ok_or_else(|| {
+                let mut names: Vec<&str> = BUILTIN_THEMES.to_vec();
+                names.extend(themes.keys().map(String::as_str));
+                names.sort_unstable();
+                names.dedup();
+
+                if chain.contains(&name) {
+                    return format!("Theme '{name}' extends itself").into();
+                }
+                return format!(
+                    "Unknown theme '{name}', expected one of: {}",
+                    names.join(", ")
+                )
+                .into();
+            });
+        };
+
+        chain.push(name);
+        let mut theme: Theme = Self::resolve_chain(
+            config.extends.as_deref().unwrap_or(DEFAULT_THEME),
+            themes,
+            chain,
+        )?;
+
+        for (role, style) in &config.styles {
+            theme.styles.insert(*role, style.style());
+        }
+
+        return Ok(theme);
+    }
+
+    /// Limit the theme's colors to what the terminal can show
+    pub fn with_color_level(mut self, level: ColorLevel) -> Self {
+        self.level = level;
+        return self;
+    }
+
+    pub fn color_level(&self) -> ColorLevel {
+        return self.level;
+    }
+
+    /// Console style of a role
+    pub fn style(&self, role: ThemeRole) -> Style {
+        return self
+            .styles
+            .get(&role)
+            .copied()
+            .unwrap_or_default()
+            .to_console(self.level);
+    }
+
+    /// Style text with a role
+    pub fn paint<D>(&self, role: ThemeRole, text: D) -> StyledObject<D> {
+        return self.style(role).apply_to(text);
+    }
+
+    /// Theme for dialoguer prompts, which are drawn on stderr
+    pub fn dialoguer(&self) -> ColorfulTheme {
+        let stderr = |role: ThemeRole| -> Style {
+            return self.style(role).for_stderr();
+        };
+        let symbol = |symbol: &str, role: ThemeRole| -> StyledObject<String> {
+            return stderr(role).apply_to(symbol.to_string());
+        };
+
+        return ColorfulTheme {
+            defaults_style: stderr(ThemeRole::Selected),
+            prompt_style: stderr(ThemeRole::Prompt),
+            prompt_prefix: symbol("?", ThemeRole::Warning),
+            prompt_suffix: symbol("›", ThemeRole::Muted),
+            success_prefix: symbol("✔", ThemeRole::Success),
+            success_suffix: symbol("·", ThemeRole::Muted),
+            error_prefix: symbol("✘", ThemeRole::Error),
+            error_style: stderr(ThemeRole::Error),
+            hint_style: stderr(ThemeRole::Muted),
+            values_style: stderr(ThemeRole::Success),
+            active_item_style: stderr(ThemeRole::Selected),
+            inactive_item_style: Style::new().for_stderr(),
+            active_item_prefix: symbol("❯", ThemeRole::Selected),
+            ..ColorfulTheme::default()
+        };
+    }
+}
+
+/// Use a theme for the rest of the process and enable colors to match it
+pub fn init(theme: Theme) {
+    console::set_colors_enabled(theme.color_level() != ColorLevel::None);
+    console::set_true_colors_enabled(theme.color_level() == ColorLevel::TrueColor);
+    console::set_colors_enabled_stderr(ColorLevel::stderr() != ColorLevel::None);
+
+    let _ = CURRENT_THEME.set(theme);
+}
+
+/// The theme set with [`init`], or the default theme for stdout
+pub fn current() -> &'static Theme {
+    return CURRENT_THEME.get_or_init(|| {
+        return Theme::builtin(DEFAULT_THEME)
+            .expect("the default theme is built in")
+            .with_color_level(ColorLevel::stdout());
+    });
+}
+
+/// The xterm 256-color palette index closest to an RGB color
+fn rgb_to_256(red: u8, green: u8, blue: u8) -> u8 {
+    const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    let nearest_level = |value: u8| -> usize {
+        return (0..CUBE_LEVELS.len())
+            .min_by_key(|index| CUBE_LEVELS[*index].abs_diff(value))
+            .unwrap_or(0);
+    };
+    let (r, g, b) = (
+        nearest_level(red),
+        nearest_level(green),
+        nearest_level(blue),
+    );
+    let cube_index: u8 = (16 + 36 * r + 6 * g + b) as u8;
+
+    // Grays are better matched by the 24-step grayscale ramp
+    let average: u32 = (u32::from(red) + u32::from(green) + u32::from(blue)) / 3;
+    let gray_step: u8 = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_index: u8 = 232 + gray_step;
+
+    return [cube_index, gray_index]
+        .into_iter()
+        .min_by_key(|index| color_distance(palette_rgb(*index), (red, green, blue)))
+        .unwrap_or(cube_index);
+}
+
+/// RGB value of an xterm 256-color palette index
+fn palette_rgb(index: u8) -> (u8, u8, u8) {
+    const ANSI: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    return match index {
+        0..=15 => ANSI[usize::from(index)],
+        16..=231 => {
+            let cube: usize = usize::from(index - 16);
+            (
+                CUBE_LEVELS[cube / 36],
+                CUBE_LEVELS[cube / 6 % 6],
+                CUBE_LEVELS[cube % 6],
+            )
+        }
+        232..=255 => {
+            let gray: u8 = 8 + 10 * (index - 232);
+            (gray, gray, gray)
+        }
+    };
+}
+
+/// The base ANSI color closest to an RGB color
+fn nearest_base_color(red: u8, green: u8, blue: u8) -> BaseColor {
+    const GRAY_SPREAD: u8 = 32;
+
+    // Grays would otherwise match dull colors like yellow, so they become black or white
+    let (min, max) = (red.min(green).min(blue), red.max(green).max(blue));
+    if max - min < GRAY_SPREAD {
+        return if max < 64 {
+            BaseColor::Black
+        } else {
+            BaseColor::White
+        };
+    }
+
+    // Bright variants are left out, since they are shown as their base color
+    let index: u8 = (0..8)
+        .min_by_key(|index| color_distance(palette_rgb(*index), (red, green, blue)))
+        .unwrap_or(0);
+
+    return BaseColor::ALL[usize::from(index)];
+}
+
+/// Squared distance between two RGB colors
+fn color_distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let channel = |x: u8, y: u8| -> u32 { u32::from(x.abs_diff(y)).pow(2) };
+    return channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        return move |name| vars.get(name).cloned();
+    }
+
+    #[test]
+    fn test_color_level_detect() {
+        let truecolor = [("TERM", "xterm-256color"), ("COLORTERM", "truecolor")];
+
+        assert_eq!(
+            ColorLevel::detect(true, env(&truecolor)),
+            ColorLevel::TrueColor
+        );
+        assert_eq!(
+            ColorLevel::detect(true, env(&[("TERM", "xterm-256color")])),
+            ColorLevel::Ansi256
+        );
+        assert_eq!(
+            ColorLevel::detect(true, env(&[("TERM", "xterm")])),
+            ColorLevel::Ansi16
+        );
+        assert_eq!(
+            ColorLevel::detect(true, env(&[("TERM", "dumb")])),
+            ColorLevel::None
+        );
+
+        // Piped output has no colors unless forced
+        assert_eq!(ColorLevel::detect(false, env(&truecolor)), ColorLevel::None);
+        assert_eq!(
+            ColorLevel::detect(false, env(&[("TERM", "xterm"), ("CLICOLOR_FORCE", "1")])),
+            ColorLevel::Ansi16
+        );
+        assert_eq!(
+            ColorLevel::detect(false, env(&[("CLICOLOR_FORCE", "0")])),
+            ColorLevel::None
+        );
+        assert_eq!(
+            ColorLevel::detect(true, env(&[("CLICOLOR", "0")])),
+            ColorLevel::None
+        );
+
+        // NO_COLOR wins over everything
+        assert_eq!(
+            ColorLevel::detect(true, env(&[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")])),
+            ColorLevel::None
+        );
+        assert_eq!(
+            ColorLevel::detect(true, env(&[("NO_COLOR", ""), ("TERM", "xterm")])),
+            ColorLevel::Ansi16
+        );
+    }
+
+    #[test]
+    fn test_theme_color_from_str() {
+        assert_eq!("#ff6600".parse(), Ok(ThemeColor::Rgb(255, 102, 0)));
+        assert_eq!("#F60".parse(), Ok(ThemeColor::Rgb(255, 102, 0)));
+        assert_eq!("208".parse(), Ok(ThemeColor::Indexed(208)));
+        assert_eq!("Red".parse(), Ok(ThemeColor::named(BaseColor::Red)));
+        assert_eq!(
+            "bright-cyan".parse(),
+            Ok(ThemeColor::bright(BaseColor::Cyan))
+        );
+        assert!("#ff66".parse::<ThemeColor>().is_err());
+        assert!("orange".parse::<ThemeColor>().is_err());
+        assert!("256".parse::<ThemeColor>().is_err());
+
+        assert_eq!(ThemeColor::Rgb(255, 102, 0).to_string(), "#ff6600");
+        assert_eq!(
+            ThemeColor::bright(BaseColor::Cyan).to_string(),
+            "bright-cyan"
+        );
+    }
+
+    #[test]
+    fn test_theme_color_falls_back_to_terminal_palette() {
+        let orange: ThemeColor = ThemeColor::Rgb(255, 102, 0);
+
+        assert_eq!(
+            orange.to_console(ColorLevel::TrueColor),
+            Some(Color::TrueColor(255, 102, 0))
+        );
+        assert_eq!(
+            orange.to_console(ColorLevel::Ansi256),
+            Some(Color::Color256(202))
+        );
+        assert_eq!(orange.to_console(ColorLevel::Ansi16), Some(Color::Red));
+        assert_eq!(orange.to_console(ColorLevel::None), None);
+
+        // Grays use the grayscale ramp
+        assert_eq!(
+            ThemeColor::Rgb(128, 128, 128).to_console(ColorLevel::Ansi256),
+            Some(Color::Color256(244))
+        );
+        assert_eq!(
+            ThemeColor::Indexed(244).to_console(ColorLevel::Ansi16),
+            Some(Color::White)
+        );
+        assert_eq!(
+            ThemeColor::bright(BaseColor::Cyan).to_console(ColorLevel::Ansi256),
+            Some(Color::Color256(14))
+        );
+        assert_eq!(
+            ThemeColor::bright(BaseColor::Cyan).to_console(ColorLevel::Ansi16),
+            Some(Color::Cyan)
+        );
+    }
+
+    #[test]
+    fn test_theme_style_escape_codes() {
+        let theme: Theme = Theme::builtin("dark").unwrap();
+
+        let painted = |level: ColorLevel| -> String {
+            let style: Style = theme
+                .clone()
+                .with_color_level(level)
+                .style(ThemeRole::Accent)
+                .force_styling(true);
+            return style.apply_to("hn").to_string();
+        };
+
+        assert_eq!(
+            painted(ColorLevel::TrueColor),
+            "\x1b[38;2;255;102;0mhn\x1b[0m"
+        );
+        assert_eq!(painted(ColorLevel::Ansi256), "\x1b[38;5;202mhn\x1b[0m");
+        assert_eq!(painted(ColorLevel::Ansi16), "\x1b[31mhn\x1b[0m");
+        assert_eq!(painted(ColorLevel::None), "hn");
+
+        // Monochrome keeps attributes but no colors
+        let monochrome: Theme = Theme::builtin("monochrome").unwrap();
+        assert_eq!(
+            monochrome
+                .style(ThemeRole::Selected)
+                .force_styling(true)
+                .apply_to("hn")
+                .to_string(),
+            "\x1b[1m\x1b[4mhn\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_builtin_themes() {
+        for name in BUILTIN_THEMES {
+            assert!(Theme::builtin(name).is_some(), "{name}");
+        }
+        assert!(Theme::builtin("solarized").is_none());
+    }
+
+    #[test]
+    fn test_resolve_user_theme() {
+        let themes: HashMap<String, ThemeConfig> = toml::from_str(
+            r##"
+            [ocean]
+            accent = "#0087af"
+            selected = { color = "bright-cyan", underline = true }
+
+            [deep-ocean]
+            extends = "ocean"
+            muted = "244"
+
+            [dark]
+            extends = "dark"
+            error = { bold = true }
+            "##,
+        )
+        .unwrap();
+
+        let theme: Theme = Theme::resolve("deep-ocean", &themes).unwrap();
+        let dark: Theme = Theme::builtin("dark").unwrap();
+
+        assert_eq!(
+            theme.styles[&ThemeRole::Accent],
+            ThemeStyle::color(ThemeColor::Rgb(0, 135, 175))
+        );
+        assert_eq!(
+            theme.styles[&ThemeRole::Selected],
+            ThemeStyle::color(ThemeColor::bright(BaseColor::Cyan)).underline()
+        );
+        assert_eq!(
+            theme.styles[&ThemeRole::Muted],
+            ThemeStyle::color(ThemeColor::Indexed(244))
+        );
+        // Roles that are not overridden come from the extended theme
+        assert_eq!(
+            theme.styles[&ThemeRole::Info],
+            dark.styles[&ThemeRole::Info]
+        );
+
+        // A user theme can replace a built-in theme it extends
+        let custom_dark: Theme = Theme::resolve("dark", &themes).unwrap();
+        assert_eq!(
+            custom_dark.styles[&ThemeRole::Error],
+            ThemeStyle::plain().bold()
+        );
+        assert_eq!(
+            custom_dark.styles[&ThemeRole::Accent],
+            dark.styles[&ThemeRole::Accent]
+        );
+    }
+
+    #[test]
+    fn test_resolve_invalid_themes() {
+        let themes: HashMap<String, ThemeConfig> = toml::from_str(
+            r#"
+            [a]
+            extends = "b"
+
+            [b]
+            extends = "a"
+
+            [typo]
+            extends = "drak"
+            "#,
+        )
+        .unwrap();
+
+        assert!(
+            Theme::resolve("a", &themes)
+                .unwrap_err()
+                .to_string()
+                .contains("extends itself")
+        );
+        assert!(
+            Theme::resolve("typo", &themes)
+                .unwrap_err()
+                .to_string()
+                .contains("Unknown theme 'drak'")
+        );
+
+        let unknown_role: Result<HashMap<String, ThemeConfig>, toml::de::Error> =
+            toml::from_str("[x]\nbackground = \"red\"\n");
+        assert!(unknown_role.is_err());
+    }
+}
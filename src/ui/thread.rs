@@ -0,0 +1,302 @@
+use scraper::Html;
+use textwrap::Options;
+
+use crate::models::item::ItemThread;
+use crate::models::story::StoryItem;
+use crate::ui::theme::{Theme, ThemeRole};
+use crate::utils::time::TimeFormatter;
+
+/// Narrowest width threads are wrapped to, regardless of terminal size
+const MIN_WIDTH: usize = 20;
+/// Widest width threads are wrapped to, to keep lines readable on wide terminals
+const MAX_WIDTH: usize = 100;
+/// Columns each reply level is indented by
+const INDENT_WIDTH: usize = 2;
+/// Text shown for deleted or empty comments
+const DELETED_TEXT: &str = "[deleted]";
+
+/// Render a thread as wrapped text: the story, then each comment down to the looked-up
+/// one, indented by reply level
+pub fn format_thread(
+    thread: &ItemThread,
+    width: usize,
+    time: &TimeFormatter,
+    theme: &Theme,
+) -> String {
+    let width: usize = width.clamp(MIN_WIDTH, MAX_WIDTH);
+    let mut lines: Vec<String> = story_lines(&thread.story, width, time, theme);
+
+    let comments = thread
+        .context
+        .iter()
+        .map(|comment| (comment, ThemeRole::Muted))
+        .chain(
+            thread
+                .comment
+                .iter()
+                .map(|comment| (comment, ThemeRole::Accent)),
+        );
+    for (depth, (comment, role)) in comments.enumerate() {
+        let indent: String = " ".repeat(depth * INDENT_WIDTH);
+        lines.extend(comment_lines(comment, role, width, &indent, time, theme));
+    }
+
+    return lines.join("\n");
+}
+
+/// Render a story and some of its top-level comments as wrapped text, noting how many
+/// replies were left out
+pub fn format_comments(
+    story: &StoryItem,
+    comments: &[StoryItem],
+    width: usize,
+    time: &TimeFormatter,
+    theme: &Theme,
+) -> String {
+    let width: usize = width.clamp(MIN_WIDTH, MAX_WIDTH);
+    let mut lines: Vec<String> = story_lines(story, width, time, theme);
+
+    for comment in comments {
+        lines.extend(comment_lines(
+            comment,
+            ThemeRole::Accent,
+            width,
+            "",
+            time,
+            theme,
+        ));
+    }
+
+    let left_out: usize = story.kids.len().saturating_sub(comments.len());
+    if left_out > 0 {
+        lines.push(String::new());
+        let more: String = format!("{left_out} more replies at {}", story.discussion_url());
+        for line in wrap(&more, width, "") {
+            lines.push(theme.paint(ThemeRole::Muted, line).to_string());
+        }
+    }
+
+    return lines.join("\n");
+}
+
+/// The story's title, details and text
+fn story_lines(
+    story: &StoryItem,
+    width: usize,
+    time: &TimeFormatter,
+    theme: &Theme,
+) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in wrap(story.title.as_deref().unwrap_or("No Title"), width, "") {
+        lines.push(theme.paint(ThemeRole::Accent, line).to_string());
+    }
+    let mut details: String = format!(
+        "{} points {} | {} comments",
+        story.score.unwrap_or(0),
+        story.get_byline_text(time),
+        story.descendants.unwrap_or(0)
+    );
+    if let Some(url) = &story.url {
+        details.push_str(&format!(" | {url}"));
+    }
+    lines.push(theme.paint(ThemeRole::Muted, details).to_string());
+
+    if let Some(text) = &story.text {
+        lines.push(String::new());
+        for paragraph in html_paragraphs(text) {
+            lines.extend(wrap(&paragraph, width, ""));
+        }
+    }
+
+    return lines;
+}
+
+/// A blank line, the comment's byline and its text, all indented
+fn comment_lines(
+    comment: &StoryItem,
+    role: ThemeRole,
+    width: usize,
+    indent: &str,
+    time: &TimeFormatter,
+    theme: &Theme,
+) -> Vec<String> {
+    let mut lines: Vec<String> = vec![
+        String::new(),
+        format!(
+            "{indent}{}",
+            theme.paint(role, comment.get_byline_text(time))
+        ),
+    ];
+
+    let paragraphs: Vec<String> = comment
+        .text
+        .as_deref()
+        .map(html_paragraphs)
+        .unwrap_or_default();
+    if paragraphs.is_empty() {
+        lines.push(format!("{indent}{DELETED_TEXT}"));
+    }
+    for paragraph in paragraphs {
+        lines.extend(wrap(&paragraph, width, indent));
+    }
+
+    return lines;
+}
+
+/// Convert item HTML, where `<p>` starts a new paragraph, to plain text paragraphs
+fn html_paragraphs(html: &str) -> Vec<String> {
+    return html
+        .split("<p>")
+        .map(|paragraph| {
+            return Html::parse_fragment(paragraph)
+                .root_element()
+                .text()
+                .collect::<String>()
+                .trim()
+                .to_string();
+        })
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect();
+}
+
+/// Wrap text to the given width, indenting every line
+fn wrap(text: &str, width: usize, indent: &str) -> Vec<String> {
+    let options: Options = Options::new(width)
+        .initial_indent(indent)
+        .subsequent_indent(indent);
+
+    return textwrap::wrap(text, options)
+        .into_iter()
+        .map(|line| line.into_owned())
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    use crate::ui::theme::ColorLevel;
+    use crate::utils::time::tests::{test_formatter, test_now};
+    use crate::utils::time::{DisplayTimezone, TimeMode};
+
+    fn plain_theme() -> Theme {
+        return Theme::builtin("dark")
+            .unwrap()
+            .with_color_level(ColorLevel::None);
+    }
+
+    fn comment(id: u64, by: &str, text: Option<&str>) -> StoryItem {
+        return StoryItem {
+            id,
+            by: Some(by.to_string()),
+            text: text.map(str::to_string),
+            time: Some(test_now() - Duration::hours(2)),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn test_format_thread() {
+        let thread: ItemThread = ItemThread {
+            story: StoryItem {
+                id: 1,
+                by: Some("pg".to_string()),
+                title: Some("Ask HN: Favorite editor?".to_string()),
+                score: Some(42),
+                descendants: Some(3),
+                text: Some("Curious what &quot;everyone&quot; uses.".to_string()),
+                time: Some(test_now() - Duration::hours(3)),
+                ..Default::default()
+            },
+            context: vec![
+                comment(2, "alice", Some("Vim.<p>Mostly <i>out of habit</i>.")),
+                comment(3, "bob", None),
+            ],
+            comment: Some(comment(
+                4,
+                "carol",
+                Some("Emacs, see <a href=\"https://gnu.org\">gnu.org</a>"),
+            )),
+        };
+        let time = test_formatter(TimeMode::Relative, DisplayTimezone::Local);
+
+        let rendered: String = format_thread(&thread, 80, &time, &plain_theme());
+
+        assert_eq!(
+            rendered,
+            [
+                "Ask HN: Favorite editor?",
+                "42 points by pg 3 hours ago | 3 comments",
+                "",
+                "Curious what \"everyone\" uses.",
+                "",
+                "by alice 2 hours ago",
+                "Vim.",
+                "Mostly out of habit.",
+                "",
+                "  by bob 2 hours ago",
+                "  [deleted]",
+                "",
+                "    by carol 2 hours ago",
+                "    Emacs, see gnu.org",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_format_thread_wraps_indented_comments() {
+        let thread: ItemThread = ItemThread {
+            story: StoryItem {
+                id: 1,
+                title: Some("Story".to_string()),
+                ..Default::default()
+            },
+            context: vec![comment(2, "alice", Some("Parent"))],
+            comment: Some(comment(3, "bob", Some("one two three four five six"))),
+        };
+        let time = test_formatter(TimeMode::Relative, DisplayTimezone::Local);
+
+        let rendered: String = format_thread(&thread, 10, &time, &plain_theme());
+
+        // Narrow terminals still get the minimum width, indent included
+        assert!(rendered.ends_with("  one two three four\n  five six"));
+    }
+
+    #[test]
+    fn test_format_comments() {
+        let story: StoryItem = StoryItem {
+            id: 1,
+            by: Some("pg".to_string()),
+            title: Some("Show HN: A thing".to_string()),
+            score: Some(10),
+            descendants: Some(5),
+            kids: vec![2, 3, 4],
+            time: Some(test_now() - Duration::hours(3)),
+            ..Default::default()
+        };
+        let comments: Vec<StoryItem> =
+            vec![comment(2, "alice", Some("Nice.")), comment(3, "bob", None)];
+        let time = test_formatter(TimeMode::Relative, DisplayTimezone::Local);
+
+        let rendered: String = format_comments(&story, &comments, 80, &time, &plain_theme());
+
+        assert_eq!(
+            rendered,
+            [
+                "Show HN: A thing",
+                "10 points by pg 3 hours ago | 5 comments",
+                "",
+                "by alice 2 hours ago",
+                "Nice.",
+                "",
+                "by bob 2 hours ago",
+                "[deleted]",
+                "",
+                "1 more replies at https://news.ycombinator.com/item?id=1",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -1,4 +1,4 @@
 pub const HACKER_NEWS_API_BASE_URL: &str = "https://hacker-news.firebaseio.com";
+pub const HACKER_NEWS_SEARCH_API_BASE_URL: &str = "https://hn.algolia.com/api/v1";
 pub const Y_COMBINATOR_BASE_URL: &str = "https://news.ycombinator.com";
-
-pub const ORANGE_COLOR: &str = "\x1b[38;2;255;102;0m";
+pub const HTTP_USER_AGENT: &str = concat!("hn-stories/", env!("CARGO_PKG_VERSION"));
@@ -1,20 +1,41 @@
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 
-use chroma_print::Color;
+use crate::ui::theme::{self, ThemeRole};
 
-/// Display loading message
+/// Display loading message (only on a terminal, so it never ends up in piped output)
 pub fn show_loading(message: Option<&str>) {
+    if !io::stdout().is_terminal() {
+        return;
+    }
+
     print!(
-        "{}{}{}",
-        Color::Yellow.value(),
-        message.unwrap_or("Loading..."),
-        Color::Reset.value()
+        "{}",
+        theme::current().paint(ThemeRole::Loading, message.unwrap_or("Loading..."))
     );
     io::stdout().flush().unwrap();
 }
 
 /// Clear loading message
 pub fn clear_loading() {
+    if !io::stdout().is_terminal() {
+        return;
+    }
+
     print!("\r\x1b[2K"); // Clears the entire current line
     io::stdout().flush().unwrap();
 }
+
+/// Whether `text` contains `phrase` as whole words (both already lowercase)
+pub fn contains_phrase(text: &str, phrase: &str) -> bool {
+    if phrase.is_empty() {
+        return false;
+    }
+
+    return text.match_indices(phrase).any(|(start, _)| {
+        let end: usize = start + phrase.len();
+        let before: Option<char> = text[..start].chars().next_back();
+        let after: Option<char> = text[end..].chars().next();
+        return !before.is_some_and(char::is_alphanumeric)
+            && !after.is_some_and(char::is_alphanumeric);
+    });
+}
@@ -0,0 +1,162 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::types::HnResult;
+
+/// Read a file, or `None` when it does not exist
+pub fn read_if_exists(path: &Path) -> HnResult<Option<String>> {
+    return match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    };
+}
+
+/// A value kept as a JSON document in a file
+///
+/// Saves write a temporary file next to it and rename it over the old one, so a
+/// failed save never leaves a half-written document behind.
+pub struct JsonFile<T> {
+    path: PathBuf,
+    /// What the document holds, for errors (e.g. "mute rules")
+    description: &'static str,
+    /// Whether only the current user may read the file
+    private: bool,
+    value: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for JsonFile<T> {
+    fn clone(&self) -> Self {
+        return Self {
+            path: self.path.clone(),
+            description: self.description,
+            private: self.private,
+            value: PhantomData,
+        };
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> JsonFile<T> {
+    pub fn new(path: PathBuf, description: &'static str) -> Self {
+        return Self {
+            path,
+            description,
+            private: false,
+            value: PhantomData,
+        };
+    }
+
+    /// Create the file readable and writable only by the current user (on Unix), for secrets
+    pub fn private(mut self) -> Self {
+        self.private = true;
+        return self;
+    }
+
+    /// Load the value, or `None` when the file does not exist
+    pub fn load(&self) -> HnResult<Option<T>> {
+        let Some(contents) = read_if_exists(&self.path)? else {
+            return Ok(None);
+        };
+
+        let value: T = serde_json::from_str(&contents).map_err(|error| {
+            format!(
+                "Corrupt {} {}: {error}",
+                self.description,
+                self.path.display()
+            )
+        })?;
+
+        return Ok(Some(value));
+    }
+
+    /// Write the value, replacing the previous file atomically
+    pub fn save(&self, value: &T) -> HnResult<()> {
+        let temporary_path: PathBuf = self.path.with_extension("json.tmp");
+        // A file left by an interrupted save may have other permissions, so start anew
+        match fs::remove_file(&temporary_path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => return Err(error.into()),
+            _ => {}
+        }
+
+        let mut options: OpenOptions = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        if self.private {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file: File = options.open(&temporary_path)?;
+        file.write_all(serde_json::to_string_pretty(value)?.as_bytes())?;
+        drop(file);
+        fs::rename(&temporary_path, &self.path)?;
+
+        return Ok(());
+    }
+
+    /// Remove the file, returning whether it existed
+    pub fn remove(&self) -> HnResult<bool> {
+        return match fs::remove_file(&self.path) {
+            Ok(()) => Ok(true),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(false),
+            Err(error) => Err(error.into()),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_file_round_trip() {
+        let directory = tempfile::tempdir().unwrap();
+        let path: PathBuf = directory.path().join("ids.json");
+        let file: JsonFile<Vec<u64>> = JsonFile::new(path.clone(), "story IDs");
+
+        assert_eq!(file.load().unwrap(), None);
+        assert!(!file.remove().unwrap());
+
+        file.save(&vec![1, 2]).unwrap();
+        file.save(&vec![3]).unwrap();
+        assert_eq!(file.load().unwrap(), Some(vec![3]));
+        assert!(!directory.path().join("ids.json.tmp").exists());
+
+        fs::write(&path, "not json").unwrap();
+        assert!(
+            file.load()
+                .unwrap_err()
+                .to_string()
+                .starts_with("Corrupt story IDs")
+        );
+
+        assert!(file.remove().unwrap());
+        assert_eq!(file.load().unwrap(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_json_file_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let directory = tempfile::tempdir().unwrap();
+        let path: PathBuf = directory.path().join("secret.json");
+        let file: JsonFile<String> = JsonFile::new(path.clone(), "secret").private();
+
+        // A readable file left by an interrupted save is not reused
+        let temporary_path: PathBuf = path.with_extension("json.tmp");
+        fs::write(&temporary_path, "{}").unwrap();
+        fs::set_permissions(&temporary_path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        file.save(&"token".to_string()).unwrap();
+
+        let mode: u32 = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(file.load().unwrap().as_deref(), Some("token"));
+    }
+}
@@ -0,0 +1,430 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::{self, IsTerminal, Write};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::types::HnResult;
+use crate::ui::theme::{self, ThemeRole};
+
+/// Target of the per-request log records, e.g. for `RUST_LOG=hn_stories::http=debug`
+pub const HTTP_TARGET: &str = "hn_stories::http";
+/// Prefix removed from targets in log lines
+const CRATE_TARGET_PREFIX: &str = "hn_stories::";
+/// Most records held back while the terminal UI is on screen, older ones are dropped
+const MAX_HELD_RECORDS: usize = 1000;
+
+/// The installed logger, kept to release held records
+static LOGGER: OnceLock<&'static Logger> = OnceLock::new();
+/// Number given to the next request span
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Log levels per target, parsed from a `RUST_LOG`-style filter such as
+/// `warn,hn_stories=debug,reqwest=info`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogFilter {
+    /// Level for targets without a directive
+    default: LevelFilter,
+    /// Target prefixes and their levels, most specific first
+    directives: Vec<(String, LevelFilter)>,
+}
+
+impl LogFilter {
+    /// Parse comma-separated `level`, `target` or `target=level` directives
+    pub fn parse(filter: &str) -> HnResult<Self> {
+        let mut default: LevelFilter = LevelFilter::Off;
+        let mut directives: Vec<(String, LevelFilter)> = Vec::new();
+
+        for directive in filter.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let invalid = || format!("Invalid log filter directive '{directive}'");
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    let level: LevelFilter = level.trim().parse().map_err(|_| invalid())?;
+                    if target.trim().is_empty() {
+                        return Err(invalid().into());
+                    }
+                    directives.push((target.trim().replace('-', "_"), level));
+                }
+                None => match directive.parse::<LevelFilter>() {
+                    Ok(level) => default = level,
+                    Err(_) => directives.push((directive.replace('-', "_"), LevelFilter::Trace)),
+                },
+            }
+        }
+        directives.sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+
+        return Ok(Self {
+            default,
+            directives,
+        });
+    }
+
+    /// Level enabled for a target, from the longest matching directive
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        return self
+            .directives
+            .iter()
+            .find(|(prefix, _)| {
+                return target == prefix
+                    || target
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with("::"));
+            })
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default);
+    }
+
+    /// Most verbose level enabled for any target
+    pub fn max_level(&self) -> LevelFilter {
+        return self
+            .directives
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, Ord::max);
+    }
+}
+
+/// Where log records are written and whether they are held back
+pub struct LogConfig {
+    pub filter: LogFilter,
+    /// File records are appended to instead of stderr
+    pub file: Option<PathBuf>,
+    /// Hold records written to a terminal until [`release`], so they never
+    /// interleave with the terminal UI
+    pub hold: bool,
+}
+
+/// Install the logger for the rest of the process
+pub fn init(config: LogConfig) -> HnResult<()> {
+    let output: LogOutput = match &config.file {
+        Some(path) => LogOutput::File(open_log_file(path)?),
+        None => LogOutput::Stderr {
+            terminal: io::stderr().is_terminal(),
+        },
+    };
+    let hold: bool = config.hold && matches!(output, LogOutput::Stderr { terminal: true });
+
+    let logger: &'static Logger = Box::leak(Box::new(Logger {
+        filter: config.filter,
+        sink: Mutex::new(LogSink {
+            output,
+            held: hold.then(VecDeque::new),
+            dropped: 0,
+        }),
+    }));
+    log::set_logger(logger)?;
+    log::set_max_level(logger.filter.max_level());
+    let _ = LOGGER.set(logger);
+
+    // A panic ends the process without reaching `release`, so write held records first.
+    // The sink is skipped if the panic happened while it was locked.
+    if hold {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if let Ok(mut sink) = logger.sink.try_lock() {
+                sink.release();
+            }
+            previous(info);
+        }));
+    }
+
+    return Ok(());
+}
+
+/// Write records held back for the terminal UI and stop holding new ones
+pub fn release() {
+    if let Some(logger) = LOGGER.get() {
+        logger.sink.lock().unwrap().release();
+    }
+}
+
+/// Append to a log file, creating it and its directory if needed
+fn open_log_file(path: &Path) -> HnResult<File> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    return OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|error| format!("Could not open log file {}: {error}", path.display()).into());
+}
+
+/// Logger writing one line per record to stderr or a file
+struct Logger {
+    filter: LogFilter,
+    sink: Mutex<LogSink>,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        return metadata.level() <= self.filter.level_for(metadata.target());
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let mut sink = self.sink.lock().unwrap();
+        let line: String = format_record(record, sink.output.is_terminal());
+        sink.write(line);
+    }
+
+    fn flush(&self) {
+        let _ = self.sink.lock().unwrap().output.flush();
+    }
+}
+
+enum LogOutput {
+    Stderr { terminal: bool },
+    File(File),
+}
+
+impl LogOutput {
+    fn is_terminal(&self) -> bool {
+        return matches!(self, LogOutput::Stderr { terminal: true });
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        return match self {
+            // Clear a loading message left on the line before logging over it
+            LogOutput::Stderr { terminal: true } => writeln!(io::stderr(), "\r\x1b[2K{line}"),
+            LogOutput::Stderr { terminal: false } => writeln!(io::stderr(), "{line}"),
+            LogOutput::File(file) => writeln!(file, "{line}"),
+        };
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        return match self {
+            LogOutput::Stderr { .. } => io::stderr().flush(),
+            LogOutput::File(file) => file.flush(),
+        };
+    }
+}
+
+/// Formatted records and the records held back while the terminal UI is shown
+struct LogSink {
+    output: LogOutput,
+    held: Option<VecDeque<String>>,
+    /// Held records dropped to stay within [`MAX_HELD_RECORDS`]
+    dropped: usize,
+}
+
+impl LogSink {
+    fn write(&mut self, line: String) {
+        match &mut self.held {
+            Some(held) => {
+                if held.len() == MAX_HELD_RECORDS {
+                    held.pop_front();
+                    self.dropped += 1;
+                }
+                held.push_back(line);
+            }
+            // Logging must never fail the command, so write errors are ignored
+            None => {
+                let _ = self.output.write_line(&line);
+            }
+        }
+    }
+
+    fn release(&mut self) {
+        let Some(held) = self.held.take() else {
+            return;
+        };
+
+        if self.dropped > 0 {
+            let _ = self
+                .output
+                .write_line(&format!("... {} earlier log records dropped", self.dropped));
+        }
+        for line in held {
+            let _ = self.output.write_line(&line);
+        }
+        let _ = self.output.flush();
+    }
+}
+
+/// Format a record as `time LEVEL target: message`, colored for terminals
+fn format_record(record: &Record, terminal: bool) -> String {
+    let target: &str = record
+        .target()
+        .strip_prefix(CRATE_TARGET_PREFIX)
+        .unwrap_or(record.target());
+    if !terminal {
+        return format!(
+            "{} {:<5} {target}: {}",
+            Local::now().format("%Y-%m-%dT%H:%M:%S%.3f%:z"),
+            record.level(),
+            record.args()
+        );
+    }
+
+    let role: ThemeRole = match record.level() {
+        Level::Error => ThemeRole::Error,
+        Level::Warn => ThemeRole::Warning,
+        Level::Info => ThemeRole::Info,
+        Level::Debug | Level::Trace => ThemeRole::Muted,
+    };
+    let style = |role: ThemeRole| theme::current().style(role).for_stderr();
+
+    return format!(
+        "{} {} {}: {}",
+        style(ThemeRole::Muted).apply_to(Local::now().format("%H:%M:%S%.3f")),
+        style(role).apply_to(format!("{:<5}", record.level())),
+        style(ThemeRole::Muted).apply_to(target),
+        record.args()
+    );
+}
+
+/// Timing of one HTTP request, logged as `req=N` records when it starts and ends
+pub struct RequestSpan {
+    id: u64,
+    method: String,
+    url: String,
+    started: Instant,
+}
+
+impl RequestSpan {
+    pub fn start(method: &str, url: &str) -> Self {
+        let span: RequestSpan = Self {
+            id: NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed),
+            method: method.to_string(),
+            url: url.to_string(),
+            started: Instant::now(),
+        };
+        log::debug!(target: HTTP_TARGET, "req={} {} {} started", span.id, span.method, span.url);
+
+        return span;
+    }
+
+    /// Log a retry of the request after a rate-limited response
+    pub fn retry(&self, status: u16, delay: Duration, attempt: u32) {
+        log::warn!(
+            target: HTTP_TARGET,
+            "req={} {} {} status={status} retry={attempt} wait={}ms",
+            self.id,
+            self.method,
+            self.url,
+            delay.as_millis()
+        );
+    }
+
+    /// Log a completed request and the size of its body
+    pub fn finish(&self, status: u16, bytes: usize) {
+        log::info!(
+            target: HTTP_TARGET,
+            "req={} {} {} status={status} latency={}ms bytes={bytes}",
+            self.id,
+            self.method,
+            self.url,
+            self.started.elapsed().as_millis()
+        );
+    }
+
+    /// Log a request answered with `304 Not Modified`, whose cached body is reused
+    pub fn revalidated(&self, cached_bytes: usize) {
+        log::info!(
+            target: HTTP_TARGET,
+            "req={} {} {} status=304 latency={}ms bytes=0 cached_bytes={cached_bytes}",
+            self.id,
+            self.method,
+            self.url,
+            self.started.elapsed().as_millis()
+        );
+    }
+
+    /// Log a failed request with the chain of errors that caused it
+    pub fn fail(&self, error: &(dyn Error + 'static)) {
+        let mut error_chain: String = error.to_string();
+        let mut source: Option<&(dyn Error + 'static)> = error.source();
+        while let Some(cause) = source {
+            error_chain.push_str(&format!(": {cause}"));
+            source = cause.source();
+        }
+
+        log::warn!(
+            target: HTTP_TARGET,
+            "req={} {} {} latency={}ms error=\"{error_chain}\"",
+            self.id,
+            self.method,
+            self.url,
+            self.started.elapsed().as_millis()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_filter_levels() {
+        let filter: LogFilter =
+            LogFilter::parse("warn, hn_stories=info,hn_stories::http=debug,hyper").unwrap();
+
+        assert_eq!(filter.level_for("reqwest::connect"), LevelFilter::Warn);
+        assert_eq!(filter.level_for("hn_stories::client"), LevelFilter::Info);
+        assert_eq!(filter.level_for("hn_stories::http"), LevelFilter::Debug);
+        assert_eq!(filter.level_for("hn_stories_extra"), LevelFilter::Warn);
+        assert_eq!(filter.level_for("hyper::proto"), LevelFilter::Trace);
+        assert_eq!(filter.max_level(), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn test_log_filter_defaults_to_off() {
+        let filter: LogFilter = LogFilter::parse("hn-stories=debug").unwrap();
+
+        assert_eq!(filter.level_for("hn_stories::args"), LevelFilter::Debug);
+        assert_eq!(filter.level_for("reqwest"), LevelFilter::Off);
+        assert_eq!(filter.max_level(), LevelFilter::Debug);
+    }
+
+    #[test]
+    fn test_log_filter_rejects_invalid_levels() {
+        let error: String = LogFilter::parse("hn_stories=loud").unwrap_err().to_string();
+
+        assert_eq!(error, "Invalid log filter directive 'hn_stories=loud'");
+        assert!(LogFilter::parse("=info").is_err());
+    }
+
+    #[test]
+    fn test_held_records_are_capped_and_released_in_order() {
+        let file: tempfile::NamedTempFile = tempfile::NamedTempFile::new().unwrap();
+        let mut sink: LogSink = LogSink {
+            output: LogOutput::File(file.reopen().unwrap()),
+            held: Some(VecDeque::new()),
+            dropped: 0,
+        };
+
+        for index in 0..MAX_HELD_RECORDS + 2 {
+            sink.write(format!("record {index}"));
+        }
+        assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "");
+
+        sink.release();
+        sink.write("after".to_string());
+
+        let written: String = std::fs::read_to_string(file.path()).unwrap();
+        let lines: Vec<&str> = written.lines().collect();
+        assert_eq!(lines.len(), MAX_HELD_RECORDS + 2);
+        assert_eq!(lines[0], "... 2 earlier log records dropped");
+        assert_eq!(lines[1], "record 2");
+        assert_eq!(
+            lines[MAX_HELD_RECORDS],
+            format!("record {}", MAX_HELD_RECORDS + 1)
+        );
+        assert_eq!(lines[MAX_HELD_RECORDS + 1], "after");
+    }
+}
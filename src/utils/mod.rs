@@ -1,2 +1,9 @@
 pub mod constants;
 pub mod helpers;
+pub mod json_store;
+pub mod logging;
+pub mod paths;
+pub mod rate_limit;
+#[cfg(test)]
+pub mod test_server;
+pub mod time;
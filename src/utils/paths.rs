@@ -0,0 +1,45 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::types::HnResult;
+
+/// Environment variable that overrides the local data directory
+pub const DATA_DIR_ENV: &str = "HN_STORIES_DATA_DIR";
+/// Environment variable that overrides the config file path
+pub const CONFIG_FILE_ENV: &str = "HN_STORIES_CONFIG";
+
+/// Get (and create if missing) the local data directory for stored state
+///
+/// Defaults to the platform data directory (e.g. `~/.local/share/hn-stories`),
+/// and can be overridden with the `HN_STORIES_DATA_DIR` environment variable.
+pub fn data_dir() -> HnResult<PathBuf> {
+    let path: PathBuf = match env::var_os(DATA_DIR_ENV) {
+        Some(path) => PathBuf::from(path),
+        None => dirs::data_dir()
+            .ok_or("Unable to determine the local data directory")?
+            .join("hn-stories"),
+    };
+
+    fs::create_dir_all(&path)?;
+
+    return Ok(path);
+}
+
+/// Get the path of the config file (which may not exist)
+///
+/// Defaults to `config.toml` in the platform config directory (e.g.
+/// `~/.config/hn-stories/config.toml`), and can be overridden with the
+/// `HN_STORIES_CONFIG` environment variable.
+pub fn config_file() -> HnResult<PathBuf> {
+    if let Some(path) = env::var_os(CONFIG_FILE_ENV) {
+        return Ok(PathBuf::from(path));
+    }
+
+    let path: PathBuf = dirs::config_dir()
+        .ok_or("Unable to determine the config directory")?
+        .join("hn-stories")
+        .join("config.toml");
+
+    return Ok(path);
+}
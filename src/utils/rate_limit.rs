@@ -0,0 +1,218 @@
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tokio::time::{Instant, sleep_until};
+
+use crate::utils::logging::HTTP_TARGET;
+
+/// Slowest rate allowed, one request every 100 seconds, so waits stay within a `Duration`
+pub const MIN_REQUESTS_PER_SECOND: f64 = 0.01;
+
+/// Rate limit settings for requests to Hacker News, from the `[rate_limit]` config section
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// Requests per second allowed on average
+    pub requests_per_second: f64,
+    /// Requests that may be sent at once after a quiet period
+    pub burst: u32,
+    /// Times a rate-limited (429 or 503) request is retried
+    pub max_retries: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        return Self {
+            requests_per_second: 20.0,
+            burst: 40,
+            max_retries: 3,
+        };
+    }
+}
+
+/// Counts of requests and of the time they were held back
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ThrottleStats {
+    pub requests: u64,
+    /// Requests that waited for the rate limit or a server's `Retry-After`
+    pub delayed: u64,
+    /// Total time requests waited
+    pub delay: Duration,
+    /// Responses that asked to slow down (429 or 503)
+    pub rate_limited: u64,
+    pub retries: u64,
+}
+
+impl fmt::Display for ThrottleStats {
+    /// Formats the stats for readable output
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(
+            f,
+            "{} requests, {} delayed for {:.1}s in total, {} rate-limited responses, {} retries",
+            self.requests,
+            self.delayed,
+            self.delay.as_secs_f64(),
+            self.rate_limited,
+            self.retries
+        );
+    }
+}
+
+/// Token bucket state
+struct Bucket {
+    /// Tokens available, negative when requests have reserved future tokens
+    tokens: f64,
+    updated: Instant,
+    /// No request may start before this time, after a server asked to slow down
+    paused_until: Option<Instant>,
+}
+
+/// Token-bucket rate limiter shared by every request of a client
+///
+/// Each request takes a token; tokens refill at `requests_per_second` up to `burst`.
+/// Requests without a token reserve the next one and wait for it, so waiting
+/// requests are served in order.
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    bucket: Mutex<Bucket>,
+    stats: Mutex<ThrottleStats>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        return Self {
+            config,
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(config.burst.max(1)),
+                updated: Instant::now(),
+                paused_until: None,
+            }),
+            stats: Mutex::new(ThrottleStats::default()),
+        };
+    }
+
+    pub fn max_retries(&self) -> u32 {
+        return self.config.max_retries;
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(&self) {
+        let now: Instant = Instant::now();
+        let ready_at: Instant = {
+            let mut bucket = self.bucket.lock().unwrap();
+            let rate: f64 = self.config.requests_per_second.max(MIN_REQUESTS_PER_SECOND);
+            let elapsed: f64 = now.duration_since(bucket.updated).as_secs_f64();
+            bucket.tokens =
+                (bucket.tokens + elapsed * rate).min(f64::from(self.config.burst.max(1)));
+            bucket.updated = now;
+            bucket.tokens -= 1.0;
+
+            let token_at: Instant = if bucket.tokens >= 0.0 {
+                now
+            } else {
+                now + Duration::from_secs_f64(-bucket.tokens / rate)
+            };
+            bucket
+                .paused_until
+                .map_or(token_at, |paused_until| paused_until.max(token_at))
+        };
+
+        {
+            let mut stats = self.stats.lock().unwrap();
+            stats.requests += 1;
+            if ready_at > now {
+                stats.delayed += 1;
+                stats.delay += ready_at - now;
+                log::debug!(
+                    target: HTTP_TARGET,
+                    "rate limit wait={}ms",
+                    (ready_at - now).as_millis()
+                );
+            }
+        }
+
+        sleep_until(ready_at).await;
+    }
+
+    /// Hold back every request for a while after a server asked to slow down
+    pub fn pause(&self, delay: Duration) {
+        let until: Instant = Instant::now() + delay;
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.paused_until = Some(
+            bucket
+                .paused_until
+                .map_or(until, |paused| paused.max(until)),
+        );
+    }
+
+    /// Count a response that asked to slow down
+    pub fn record_rate_limited(&self) {
+        self.stats.lock().unwrap().rate_limited += 1;
+    }
+
+    /// Count a request sent again after a rate-limited response
+    pub fn record_retry(&self) {
+        self.stats.lock().unwrap().retries += 1;
+    }
+
+    pub fn stats(&self) -> ThrottleStats {
+        return *self.stats.lock().unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rate_limiter_allows_burst_then_paces() {
+        let limiter: RateLimiter = RateLimiter::new(RateLimitConfig {
+            requests_per_second: 50.0,
+            burst: 3,
+            max_retries: 0,
+        });
+
+        let started: Instant = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert_eq!(limiter.stats().delayed, 0);
+
+        // The next three wait for tokens refilled every 20ms
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert!(started.elapsed() >= Duration::from_millis(55));
+
+        let stats: ThrottleStats = limiter.stats();
+        assert_eq!(stats.requests, 6);
+        assert_eq!(stats.delayed, 3);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_pause() {
+        let limiter: RateLimiter = RateLimiter::new(RateLimitConfig::default());
+
+        limiter.pause(Duration::from_millis(50));
+        limiter.pause(Duration::from_millis(10));
+        limiter.record_rate_limited();
+        limiter.record_rate_limited();
+
+        let started: Instant = Instant::now();
+        limiter.acquire().await;
+        assert!(started.elapsed() >= Duration::from_millis(45));
+
+        let stats: ThrottleStats = limiter.stats();
+        assert_eq!(stats.rate_limited, 2);
+        assert_eq!(stats.delayed, 1);
+        assert_eq!(
+            stats.to_string(),
+            format!(
+                "1 requests, 1 delayed for {:.1}s in total, 2 rate-limited responses, 0 retries",
+                stats.delay.as_secs_f64()
+            )
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+
+/// Start a local HTTP server answering every request with `handler`, returning its base
+/// URL (e.g. `http://127.0.0.1:4000`)
+///
+/// Request bodies are read in full before the handler is called.
+pub async fn spawn<F>(handler: F) -> String
+where
+    F: Fn(Request<Bytes>) -> Response<Full<Bytes>> + Send + Sync + 'static,
+{
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url: String = format!("http://{}", listener.local_addr().unwrap());
+    let handler: Arc<F> = Arc::new(handler);
+
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let handler: Arc<F> = Arc::clone(&handler);
+            tokio::spawn(async move {
+                let service = service_fn(move |request: Request<Incoming>| {
+                    let handler: Arc<F> = Arc::clone(&handler);
+                    async move {
+                        let (parts, body) = request.into_parts();
+                        let body: Bytes = body.collect().await?.to_bytes();
+                        Ok::<_, hyper::Error>(handler(Request::from_parts(parts, body)))
+                    }
+                });
+                let _ = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    });
+
+    return base_url;
+}
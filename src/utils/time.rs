@@ -0,0 +1,258 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::{DateTime, Local, Utc};
+use chrono_tz::Tz;
+use clap::ValueEnum;
+
+/// Source of the current time, injectable so time-dependent output can be tested
+#[cfg_attr(test, mockall::automock)]
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        return Utc::now();
+    }
+}
+
+/// How story times are displayed (relative, absolute)
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum TimeMode {
+    /// Age of the story, e.g. "3 hours ago"
+    #[default]
+    Relative,
+
+    /// Date and time in the display timezone, e.g. "2026-10-19 09:30"
+    Absolute,
+}
+
+/// Timezone absolute times are displayed in
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DisplayTimezone {
+    /// The system's local timezone
+    #[default]
+    Local,
+
+    /// An IANA timezone, e.g. `UTC` or `Europe/Berlin`
+    Named(Tz),
+}
+
+impl FromStr for DisplayTimezone {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.eq_ignore_ascii_case("local") {
+            return Ok(Self::Local);
+        }
+
+        return value
+            .parse::<Tz>()
+            .or_else(|_| value.to_uppercase().parse::<Tz>())
+            .map(Self::Named)
+            .map_err(|_| {
+                format!(
+                    "Unknown timezone '{value}', expected 'local' or a name like 'Europe/Berlin'"
+                )
+            });
+    }
+}
+
+impl fmt::Display for DisplayTimezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Self::Local => write!(f, "local"),
+            Self::Named(timezone) => write!(f, "{}", timezone.name()),
+        };
+    }
+}
+
+/// Formats story times as relative ages or absolute dates
+#[derive(Clone)]
+pub struct TimeFormatter {
+    mode: TimeMode,
+    timezone: DisplayTimezone,
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for TimeFormatter {
+    fn default() -> Self {
+        return Self::new(
+            TimeMode::default(),
+            DisplayTimezone::default(),
+            Arc::new(SystemClock),
+        );
+    }
+}
+
+impl fmt::Debug for TimeFormatter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f
+            .debug_struct("TimeFormatter")
+            .field("mode", &self.mode)
+            .field("timezone", &self.timezone)
+            .finish_non_exhaustive();
+    }
+}
+
+impl TimeFormatter {
+    pub fn new(mode: TimeMode, timezone: DisplayTimezone, clock: Arc<dyn Clock>) -> Self {
+        return Self {
+            mode,
+            timezone,
+            clock,
+        };
+    }
+
+    /// Format a time according to the display mode
+    pub fn format(&self, time: DateTime<Utc>) -> String {
+        return match self.mode {
+            TimeMode::Relative => relative_age(time, self.clock.now()),
+            TimeMode::Absolute => self.format_absolute(time),
+        };
+    }
+
+    /// Format a time compactly for table columns (e.g. "3h", "10-19 09:30")
+    pub fn format_short(&self, time: DateTime<Utc>) -> String {
+        return match self.mode {
+            TimeMode::Relative => short_relative_age(time, self.clock.now()),
+            TimeMode::Absolute => self.format_in_timezone(time, "%m-%d %H:%M"),
+        };
+    }
+
+    /// Format a time as a date and time in the display timezone
+    pub fn format_absolute(&self, time: DateTime<Utc>) -> String {
+        return self.format_in_timezone(time, "%Y-%m-%d %H:%M");
+    }
+
+    /// Format a time in the display timezone with a `strftime` format
+    fn format_in_timezone(&self, time: DateTime<Utc>, format: &str) -> String {
+        return match self.timezone {
+            DisplayTimezone::Local => time.with_timezone(&Local).format(format).to_string(),
+            DisplayTimezone::Named(timezone) => {
+                time.with_timezone(&timezone).format(format).to_string()
+            }
+        };
+    }
+}
+
+/// Describe how long ago a time was, the way Hacker News does (e.g. "3 hours ago")
+pub fn relative_age(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let Some((count, unit)) = age_in_units(time, now) else {
+        return "just now".to_string();
+    };
+    let suffix: &str = if count == 1 { "" } else { "s" };
+
+    return format!("{count} {unit}{suffix} ago");
+}
+
+/// Describe how long ago a time was in a few characters (e.g. "3h", "2mo")
+pub fn short_relative_age(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let Some((count, unit)) = age_in_units(time, now) else {
+        return "now".to_string();
+    };
+    let abbreviation: &str = match unit {
+        "minute" => "m",
+        "hour" => "h",
+        "day" => "d",
+        "month" => "mo",
+        _ => "y",
+    };
+
+    return format!("{count}{abbreviation}");
+}
+
+/// Get an age as a count of its largest whole unit, or `None` under a minute
+fn age_in_units(time: DateTime<Utc>, now: DateTime<Utc>) -> Option<(i64, &'static str)> {
+    let seconds: i64 = (now - time).num_seconds();
+
+    // Allow for small clock differences with the API
+    return match seconds {
+        ..60 => None,
+        60..3_600 => Some((seconds / 60, "minute")),
+        3_600..86_400 => Some((seconds / 3_600, "hour")),
+        86_400..2_592_000 => Some((seconds / 86_400, "day")),
+        2_592_000..31_536_000 => Some((seconds / 2_592_000, "month")),
+        _ => Some((seconds / 31_536_000, "year")),
+    };
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    /// Fixed "now" used by time formatting tests
+    pub fn test_now() -> DateTime<Utc> {
+        return Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap();
+    }
+
+    /// Formatter with a clock fixed at [`test_now`]
+    pub fn test_formatter(mode: TimeMode, timezone: DisplayTimezone) -> TimeFormatter {
+        let mut clock = MockClock::new();
+        clock.expect_now().returning(test_now);
+        return TimeFormatter::new(mode, timezone, Arc::new(clock));
+    }
+
+    #[test]
+    fn test_relative_age() {
+        let now: DateTime<Utc> = test_now();
+        let cases: [(Duration, &str); 10] = [
+            (Duration::seconds(-30), "just now"),
+            (Duration::seconds(59), "just now"),
+            (Duration::minutes(1), "1 minute ago"),
+            (Duration::minutes(59), "59 minutes ago"),
+            (Duration::hours(3), "3 hours ago"),
+            (Duration::hours(25), "1 day ago"),
+            (Duration::days(29), "29 days ago"),
+            (Duration::days(65), "2 months ago"),
+            (Duration::days(365), "1 year ago"),
+            (Duration::days(365 * 7), "7 years ago"),
+        ];
+
+        for (age, expected) in cases {
+            assert_eq!(relative_age(now - age, now), expected, "{age}");
+        }
+    }
+
+    #[test]
+    fn test_short_relative_age() {
+        let now: DateTime<Utc> = test_now();
+
+        assert_eq!(short_relative_age(now, now), "now");
+        assert_eq!(short_relative_age(now - Duration::minutes(12), now), "12m");
+        assert_eq!(short_relative_age(now - Duration::hours(3), now), "3h");
+        assert_eq!(short_relative_age(now - Duration::days(40), now), "1mo");
+        assert_eq!(short_relative_age(now - Duration::days(800), now), "2y");
+    }
+
+    #[test]
+    fn test_time_formatter() {
+        let time: DateTime<Utc> = test_now() - Duration::hours(3);
+
+        let relative: TimeFormatter = test_formatter(TimeMode::Relative, DisplayTimezone::Local);
+        assert_eq!(relative.format(time), "3 hours ago");
+
+        let timezone: DisplayTimezone = "Asia/Kolkata".parse().unwrap();
+        let absolute: TimeFormatter = test_formatter(TimeMode::Absolute, timezone);
+        assert_eq!(absolute.format(time), "2026-10-19 14:30");
+        assert_eq!(absolute.format_short(time), "10-19 14:30");
+        assert_eq!(relative.format_short(time), "3h");
+    }
+
+    #[test]
+    fn test_display_timezone_from_str() {
+        assert_eq!("local".parse(), Ok(DisplayTimezone::Local));
+        assert_eq!("utc".parse(), Ok(DisplayTimezone::Named(Tz::UTC)));
+        assert_eq!(
+            "Europe/Berlin".parse(),
+            Ok(DisplayTimezone::Named(Tz::Europe__Berlin))
+        );
+        assert!("Mars/Olympus".parse::<DisplayTimezone>().is_err());
+    }
+}